@@ -1,7 +1,5 @@
-
 extern crate log;
 extern crate specs;
-extern crate simple_logger;
 extern crate airmash_server;
 
 use std::env;
@@ -10,11 +8,13 @@ use specs::Entity;
 use airmash_server::*;
 use airmash_server::protocol::GameType;
 
-struct EmptyGameMode;
+struct EmptyGameMode {
+    room: String,
+}
 
 impl GameMode for EmptyGameMode {
-    fn assign_team(&mut self, player: Entity) -> Team {
-        Team(player.id() as u16)
+    fn assign_team(&mut self, _: Entity, id: u16) -> Team {
+        Team(id)
     }
     fn spawn_pos(&mut self, _: Entity, _: Team) -> Position {
         Position::default()
@@ -23,17 +23,26 @@ impl GameMode for EmptyGameMode {
         GameType::FFA
     }
     fn room(&self) -> String {
-        "matrix".to_owned()
+        self.room.clone()
     }
 }
 
 fn main() {
-    simple_logger::init_with_level(log::Level::Info).unwrap();
+    let options = ServerOptions::from_env();
+    options.init_logger();
 
     env::set_var("RUST_BACKTRACE", "1");
 
-    AirmashServer::new("0.0.0.0:3501")
+    let server = options
+        .server()
+        .unwrap_or_else(|e| panic!("Unable to start server: {}", e))
         .with_engine()
-        .with_gamemode(EmptyGameMode)
+        .with_gamemode(EmptyGameMode {
+            room: options.room("matrix"),
+        });
+
+    options
+        .apply(server)
+        .unwrap_or_else(|e| panic!("Unable to start server: {}", e))
         .run();
 }
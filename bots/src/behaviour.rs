@@ -0,0 +1,258 @@
+use airmash_protocol::client::{Chat, Command, Key, Pong};
+use airmash_protocol::{ClientPacket, KeyCode};
+
+use rand;
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+
+use config::Config;
+use packets::Incoming;
+
+const CHAT_LINES: &'static [&'static str] = &[
+	"gg",
+	"hi",
+	"lol",
+	"nice shot",
+	"where is everyone?",
+	"beep boop",
+];
+
+/// Only fire when pointed within this
+/// angle (in radians) of the target.
+const FIRE_CONE: f32 = 0.3;
+/// Turn towards the target until within
+/// this angle (in radians) of it.
+const TURN_DEADZONE: f32 = 0.1;
+/// Seconds to wait after dying before respawning.
+const RESPAWN_DELAY: u64 = 3;
+
+#[derive(Copy, Clone, Debug)]
+struct PlayerState {
+	x: f32,
+	y: f32,
+	rot: f32,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Mode {
+	/// Fly around aimlessly, possibly turning
+	Wander(Option<KeyCode>),
+	/// Chase and shoot at a player
+	Hunt(u16),
+}
+
+/// Decides what a single bot does each tick.
+///
+/// Bots have no knowledge of the map, they
+/// wander around randomly and, depending on
+/// their aggression, will chase down and fire
+/// at whichever player is closest.
+pub struct Behaviour {
+	config: Config,
+
+	me: Option<u16>,
+	alive: bool,
+	players: HashMap<u16, PlayerState>,
+
+	mode: Mode,
+	mode_until: Instant,
+	respawn_at: Option<Instant>,
+	last_tick: Instant,
+
+	seq: u32,
+	keys: HashMap<KeyCode, bool>,
+}
+
+fn chance(p: f32) -> bool {
+	rand::random::<f32>() < p
+}
+
+/// Difference between two angles, normalized to [-PI, PI)
+fn angle_diff(a: f32, b: f32) -> f32 {
+	let d = (a - b) % (2.0 * PI);
+	if d >= PI {
+		d - 2.0 * PI
+	} else if d < -PI {
+		d + 2.0 * PI
+	} else {
+		d
+	}
+}
+
+impl Behaviour {
+	pub fn new(config: Config) -> Self {
+		let now = Instant::now();
+
+		Self {
+			config,
+			me: None,
+			alive: false,
+			players: HashMap::default(),
+			mode: Mode::Wander(None),
+			mode_until: now,
+			respawn_at: None,
+			last_tick: now,
+			seq: 0,
+			keys: HashMap::default(),
+		}
+	}
+
+	fn respawn(&self) -> ClientPacket {
+		ClientPacket::Command(Command {
+			com: "respawn".to_owned(),
+			data: u8::from(self.config.plane).to_string(),
+		})
+	}
+
+	/// Press or release a key, only sending a
+	/// packet if the key state actually changed.
+	fn set_key(&mut self, key: KeyCode, state: bool, out: &mut Vec<ClientPacket>) {
+		let prev = self.keys.insert(key, state).unwrap_or(false);
+
+		if prev != state {
+			self.seq += 1;
+			out.push(ClientPacket::Key(Key {
+				seq: self.seq,
+				key,
+				state,
+			}));
+		}
+	}
+
+	fn release_all(&mut self, out: &mut Vec<ClientPacket>) {
+		for key in [KeyCode::Up, KeyCode::Left, KeyCode::Right, KeyCode::Fire].iter() {
+			self.set_key(*key, false, out);
+		}
+	}
+
+	/// React to a packet from the server.
+	pub fn handle(&mut self, evt: Incoming, out: &mut Vec<ClientPacket>) {
+		match evt {
+			Incoming::Login { id } => {
+				self.me = Some(id);
+				self.alive = true;
+				// Switch to the configured plane
+				out.push(self.respawn());
+			}
+			Incoming::Ping { num } => out.push(ClientPacket::Pong(Pong { num })),
+			Incoming::Update { id, x, y, rot } => {
+				self.players.insert(id, PlayerState { x, y, rot });
+
+				if Some(id) == self.me && !self.alive {
+					// Updates for ourselves only come
+					// in once we've respawned
+					self.alive = true;
+				}
+			}
+			Incoming::Kill { id } => {
+				self.players.remove(&id);
+
+				if Some(id) == self.me {
+					self.alive = false;
+					self.respawn_at = Some(Instant::now() + Duration::from_secs(RESPAWN_DELAY));
+					self.release_all(out);
+				}
+			}
+			Incoming::Leave { id } => {
+				self.players.remove(&id);
+			}
+		}
+	}
+
+	fn closest_player(&self, me: &PlayerState) -> Option<u16> {
+		let dist2 = |p: &PlayerState| (p.x - me.x).powi(2) + (p.y - me.y).powi(2);
+
+		self.players
+			.iter()
+			.filter(|&(id, _)| Some(*id) != self.me)
+			.min_by(|a, b| dist2(a.1).partial_cmp(&dist2(b.1)).unwrap())
+			.map(|(id, _)| *id)
+	}
+
+	fn pick_mode(&mut self, now: Instant, me: &PlayerState) {
+		let target = if chance(self.config.aggression) {
+			self.closest_player(me)
+		} else {
+			None
+		};
+
+		self.mode = match target {
+			Some(id) => Mode::Hunt(id),
+			None => Mode::Wander(match rand::random::<u8>() % 3 {
+				0 => Some(KeyCode::Left),
+				1 => Some(KeyCode::Right),
+				_ => None,
+			}),
+		};
+
+		let millis = 500 + (rand::random::<u32>() % 2500) as u64;
+		self.mode_until = now + Duration::from_millis(millis);
+	}
+
+	/// Update held keys and occasionally chat.
+	pub fn tick(&mut self, now: Instant, out: &mut Vec<ClientPacket>) {
+		let elapsed = now - self.last_tick;
+		let secs = elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 * 1e-9;
+		self.last_tick = now;
+
+		if chance(self.config.chattiness * secs) {
+			let line = CHAT_LINES[rand::random::<usize>() % CHAT_LINES.len()];
+			out.push(ClientPacket::Chat(Chat {
+				text: line.to_owned(),
+			}));
+		}
+
+		if let Some(at) = self.respawn_at {
+			if now >= at {
+				self.respawn_at = None;
+				out.push(self.respawn());
+			}
+			return;
+		}
+
+		let me = match self.me.and_then(|id| self.players.get(&id).cloned()) {
+			Some(me) => me,
+			None => return,
+		};
+		if !self.alive {
+			return;
+		}
+
+		if now >= self.mode_until {
+			self.pick_mode(now, &me);
+		}
+
+		let (turn, fire) = match self.mode {
+			Mode::Wander(turn) => (turn, false),
+			Mode::Hunt(id) => match self.players.get(&id) {
+				Some(target) => {
+					// Rotation 0 points up, and increases clockwise
+					let angle = (target.x - me.x).atan2(me.y - target.y);
+					let diff = angle_diff(angle, me.rot);
+
+					let turn = if diff > TURN_DEADZONE {
+						Some(KeyCode::Right)
+					} else if diff < -TURN_DEADZONE {
+						Some(KeyCode::Left)
+					} else {
+						None
+					};
+
+					(turn, diff.abs() < FIRE_CONE)
+				}
+				// Target is gone, pick something else
+				None => {
+					self.mode_until = now;
+					(None, false)
+				}
+			},
+		};
+
+		self.set_key(KeyCode::Up, true, out);
+		self.set_key(KeyCode::Left, turn == Some(KeyCode::Left), out);
+		self.set_key(KeyCode::Right, turn == Some(KeyCode::Right), out);
+		self.set_key(KeyCode::Fire, fire, out);
+	}
+}
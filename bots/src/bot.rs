@@ -0,0 +1,111 @@
+use airmash_protocol::client::Login;
+use airmash_protocol::{to_bytes, ClientPacket};
+
+use websocket::sync::Client;
+use websocket::{ClientBuilder, OwnedMessage, WebSocketError};
+
+use std::net::TcpStream;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use behaviour::Behaviour;
+use config::Config;
+use packets::{decode, Incoming};
+
+/// How often bots update their inputs.
+const TICK_MILLIS: u64 = 50;
+
+/// A single simulated player.
+pub struct Bot {
+	name: String,
+	config: Config,
+}
+
+impl Bot {
+	pub fn new(name: String, config: Config) -> Self {
+		Self { name, config }
+	}
+
+	fn connect(&self) -> Result<Client<TcpStream>, WebSocketError> {
+		ClientBuilder::new(&self.config.url)?.connect_insecure()
+	}
+
+	/// Connect to the server and play until
+	/// the connection is closed.
+	pub fn run(self) -> Result<(), WebSocketError> {
+		let client = self.connect()?;
+		let (mut reader, mut writer) = client.split()?;
+
+		let (send, recv) = channel();
+
+		// Read on a separate thread so that the
+		// bot can keep acting while there is
+		// nothing coming from the server.
+		thread::spawn(move || {
+			for msg in reader.incoming_messages() {
+				let data = match msg {
+					Ok(OwnedMessage::Binary(data)) => data,
+					Ok(OwnedMessage::Close(_)) | Err(_) => break,
+					Ok(_) => continue,
+				};
+
+				if let Some(evt) = decode(&data) {
+					if send.send(evt).is_err() {
+						break;
+					}
+				}
+			}
+		});
+
+		let mut out = vec![ClientPacket::Login(Login {
+			protocol: 5,
+			name: self.name.clone(),
+			session: "none".to_owned(),
+			horizon_x: 3000,
+			horizon_y: 3000,
+			flag: self.config.flag.clone(),
+		})];
+
+		let mut behaviour = Behaviour::new(self.config.clone());
+
+		loop {
+			if !Self::poll(&recv, &mut behaviour, &mut out) {
+				info!("{} was disconnected", self.name);
+				return Ok(());
+			}
+
+			behaviour.tick(Instant::now(), &mut out);
+
+			for packet in out.drain(..) {
+				let data = match to_bytes(&packet) {
+					Ok(data) => data,
+					Err(e) => {
+						warn!("Failed to serialize {:?}: {:?}", packet, e);
+						continue;
+					}
+				};
+
+				writer.send_message(&OwnedMessage::Binary(data))?;
+			}
+
+			thread::sleep(Duration::from_millis(TICK_MILLIS));
+		}
+	}
+
+	/// Handle all events received since the last tick.
+	/// Returns false once the connection has closed.
+	fn poll(
+		recv: &Receiver<Incoming>,
+		behaviour: &mut Behaviour,
+		out: &mut Vec<ClientPacket>,
+	) -> bool {
+		loop {
+			match recv.try_recv() {
+				Ok(evt) => behaviour.handle(evt, out),
+				Err(TryRecvError::Empty) => return true,
+				Err(TryRecvError::Disconnected) => return false,
+			}
+		}
+	}
+}
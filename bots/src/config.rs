@@ -0,0 +1,121 @@
+use airmash_protocol::PlaneType;
+
+use std::env;
+use std::process;
+use std::time::Duration;
+
+/// Settings shared by all bots in a run.
+#[derive(Clone, Debug)]
+pub struct Config {
+	/// Websocket URL of the server
+	pub url: String,
+	/// Number of bots to spawn
+	pub count: usize,
+	/// Plane that each bot will respawn as
+	pub plane: PlaneType,
+	/// How eager the bots are to chase and shoot
+	/// at other players. 0 will never attack,
+	/// 1 will always attack.
+	pub aggression: f32,
+	/// Chance per second that a bot says something
+	pub chattiness: f32,
+	/// Prefix for bot names, the bot index is
+	/// appended to this.
+	pub name: String,
+	pub flag: String,
+	/// Delay between successive bot connections,
+	/// so that the server isn't hit with all
+	/// logins within the same frame.
+	pub stagger: Duration,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			url: "ws://127.0.0.1:3501".to_owned(),
+			count: 10,
+			plane: PlaneType::Predator,
+			aggression: 0.5,
+			chattiness: 0.01,
+			name: "bot".to_owned(),
+			flag: "UN".to_owned(),
+			stagger: Duration::from_millis(50),
+		}
+	}
+}
+
+const USAGE: &'static str = "\
+Usage: bots [OPTIONS]
+
+Options:
+    --url <URL>            Server to connect to [default: ws://127.0.0.1:3501]
+    --count <N>            Number of bots [default: 10]
+    --plane <PLANE>        predator, goliath, mohawk, tornado or prowler [default: predator]
+    --aggression <0-1>     How often bots chase and shoot players [default: 0.5]
+    --chattiness <0-1>     Chance per second of a bot chatting [default: 0.01]
+    --name <PREFIX>        Bot name prefix [default: bot]
+    --flag <CODE>          Bot flag [default: UN]
+    --stagger <MS>         Milliseconds between bot logins [default: 50]
+    --help                 Print this message
+";
+
+fn parse_plane(s: &str) -> Option<PlaneType> {
+	match &*s.to_lowercase() {
+		"predator" => Some(PlaneType::Predator),
+		"goliath" => Some(PlaneType::Goliath),
+		"mohawk" => Some(PlaneType::Mohawk),
+		"tornado" => Some(PlaneType::Tornado),
+		"prowler" => Some(PlaneType::Prowler),
+		_ => s.parse().ok().and_then(PlaneType::try_from),
+	}
+}
+
+fn unit(s: &str) -> Option<f32> {
+	match s.parse::<f32>() {
+		Ok(x) if x >= 0.0 && x <= 1.0 => Some(x),
+		_ => None,
+	}
+}
+
+fn fail(msg: &str) -> ! {
+	eprintln!("error: {}\n\n{}", msg, USAGE);
+	process::exit(2);
+}
+
+impl Config {
+	/// Parse the config from the command line arguments,
+	/// exiting with a usage message if they are invalid.
+	pub fn from_args() -> Self {
+		let mut config = Self::default();
+		let mut args = env::args().skip(1);
+
+		while let Some(arg) = args.next() {
+			if arg == "--help" || arg == "-h" {
+				print!("{}", USAGE);
+				process::exit(0);
+			}
+
+			let val = match args.next() {
+				Some(val) => val,
+				None => fail(&format!("missing value for {}", arg)),
+			};
+			let invalid = || fail(&format!("invalid value for {}: {}", arg, val));
+
+			match &*arg {
+				"--url" => config.url = val.clone(),
+				"--count" => config.count = val.parse().unwrap_or_else(|_| invalid()),
+				"--plane" => config.plane = parse_plane(&val).unwrap_or_else(|| invalid()),
+				"--aggression" => config.aggression = unit(&val).unwrap_or_else(|| invalid()),
+				"--chattiness" => config.chattiness = unit(&val).unwrap_or_else(|| invalid()),
+				"--name" => config.name = val.clone(),
+				"--flag" => config.flag = val.clone(),
+				"--stagger" => {
+					config.stagger = Duration::from_millis(val.parse().unwrap_or_else(|_| invalid()))
+				}
+				_ => fail(&format!("unknown option {}", arg)),
+			}
+		}
+
+		config
+	}
+}
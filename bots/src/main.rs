@@ -0,0 +1,58 @@
+//! Headless clients for load testing a server.
+//!
+//! Each bot connects over its own websocket, logs
+//! in, and then flies around according to a simple
+//! behaviour controlled by the command line options.
+//! Run with `--help` to see them.
+
+#[macro_use]
+extern crate log;
+
+extern crate airmash_protocol;
+extern crate rand;
+extern crate simple_logger;
+extern crate websocket;
+
+mod behaviour;
+mod bot;
+mod config;
+mod packets;
+
+use std::env;
+use std::thread;
+
+use bot::Bot;
+use config::Config;
+
+fn main() {
+	env::set_var("RUST_BACKTRACE", "1");
+
+	simple_logger::init_with_level(log::Level::Info).unwrap();
+
+	let config = Config::from_args();
+
+	info!("Spawning {} bots against {}", config.count, config.url);
+
+	let handles = (0..config.count)
+		.map(|i| {
+			let bot = Bot::new(format!("{}{}", config.name, i), config.clone());
+
+			let handle = thread::Builder::new()
+				.name(format!("bot-{}", i))
+				.spawn(move || {
+					if let Err(e) = bot.run() {
+						error!("Bot {} failed: {}", i, e);
+					}
+				})
+				.unwrap();
+
+			thread::sleep(config.stagger);
+			handle
+		})
+		.collect::<Vec<_>>();
+
+	for handle in handles {
+		// Panics are reported by the bot's own thread
+		let _ = handle.join();
+	}
+}
@@ -0,0 +1,43 @@
+//! Decoding of the server packets that bots care about.
+//!
+//! Bots use the protocol without specs, so
+//! entities within packets are just their ids.
+
+use airmash_protocol::{from_bytes, ServerPacket};
+
+#[derive(Copy, Clone, Debug)]
+pub enum Incoming {
+	/// Our own id, assigned by the server
+	Login { id: u16 },
+	/// Must be answered with a Pong
+	Ping { num: u32 },
+	/// Position and rotation of a player
+	Update { id: u16, x: f32, y: f32, rot: f32 },
+	/// A player died
+	Kill { id: u16 },
+	/// A player left the game
+	Leave { id: u16 },
+}
+
+/// Decode a packet, returning `None` for packets
+/// that bots don't use or that are malformed.
+pub fn decode(b: &[u8]) -> Option<Incoming> {
+	let packet = match from_bytes::<ServerPacket>(b) {
+		Ok(packet) => packet,
+		Err(_) => return None,
+	};
+
+	match packet {
+		ServerPacket::Login(p) => Some(Incoming::Login { id: p.id.0 }),
+		ServerPacket::Ping(p) => Some(Incoming::Ping { num: p.num }),
+		ServerPacket::PlayerUpdate(p) => Some(Incoming::Update {
+			id: p.id.0,
+			x: p.pos.x.inner(),
+			y: p.pos.y.inner(),
+			rot: p.rot.inner(),
+		}),
+		ServerPacket::PlayerKill(p) => Some(Incoming::Kill { id: p.id.0 }),
+		ServerPacket::PlayerLeave(p) => Some(Incoming::Leave { id: p.id.0 }),
+		_ => None,
+	}
+}
@@ -31,7 +31,9 @@ pub struct FlagEvent {
 #[storage(HashMapStorage)]
 pub struct LastDrop {
 	pub player: Option<Entity>,
-	pub time: Instant,
+	/// `None` if the flag hasn't been dropped
+	/// since the game started.
+	pub time: Option<Instant>,
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -14,20 +14,29 @@ lazy_static! {
 	static ref BLUE_TEAM_RESPAWN: Position = Position::default();
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct CTFGameMode {
 	pub redteam: u16,
 	pub blueteam: u16,
+	pub room: String,
 }
 
 impl CTFGameMode {
 	pub fn new() -> Self {
-		Self::default()
+		Self::with_room("matrix".to_owned())
+	}
+
+	pub fn with_room(room: String) -> Self {
+		Self {
+			redteam: 0,
+			blueteam: 0,
+			room,
+		}
 	}
 }
 
 impl GameMode for CTFGameMode {
-	fn assign_team(&mut self, _: Entity) -> Team {
+	fn assign_team(&mut self, _: Entity, _: u16) -> Team {
 		info!("Teams: {} blue, {} red", self.blueteam, self.redteam);
 		match self.redteam.cmp(&self.blueteam) {
 			Ordering::Less => {
@@ -72,6 +81,6 @@ impl GameMode for CTFGameMode {
 	}
 
 	fn room(&self) -> String {
-		"matrix".to_owned()
+		self.room.clone()
 	}
 }
@@ -13,7 +13,6 @@ extern crate htmlescape;
 extern crate rand;
 extern crate shred;
 extern crate shrev;
-extern crate simple_logger;
 extern crate specs;
 
 use airmash_server as server;
@@ -23,21 +22,30 @@ mod config;
 mod gamemode;
 mod systems;
 
+#[cfg(test)]
+mod tests;
+
 use std::env;
 
 use gamemode::{CTFGameMode, BLUE_TEAM, RED_TEAM};
-use server::AirmashServer;
+use server::ServerOptions;
 
 fn main() {
 	env::set_var("RUST_BACKTRACE", "1");
 
-	simple_logger::init_with_level(log::Level::Info).unwrap();
+	let options = ServerOptions::from_env();
+	options.init_logger();
 
-	let mut server = AirmashServer::new("0.0.0.0:3501")
+	let mut server = options
+		.server()
+		.unwrap_or_else(|e| panic!("Unable to start server: {}", e))
 		.with_engine()
-		.with_gamemode(CTFGameMode::new());
+		.with_gamemode(CTFGameMode::with_room(options.room("matrix")));
 
 	server.builder = systems::register(&mut server.world, server.builder);
 
-	server.run();
+	options
+		.apply(server)
+		.unwrap_or_else(|e| panic!("Unable to start server: {}", e))
+		.run();
 }
@@ -4,7 +4,7 @@ use specs::*;
 use server::component::channel::{OnCommand, OnCommandReader};
 use server::component::time::ThisFrame;
 use server::protocol::server::{GameFlag, ServerPacket};
-use server::protocol::{to_bytes, FlagUpdateType};
+use server::protocol::FlagUpdateType;
 
 use component::*;
 
@@ -16,7 +16,7 @@ pub struct DropSystem {
 pub struct DropSystemData<'a> {
 	pub channel: Read<'a, OnCommand>,
 	pub conns: Read<'a, Connections>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 
 	pub entities: Entities<'a>,
 	pub pos: WriteStorage<'a, Position>,
@@ -102,12 +102,10 @@ impl<'a> System<'a> for DropSystem {
 					*carrier = FlagCarrier(None);
 					*lastdrop = LastDrop {
 						player: Some(player),
-						time: thisframe.0,
+						time: Some(thisframe.0),
 					};
 
-					conns.send_to_all(OwnedMessage::Binary(
-						to_bytes(&ServerPacket::GameFlag(packet)).unwrap(),
-					));
+					conns.send_to_all(ServerPacket::GameFlag(packet));
 				});
 		}
 	}
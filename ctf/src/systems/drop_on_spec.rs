@@ -19,7 +19,7 @@ pub struct DropOnSpecData<'a> {
 	pub commands: Read<'a, OnCommand>,
 	pub conns: Read<'a, Connections>,
 	pub entities: Entities<'a>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 
 	pub team: ReadStorage<'a, Team>,
 	pub pos: ReadStorage<'a, Position>,
@@ -7,7 +7,7 @@ use config as ctfconfig;
 use htmlescape;
 
 use server::protocol::server::{ServerMessage, ServerPacket};
-use server::protocol::{to_bytes, ServerMessageType};
+use server::protocol::ServerMessageType;
 
 pub struct PickupMessageSystem {
 	reader: Option<OnFlagReader>,
@@ -62,9 +62,7 @@ impl<'a> System<'a> for PickupMessageSystem {
 				text: msg,
 			};
 
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::ServerMessage(packet)).unwrap(),
-			));
+			data.conns.send_to_all(ServerPacket::ServerMessage(packet));
 		}
 	}
 }
@@ -1,7 +1,8 @@
 use specs::*;
 
 use server::component::channel::*;
-use server::component::event::PlayerLeave;
+use server::component::event::{PlayerLeave, PlayerLeaveTeam};
+use server::component::flag::IsPureSpectator;
 use server::systems::handlers::packet::OnCloseHandler;
 use server::*;
 
@@ -11,14 +12,29 @@ use RED_TEAM;
 
 pub struct UpdateGameModeOnPlayerLeave {
 	reader: Option<OnPlayerLeaveReader>,
+	team_reader: Option<OnPlayerLeaveTeamReader>,
 }
 
 #[derive(SystemData)]
 pub struct UpdateGameModeOnPlayerLeaveData<'a> {
 	pub gamemode: GameModeWriter<'a, CTFGameMode>,
 	pub channel: Read<'a, OnPlayerLeave>,
+	pub team_channel: Read<'a, OnPlayerLeaveTeam>,
 
 	pub teams: ReadStorage<'a, Team>,
+	pub pure_spec: ReadStorage<'a, IsPureSpectator>,
+}
+
+impl<'a> UpdateGameModeOnPlayerLeaveData<'a> {
+	fn remove_from(&mut self, team: Team) {
+		if team == RED_TEAM {
+			self.gamemode.redteam -= 1;
+		} else if team == BLUE_TEAM {
+			self.gamemode.blueteam -= 1;
+		} else {
+			unimplemented!();
+		}
+	}
 }
 
 impl<'a> System<'a> for UpdateGameModeOnPlayerLeave {
@@ -28,19 +44,27 @@ impl<'a> System<'a> for UpdateGameModeOnPlayerLeave {
 		Self::SystemData::setup(res);
 
 		self.reader = Some(res.fetch_mut::<OnPlayerLeave>().register_reader());
+		self.team_reader = Some(res.fetch_mut::<OnPlayerLeaveTeam>().register_reader());
 	}
 
 	fn run(&mut self, mut data: Self::SystemData) {
+		let mut left = vec![];
+
 		for PlayerLeave(ent) in data.channel.read(self.reader.as_mut().unwrap()) {
-			let team = data.teams.get(*ent).unwrap();
-
-			if *team == RED_TEAM {
-				data.gamemode.redteam -= 1;
-			} else if *team == BLUE_TEAM {
-				data.gamemode.blueteam -= 1;
-			} else {
-				unimplemented!();
+			// Pure spectators were never counted
+			if data.pure_spec.get(*ent).is_some() {
+				continue;
 			}
+
+			left.push(*data.teams.get(*ent).unwrap());
+		}
+
+		for evt in data.team_channel.read(self.team_reader.as_mut().unwrap()) {
+			left.push(evt.team);
+		}
+
+		for team in left {
+			data.remove_from(team);
 		}
 	}
 }
@@ -53,6 +77,9 @@ impl SystemInfo for UpdateGameModeOnPlayerLeave {
 	}
 
 	fn new() -> Self {
-		Self { reader: None }
+		Self {
+			reader: None,
+			team_reader: None,
+		}
 	}
 }
@@ -4,7 +4,7 @@ use specs::*;
 use server::component::channel::*;
 use server::component::time::ThisFrame;
 use server::protocol::server::GameFlag;
-use server::protocol::{to_bytes, FlagUpdateType, ServerPacket};
+use server::protocol::{FlagUpdateType, ServerPacket};
 
 use component::*;
 
@@ -22,7 +22,7 @@ pub struct LeaveUpdateSystemData<'a> {
 	pub carrier: WriteStorage<'a, FlagCarrier>,
 	pub teams: ReadStorage<'a, Team>,
 	pub lastdrop: WriteStorage<'a, LastDrop>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 }
 
 impl LeaveUpdateSystem {
@@ -80,12 +80,10 @@ impl<'a> System<'a> for LeaveUpdateSystem {
 						// picking the flag up again if the pickup update
 						// runs after this system
 						player: Some(ent),
-						time: thisframe.0,
+						time: Some(thisframe.0),
 					};
 
-					conns.send_to_all(OwnedMessage::Binary(
-						to_bytes(&ServerPacket::GameFlag(packet)).unwrap(),
-					));
+					conns.send_to_all(ServerPacket::GameFlag(packet));
 				});
 		}
 	}
@@ -2,7 +2,7 @@ use specs::*;
 
 use server::component::channel::*;
 use server::protocol::server::{GameFlag, ServerPacket};
-use server::protocol::{to_bytes, FlagUpdateType};
+use server::protocol::FlagUpdateType;
 use server::*;
 
 use component::*;
@@ -54,15 +54,13 @@ impl<'a> System<'a> for LoginUpdateSystem {
 						ty,
 						flag: *team,
 						pos: *pos,
-						id: carrier.0,
+						id: carrier.0.map(|ent| data.conns.entity_ids().wire(ent)),
 						blueteam: 0,
 						redteam: 0,
 					};
 
-					data.conns.send_to_player(
-						evt.0,
-						OwnedMessage::Binary(to_bytes(&ServerPacket::GameFlag(packet)).unwrap()),
-					);
+					data.conns
+						.send_to_player(evt.0, ServerPacket::GameFlag(packet));
 				});
 		}
 	}
@@ -17,7 +17,7 @@ pub struct PickupFlagSystemData<'a> {
 	pub config: Read<'a, Config>,
 	pub entities: Entities<'a>,
 	pub channel: Write<'a, OnFlag>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 
 	// Player data
 	pub plane: ReadStorage<'a, Plane>,
@@ -68,7 +68,10 @@ impl<'a> System<'a> for PickupFlagSystem {
 				.filter(|(_, _, p_team, _, _)| f_team != **p_team)
 				.filter(|(ent, _, _, _, _)| {
 					// Check against time-since-drop
-					(data.thisframe.0 - lastdrop.time) > *ctfconfig::FLAG_NO_REGRAB_TIME
+					lastdrop
+						.time
+						.map(|t| (data.thisframe.0 - t) > *ctfconfig::FLAG_NO_REGRAB_TIME)
+						.unwrap_or(true)
 						// Then check against contained player id
 						|| lastdrop.player.map(|x| x != *ent).unwrap_or(false)
 				})
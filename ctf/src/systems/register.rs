@@ -4,8 +4,6 @@ use server::{Builder, Position, Team};
 use specs::*;
 use specs::Builder as SpecsBuilder;
 
-use std::time::Instant;
-
 use super::*;
 use config;
 
@@ -18,7 +16,7 @@ pub fn register<'a, 'b>(world: &mut World, disp: Builder<'a, 'b>) -> Builder<'a,
 
 	let lastdrop = LastDrop {
 		player: None,
-		time: Instant::now(),
+		time: None,
 	};
 
 	let blue = world
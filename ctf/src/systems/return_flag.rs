@@ -6,7 +6,7 @@ use config as ctfconfig;
 use component::*;
 
 use server::protocol::server::{GameFlag, ServerPacket};
-use server::protocol::{to_bytes, FlagUpdateType};
+use server::protocol::FlagUpdateType;
 
 pub struct ReturnFlagSystem;
 
@@ -56,9 +56,7 @@ impl<'a> System<'a> for ReturnFlagSystem {
 					redteam: 0,
 				};
 
-				conns.send_to_all(OwnedMessage::Binary(
-					to_bytes(&ServerPacket::GameFlag(packet)).unwrap(),
-				));
+				conns.send_to_all(ServerPacket::GameFlag(packet));
 
 				channel.single_write(FlagEvent {
 					ty: FlagEventType::Capture,
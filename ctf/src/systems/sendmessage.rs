@@ -2,9 +2,10 @@ use server::*;
 use specs::*;
 
 use component::*;
+use server::component::counter::TeamScores;
 use server::protocol::server::GameFlag;
 use server::protocol::FlagUpdateType;
-use server::protocol::{to_bytes, ServerPacket};
+use server::protocol::ServerPacket;
 
 use RED_TEAM;
 use BLUE_TEAM;
@@ -24,6 +25,7 @@ pub struct SendFlagMessageSystemData<'a> {
 	pub conns: Read<'a, Connections>,
 	pub channel: Read<'a, OnFlag>,
 	pub scores: Write<'a, GameScores>,
+	pub teamscores: Write<'a, TeamScores>,
 	pub flags: ReadExpect<'a, Flags>,
 
 	pub team: ReadStorage<'a, Team>,
@@ -38,6 +40,10 @@ impl<'a> System<'a> for SendFlagMessageSystem {
 		Self::SystemData::setup(res);
 
 		self.reader = Some(res.fetch_mut::<OnFlag>().register_reader());
+
+		let mut teamscores = res.fetch_mut::<TeamScores>();
+		teamscores.0.insert(RED_TEAM, 0);
+		teamscores.0.insert(BLUE_TEAM, 0);
 	}
 
 	fn run(&mut self, mut data: Self::SystemData) {
@@ -71,32 +77,30 @@ impl<'a> System<'a> for SendFlagMessageSystem {
 					pos = Position::default();
 				}
 
-				data.conns.send_to_all(OwnedMessage::Binary(
-					to_bytes(&ServerPacket::GameFlag(GameFlag {
-						ty,
-						flag: *data.team.get(other).unwrap(),
-						pos: pos,
-						id: None,
-						blueteam: data.scores.blueteam,
-						redteam: data.scores.redteam,
-					})).unwrap()
-				));
-
-				info!("{:?}", *data.scores);
-			}
-
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::GameFlag(GameFlag {
+				data.conns.send_to_all(ServerPacket::GameFlag(GameFlag {
 					ty,
-					flag: *team,
-					pos: *data.pos.get(evt.flag).unwrap(),
-					id: evt.player,
+					flag: *data.team.get(other).unwrap(),
+					pos: pos,
+					id: None,
 					blueteam: data.scores.blueteam,
 					redteam: data.scores.redteam,
-				})).unwrap(),
-			));
+				}));
+
+				info!("{:?}", *data.scores);
+
+				let scores = *data.scores;
+				data.teamscores.0.insert(RED_TEAM, scores.redteam as u32);
+				data.teamscores.0.insert(BLUE_TEAM, scores.blueteam as u32);
+			}
 
-			
+			data.conns.send_to_all(ServerPacket::GameFlag(GameFlag {
+				ty,
+				flag: *team,
+				pos: *data.pos.get(evt.flag).unwrap(),
+				id: evt.player.map(|ent| data.conns.entity_ids().wire(ent)),
+				blueteam: data.scores.blueteam,
+				redteam: data.scores.redteam,
+			}));
 		}
 	}
 }
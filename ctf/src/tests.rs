@@ -0,0 +1,166 @@
+use server::protocol::client::Command;
+use server::protocol::{ClientPacket, FlagUpdateType, ServerPacket};
+use server::testing::{FakeClient, TestServer};
+use server::*;
+use specs::*;
+
+use component::{FlagCarrier, Flags, GameScores};
+use config::{FLAG_POS, FLAG_RETURN_POS};
+use gamemode::{CTFGameMode, BLUE_TEAM, RED_TEAM};
+use systems;
+
+fn new_server() -> TestServer {
+	TestServer::new(|server| {
+		let mut server = server.with_engine().with_gamemode(CTFGameMode::new());
+		server.builder = systems::register(&mut server.world, server.builder);
+		server
+	})
+}
+
+fn login(server: &mut TestServer, name: &str) -> (FakeClient, Entity) {
+	let client = server.connect();
+	server.step();
+	client.login(name);
+	server.step();
+
+	let player = server.player(&client).unwrap();
+	(client, player)
+}
+
+fn teleport(server: &mut TestServer, player: Entity, pos: Position) {
+	server
+		.world
+		.write_storage::<Position>()
+		.insert(player, pos)
+		.unwrap();
+}
+
+fn carrier(server: &TestServer, flag: Entity) -> Option<Entity> {
+	server
+		.world
+		.read_storage::<FlagCarrier>()
+		.get(flag)
+		.unwrap()
+		.0
+}
+
+#[test]
+fn capture_flag() {
+	let mut server = new_server();
+	let (client, player) = login(&mut server, "capper");
+
+	let team = *server.world.read_storage::<Team>().get(player).unwrap();
+	let flags = *server.world.read_resource::<Flags>();
+	let (flag, flag_team) = if team == RED_TEAM {
+		(flags.blue, BLUE_TEAM)
+	} else {
+		(flags.red, RED_TEAM)
+	};
+
+	// Flags that were never dropped can be
+	// grabbed as soon as the game starts.
+	server.received(&client);
+
+	teleport(&mut server, player, FLAG_POS[&flag_team]);
+	server.step_n(2);
+
+	assert_eq!(carrier(&server, flag), Some(player));
+	let picked_up = server.received(&client).into_iter().any(|p| match p {
+		ServerPacket::GameFlag(p) => {
+			p.ty == FlagUpdateType::Carrier
+				&& p.flag == flag_team
+				&& p.id == server.entity_id(player)
+		}
+		_ => false,
+	});
+	assert!(picked_up, "No carrier update was sent");
+
+	teleport(&mut server, player, FLAG_RETURN_POS[&flag_team]);
+	server.step_n(3);
+
+	assert_eq!(carrier(&server, flag), None);
+
+	let scores = *server.world.read_resource::<GameScores>();
+	let (ours, theirs) = if team == RED_TEAM {
+		(scores.redteam, scores.blueteam)
+	} else {
+		(scores.blueteam, scores.redteam)
+	};
+	assert_eq!((ours, theirs), (1, 0));
+
+	let announced = server.received(&client).into_iter().any(|p| match p {
+		ServerPacket::GameFlag(p) => p.blueteam + p.redteam == 1,
+		_ => false,
+	});
+	assert!(announced, "Capture was not sent to the client");
+}
+
+fn team_count(server: &mut TestServer) -> u16 {
+	server
+		.world
+		.exec(|mode: GameModeWriter<CTFGameMode>| mode.redteam + mode.blueteam)
+}
+
+#[test]
+fn spectator_logins_are_never_counted() {
+	let mut server = new_server();
+	let (_client, _) = login(&mut server, "player");
+
+	let spec = server.connect();
+	server.step();
+	spec.login_spectator("spectator");
+	server.step_n(2);
+	assert_eq!(team_count(&mut server), 1);
+
+	let player = server.player(&spec).unwrap();
+	let team = *server.world.read_storage::<Team>().get(player).unwrap();
+	assert!(team != RED_TEAM && team != BLUE_TEAM);
+
+	spec.close();
+	server.step();
+	assert_eq!(team_count(&mut server), 1);
+}
+
+#[test]
+fn spectators_are_not_on_a_team() {
+	let mut server = new_server();
+	let (_client, _) = login(&mut server, "player");
+	let (spec, _) = login(&mut server, "spectator");
+	assert_eq!(team_count(&mut server), 2);
+
+	// Force spectate keeps the player on their team
+	spec.send(&ClientPacket::Command(Command {
+		com: "spectate".to_owned(),
+		data: "-3".to_owned(),
+	}));
+	server.step();
+	assert_eq!(team_count(&mut server), 2);
+
+	spec.send(&ClientPacket::Command(Command {
+		com: "spectator".to_owned(),
+		data: "".to_owned(),
+	}));
+	server.step();
+	assert_eq!(team_count(&mut server), 1);
+
+	// Respawning joins a team again
+	spec.send(&ClientPacket::Command(Command {
+		com: "respawn".to_owned(),
+		data: "1".to_owned(),
+	}));
+	server.step();
+	assert_eq!(team_count(&mut server), 2);
+
+	spec.send(&ClientPacket::Command(Command {
+		com: "spectator".to_owned(),
+		data: "".to_owned(),
+	}));
+	server.step();
+	assert_eq!(team_count(&mut server), 1);
+
+	// Leaving as a spectator doesn't
+	// change the counts either
+	spec.close();
+	server.step();
+	assert_eq!(team_count(&mut server), 1);
+}
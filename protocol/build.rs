@@ -159,19 +159,19 @@ mod flags {
 mod protocol {
 	use std::env;
 	use std::fs::File;
-	use std::io::{BufWriter, Read};
+	use std::io::{BufWriter, Read, Write};
 	use std::path::Path;
 
 	use specgen::*;
 
 	const PRELUDE: &'static str = "
 	#![allow(unused_imports)]
-	use specs::*;
-	use protocol::serde_am::{Serializer, Deserializer, Serialize, Deserialize};
-	use protocol::error::{SerError, DeError};
-	use protocol::include::*;
-	use protocol::datatypes::*;
+	use serde_am::{Serializer, Deserializer, Serialize, Deserialize};
+	use error::{SerError, DeError};
+	use include::*;
+	use datatypes::*;
 	use types::{
+		Entity,
 		Position,
 		Rotation,
 		Speed,
@@ -197,37 +197,34 @@ mod protocol {
 	fn serde_map(s: &FieldType) -> &'static str {
 		match s {
 			FieldType::Simple(name) => match name {
-				&"Text" => return "::protocol::field::text",
-				&"TextBig" => return "::protocol::field::textbig",
-				&"Entity" => return "::protocol::field::entity",
-				&"Score" => return "::protocol::field::score",
-				&"Level" => return "::protocol::field::level",
-				&"Team" => return "::protocol::field::team",
-				&"Position" => return "::protocol::field::pos",
-				&"Position24" => return "::protocol::field::pos24",
-				&"Position_f32" => return "::protocol::field::pos_f32",
-				&"Speed" => return "::protocol::field::speed",
-				&"Velocity" => return "::protocol::field::vel_u",
-				&"Accel" => return "::protocol::field::accel",
-				&"LowResPos" => return "::protocol::field::lowrespos",
-				&"Health" => return "::protocol::field::health",
-				&"Energy" => return "::protocol::field::energy",
-				&"HealthRegen" => return "::protocol::field::health_regen",
-				&"EnergyRegen" => return "::protocol::field::energy_regen",
-				&"Rotation" => return "::protocol::field::rotation",
-				&"Flag" => return "::protocol::field::flag",
+				&"Text" => return "::field::text",
+				&"TextBig" => return "::field::textbig",
+				&"Entity" => return "::field::entity",
+				&"Score" => return "::field::score",
+				&"Level" => return "::field::level",
+				&"Team" => return "::field::team",
+				&"Position" => return "::field::pos",
+				&"Position24" => return "::field::pos24",
+				&"Position_f32" => return "::field::pos_f32",
+				&"Speed" => return "::field::speed",
+				&"Velocity" => return "::field::vel_u",
+				&"Accel" => return "::field::accel",
+				&"LowResPos" => return "::field::lowrespos",
+				&"Health" => return "::field::health",
+				&"Energy" => return "::field::energy",
+				&"HealthRegen" => return "::field::health_regen",
+				&"EnergyRegen" => return "::field::energy_regen",
+				&"Rotation" => return "::field::rotation",
+				&"Flag" => return "::field::flag",
 				_ => (),
 			},
 			FieldType::Compound(name, rest) => match name {
-				&"Array" => return "::protocol::field::array",
-				&"ArraySmall" => return "::protocol::field::arraysmall",
+				&"Array" => return "::field::array",
+				&"ArraySmall" => return "::field::arraysmall",
+				// specgen checks that Option has exactly one argument
 				&"Option" => {
-					if rest.len() != 1 {
-						panic!("Found Option type with multiple or zero arguments!");
-					}
-
 					if rest[0].to_str() == "Entity" {
-						return "::protocol::field::option_entity";
+						return "::field::option_entity";
 					}
 				}
 				_ => (),
@@ -260,29 +257,63 @@ mod protocol {
 		serde_map(s).to_owned() + "::deserialize"
 	}
 
-	pub fn write() {
-		let path = Path::new(&env::var("OUT_DIR").unwrap()).join("protocol-spec.rs");
-		let mut file = BufWriter::new(File::create(&path).unwrap());
-
+	fn read_spec() -> Vec<u8> {
 		let mut bytes = vec![];
 		File::open("airmash.prtcl")
 			.unwrap()
 			.read_to_end(&mut bytes)
 			.unwrap();
+		bytes
+	}
+
+	pub fn write() {
+		let path = Path::new(&env::var("OUT_DIR").unwrap()).join("protocol-spec.rs");
+		let mut file = BufWriter::new(File::create(&path).unwrap());
+		let bytes = read_spec();
 
-		SerdeBuilder::new()
+		let result = SerdeBuilder::new()
 			.map_name(name_map)
 			.map_type(type_map)
 			.type_ser(ser_map)
 			.type_de(de_map)
 			.prelude(PRELUDE)
-			.def_attr("derive(Component)")
-			.build(&bytes, &mut file)
-			.unwrap();
+			.def_attr("cfg_attr(feature = \"serde\", derive(Serialize, Deserialize))")
+			.build(&bytes, &mut file);
+
+		if let Err(e) = result {
+			panic!("airmash.prtcl is invalid:\n{}", e);
+		}
+	}
+
+	/// Renders the spec as Markdown and attaches
+	/// it to an empty `reference` module so that
+	/// it shows up within rustdoc.
+	pub fn write_docs() {
+		let path = Path::new(&env::var("OUT_DIR").unwrap()).join("protocol-docs.rs");
+		let mut file = BufWriter::new(File::create(&path).unwrap());
+		let mut docs = vec![];
+
+		let result = MarkdownBuilder::new()
+			.title("AIRMASH Protocol Reference")
+			.build(&read_spec(), &mut docs);
+
+		if let Err(e) = result {
+			panic!("airmash.prtcl is invalid:\n{}", e);
+		}
+
+		for line in String::from_utf8(docs).unwrap().lines() {
+			if line.is_empty() {
+				writeln!(file, "///").unwrap();
+			} else {
+				writeln!(file, "/// {}", line).unwrap();
+			}
+		}
+		writeln!(file, "pub mod reference {{}}").unwrap();
 	}
 }
 
 fn main() {
 	flags::write();
 	protocol::write();
+	protocol::write_docs();
 }
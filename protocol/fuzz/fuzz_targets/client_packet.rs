@@ -0,0 +1,17 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate airmash_protocol;
+
+use airmash_protocol::{from_bytes, to_bytes, ClientPacket};
+
+// These are the bytes that the server reads
+// straight off of player websockets.
+fuzz_target!(|data: &[u8]| {
+	if let Ok(packet) = from_bytes::<ClientPacket>(data) {
+		// Anything that decodes must be able to be
+		// sent again (e.g. by a replay or a proxy)
+		let bytes = to_bytes(&packet).unwrap();
+		from_bytes::<ClientPacket>(&bytes).unwrap();
+	}
+});
@@ -0,0 +1,15 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate airmash_protocol;
+
+use airmash_protocol::{from_bytes, to_bytes, ServerPacket};
+
+fuzz_target!(|data: &[u8]| {
+	if let Ok(packet) = from_bytes::<ServerPacket>(data) {
+		let bytes = to_bytes(&packet).unwrap();
+		let decoded = from_bytes::<ServerPacket>(&bytes).unwrap();
+
+		assert_eq!(packet.variant_id(), decoded.variant_id());
+	}
+});
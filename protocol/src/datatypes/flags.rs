@@ -1,4 +1,4 @@
-use protocol::include::FlagCode;
+use include::FlagCode;
 
 const COUNTRY_CODES: [Option<&'static str>; 126] = [
 	None,
@@ -2,6 +2,9 @@ mod flags;
 mod serverkeystate;
 mod upgrades;
 
+#[cfg(feature = "specs")]
+mod specs_impls;
+
 pub use self::flags::*;
 pub use self::serverkeystate::*;
 pub use self::upgrades::*;
@@ -1,7 +1,7 @@
-use protocol::serde_am::*;
+use serde_am::*;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Hash)]
-#[cfg_attr(features = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ServerKeyState {
 	pub up: bool,
 	pub down: bool,
@@ -1,6 +1,6 @@
-
 use specs::*;
-use protocol::datatypes::*;
+
+use include::*;
 
 impl Component for FlagCode {
 	type Storage = DenseVecStorage<FlagCode>;
@@ -1,7 +1,7 @@
-use protocol::serde_am::*;
+use serde_am::*;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Hash)]
-#[cfg_attr(features = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Upgrades {
 	pub speed: u8,
 	pub shield: bool,
@@ -10,7 +10,9 @@ pub struct Upgrades {
 
 impl Serialize for Upgrades {
 	fn serialize(&self, ser: &mut Serializer) -> Result<(), SerError> {
-		assert!(self.speed < 8);
+		if self.speed > 7 {
+			return Err(SerError::InvalidUpgradeSpeed(self.speed));
+		}
 
 		let val = 0 | (self.speed & 7) | (self.shield as u8) << 3 | (self.inferno as u8) << 4;
 
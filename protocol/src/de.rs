@@ -1,8 +1,8 @@
 use std::mem;
 use std::str;
 
-use protocol::error::DeError;
-use protocol::serde_am::Deserialize;
+use error::DeError;
+use serde_am::Deserialize;
 
 pub struct Deserializer<'a> {
 	pub bytes: &'a [u8],
@@ -56,7 +56,13 @@ pub fn from_bytes<'a, T>(b: &'a [u8]) -> Result<T, DeError>
 where
 	T: Deserialize<'a>,
 {
-	let mut deserializer = Deserializer::from_bytes(b);
+	deserialize_all(Deserializer::from_bytes(b))
+}
+
+fn deserialize_all<'a, T>(mut deserializer: Deserializer<'a>) -> Result<T, DeError>
+where
+	T: Deserialize<'a>,
+{
 	let t = T::deserialize(&mut deserializer)?;
 
 	if deserializer.bytes.is_empty() {
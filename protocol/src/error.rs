@@ -7,6 +7,10 @@ use std::str::Utf8Error;
 pub enum SerError {
 	Utf8Error(Utf8Error),
 	ArrayLengthTooBig,
+	/// Flag teams are sent as a `u8`.
+	InvalidFlagTeam(u16),
+	/// Upgrade speed only gets 3 bits.
+	InvalidUpgradeSpeed(u8),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -21,7 +25,6 @@ pub enum DeError {
 	InvalidMobType(u8),
 	InvalidPlayerStatus(u8),
 	InvalidKeyCode(u8),
-	EntityMayNotBeDeserialized,
 	InvalidEnumValue(&'static str, u64),
 }
 
@@ -32,6 +35,8 @@ impl SerError {
 			&SerError::ArrayLengthTooBig => {
 				"Array too large to be serialized, maybe textbig or array types should be used."
 			}
+			&SerError::InvalidFlagTeam(_) => "Flag team is too large to fit in a u8.",
+			&SerError::InvalidUpgradeSpeed(_) => "Upgrade speed must be less than 8.",
 		}
 	}
 }
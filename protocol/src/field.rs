@@ -1,10 +1,10 @@
-use protocol::error::SerError as Error;
-use protocol::serde_am::*;
+use error::SerError as Error;
+use serde_am::*;
 
 pub type SerResult = Result<(), SerError>;
 
 pub mod textbig {
-	use protocol::field::*;
+	use field::*;
 
 	pub fn serialize(val: &str, ser: &mut Serializer) -> SerResult {
 		let bytes = val.as_bytes();
@@ -23,7 +23,7 @@ pub mod textbig {
 }
 
 pub mod text {
-	use protocol::field::*;
+	use field::*;
 
 	pub fn serialize(val: &str, ser: &mut Serializer) -> SerResult {
 		let bytes = val.as_bytes();
@@ -42,7 +42,7 @@ pub mod text {
 }
 
 pub mod array {
-	use protocol::field::*;
+	use field::*;
 	use std::vec::Vec;
 
 	pub fn serialize<T>(arr: &Vec<T>, ser: &mut Serializer) -> SerResult
@@ -77,7 +77,7 @@ pub mod array {
 }
 
 pub mod arraysmall {
-	use protocol::field::*;
+	use field::*;
 
 	pub fn serialize<T>(arr: &[T], ser: &mut Serializer) -> SerResult
 	where
@@ -111,7 +111,7 @@ pub mod arraysmall {
 }
 
 pub mod rotation {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	const MULT: f32 = 6553.6;
@@ -125,7 +125,7 @@ pub mod rotation {
 }
 
 pub mod healthnergy {
-	use protocol::field::*;
+	use field::*;
 
 	const MULT: f32 = 255.0;
 
@@ -138,7 +138,7 @@ pub mod healthnergy {
 }
 
 pub mod uint24 {
-	use protocol::field::*;
+	use field::*;
 
 	pub fn serialize(val: u32, ser: &mut Serializer) -> SerResult {
 		ser.serialize_u16((val >> 8) as u16)?;
@@ -148,12 +148,12 @@ pub mod uint24 {
 		let hi = de.deserialize_u16()?;
 		let lo = de.deserialize_u8()?;
 
-		Ok(((hi << 8) as u32) | (lo as u32))
+		Ok(((hi as u32) << 8) | (lo as u32))
 	}
 }
 
 pub mod coord24 {
-	use protocol::field::*;
+	use field::*;
 
 	// Note: This assumes that f32 has enough precision,
 	//       the client uses f64 as it is written in js
@@ -170,7 +170,7 @@ pub mod coord24 {
 }
 
 pub mod accel {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	// Note: This assumes that f32 has enough precision,
@@ -192,7 +192,7 @@ pub mod accel {
 }
 
 pub mod velocity {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	// Note: This assumes that f32 has enough precision,
@@ -214,7 +214,7 @@ pub mod velocity {
 }
 
 pub mod speed {
-	use protocol::field::*;
+	use field::*;
 	use types::Speed;
 
 	// Note: This assumes that f32 has enough precision,
@@ -236,7 +236,7 @@ pub mod speed {
 macro_rules! shift_mult_decode {
 	($name:ident, $shift:expr, $mult:expr) => {
 		pub mod $name {
-			use protocol::field::*;
+			use field::*;
 
 			// Note: This assumes that f32 has enough precision,
 			//       the client uses f64 as it is written in js
@@ -259,40 +259,39 @@ shift_mult_decode!(coordx, 32768, 2.0);
 shift_mult_decode!(regen, 32768, 1.0e6);
 
 pub mod entity {
-	use protocol::field::*;
-	use specs::Entity;
+	use field::*;
+	use types::Entity;
 
 	pub fn serialize(val: &Entity, ser: &mut Serializer) -> SerResult {
-		assert!(val.id() < 0xFFFF);
-		ser.serialize_u16(val.id() as u16)
+		ser.serialize_u16(val.0)
 	}
-	pub fn deserialize<'de>(_: &mut Deserializer<'de>) -> Result<Entity, DeError> {
-		unimplemented!();
+	pub fn deserialize<'de>(de: &mut Deserializer<'de>) -> Result<Entity, DeError> {
+		Ok(Entity(de.deserialize_u16()?))
 	}
 }
 
 pub mod option_entity {
-	use protocol::field::*;
-	use specs::Entity;
+	use field::*;
+	use types::Entity;
 
 	pub fn serialize(val: &Option<Entity>, ser: &mut Serializer) -> SerResult {
 		match val {
-			Some(val) => {
-				assert!(val.id() < 0xFFFF);
-				ser.serialize_u16(val.id() as u16)
-			}
+			Some(val) => entity::serialize(val, ser),
 			None => ser.serialize_u16(0),
 		}
 	}
-	pub fn deserialize<'de>(_: &mut Deserializer<'de>) -> Result<Option<Entity>, DeError> {
-		Err(DeError::EntityMayNotBeDeserialized)
+	pub fn deserialize<'de>(de: &mut Deserializer<'de>) -> Result<Option<Entity>, DeError> {
+		match de.deserialize_u16()? {
+			0 => Ok(None),
+			id => Ok(Some(Entity(id))),
+		}
 	}
 }
 
 macro_rules! serde_inner {
 	($name:ident, $type:ident) => {
 		pub mod $name {
-			use protocol::field::*;
+			use field::*;
 			use types::*;
 
 			pub fn deserialize_inner<'de, T>(de: &mut Deserializer<'de>) -> Result<T, DeError>
@@ -317,7 +316,7 @@ serde_inner!(level, Level);
 serde_inner!(team, Team);
 
 pub mod pos {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &Position, ser: &mut Serializer) -> SerResult {
@@ -333,7 +332,7 @@ pub mod pos {
 }
 
 pub mod pos24 {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &Position, ser: &mut Serializer) -> SerResult {
@@ -349,7 +348,7 @@ pub mod pos24 {
 }
 
 pub mod pos_f32 {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &Position, ser: &mut Serializer) -> SerResult {
@@ -365,7 +364,7 @@ pub mod pos_f32 {
 }
 
 pub mod vel_u {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &Velocity, ser: &mut Serializer) -> SerResult {
@@ -381,12 +380,12 @@ pub mod vel_u {
 }
 
 pub mod lowrespos {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(pos: &Position, ser: &mut Serializer) -> SerResult {
 		ser.serialize_u8(((pos.x.inner() / 128.0) as i32 + 128) as u8)?;
-		ser.serialize_u8(((pos.y.inner() / 128.0) as i32 + 128) as u8)
+		ser.serialize_u8(((pos.y.inner() / 64.0) as i32 + 128) as u8)
 	}
 	pub fn deserialize<'de>(de: &mut Deserializer<'de>) -> Result<Position, DeError> {
 		Ok(Position::new(
@@ -397,7 +396,7 @@ pub mod lowrespos {
 }
 
 pub mod health {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &Health, ser: &mut Serializer) -> SerResult {
@@ -409,7 +408,7 @@ pub mod health {
 }
 
 pub mod energy {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &Energy, ser: &mut Serializer) -> SerResult {
@@ -421,7 +420,7 @@ pub mod energy {
 }
 
 pub mod health_regen {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &HealthRegen, ser: &mut Serializer) -> SerResult {
@@ -433,7 +432,7 @@ pub mod health_regen {
 }
 
 pub mod energy_regen {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &EnergyRegen, ser: &mut Serializer) -> SerResult {
@@ -445,11 +444,13 @@ pub mod energy_regen {
 }
 
 pub mod flag {
-	use protocol::field::*;
+	use field::*;
 	use types::*;
 
 	pub fn serialize(val: &Team, ser: &mut Serializer) -> SerResult {
-		assert!(val.0 < 0xFF);
+		if val.0 > 0xFF {
+			return Err(Error::InvalidFlagTeam(val.0));
+		}
 
 		ser.serialize_u8(val.0 as u8)
 	}
@@ -1,6 +1,5 @@
-use protocol::error::{DeError, SerError};
-use protocol::serde_am::*;
-use specs::*;
+use error::{DeError, SerError};
+use serde_am::*;
 
 type Array<T> = Vec<T>;
 type ArraySmall<T> = Vec<T>;
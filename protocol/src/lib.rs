@@ -22,10 +22,17 @@
 //! type and message fields for you as well as
 //! encoding them. If an invalid message is passed
 //! then deserialize will return an error indicating
-//! what went wrong. (Note: it will currently panic
-//! in some cases. See TODOs). If an error occurse while
+//! what went wrong. If an error occurs while
 //! serializing, an error will be returned too.
-//! (Note: will panic currently. See TODOs).
+//! Neither will panic on bad input, this is
+//! checked by the fuzz targets in `fuzz/`.
+//!
+//! # Wire Format
+//! The [`reference`](reference/index.html) module
+//! describes the layout of every packet along with
+//! the values of all enums. It is generated from
+//! `airmash.prtcl` each build so it can't go out
+//! of date.
 //!
 //! # Client-Side
 //! Clients using this library will be deserializing
@@ -49,6 +56,13 @@
 //! the feature `"serde"`. Note that serde is not
 //! required for regular use of the library.
 //!
+//! # Use with Specs
+//! With the feature `"specs"` the unit and enum
+//! types can be used as [specs](https://docs.rs/specs)
+//! components. Entities within packets are always
+//! the `u16` id that is sent, servers need to map
+//! their own entities to and from these ids.
+//!
 
 // # TODOs
 // There is still a bunch of things that can be
@@ -60,13 +74,24 @@
 // - Complete documentation of BTR
 //   [`ServerCustom`](server/struct.servercustom.html)
 //   data format.
-// - Write unit tests for all serialization and deserialization
-//   groups within [`field.rs`](../src/airmash_protocol/field.rs.html).
 // - More internal documentation on specific protocol data types.
 //   This should probably go within
 //   [`field.rs`](../src/airmash_protocol/field.rs.html) too.
 //
 
+#![feature(optin_builtin_traits)]
+
+#[macro_use]
+extern crate dimensioned;
+extern crate phf;
+
+#[cfg(feature = "specs")]
+extern crate specs;
+
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
 mod serde_am;
 
 mod de;
@@ -74,7 +99,10 @@ mod error;
 mod field;
 mod ser;
 
-mod codes;
+/// Raw packet codes. These allow clients to
+/// dispatch on a packet without fully
+/// deserializing it.
+pub mod codes;
 mod impls;
 mod include;
 mod packet;
@@ -82,16 +110,22 @@ mod packet_impls;
 
 mod datatypes;
 
+pub mod types;
+
+// The `reference` module, documented with
+// the contents of airmash.prtcl.
+include!(concat!(env!("OUT_DIR"), "/protocol-docs.rs"));
+
 pub mod server {
-	pub use protocol::include::server::*;
-	pub use protocol::packet::ServerPacket;
+	pub use include::server::*;
+	pub use packet::ServerPacket;
 }
 pub mod client {
-	pub use protocol::include::client::*;
-	pub use protocol::packet::ClientPacket;
+	pub use include::client::*;
+	pub use packet::ClientPacket;
 }
 
-pub use protocol::include::{
+pub use include::{
 	CommandReplyType, ErrorType, FirewallStatus, FirewallUpdateType, FlagCode, FlagUpdateType,
 	GameType, KeyCode, LeaveHorizonType, MobType, PlaneType, PlayerLevelType, PlayerStatus,
 	PowerupType, ServerCustomType, ServerMessageType, UpgradeType,
@@ -106,3 +140,7 @@ pub use self::client::ClientPacket;
 pub use self::server::ServerPacket;
 
 pub use self::datatypes::*;
+pub use self::types::{
+	Accel, Distance, Energy, EnergyRegen, Entity, Health, HealthRegen, Level, Position, Rotation,
+	Score, Speed, Team, Velocity,
+};
@@ -1,8 +1,8 @@
-use protocol::client::*;
-use protocol::server::*;
+use client::*;
+use server::*;
 
-use protocol::client::Login as ClientLogin;
-use protocol::server::Login as ServerLogin;
+use client::Login as ClientLogin;
+use server::Login as ServerLogin;
 
 /// All possible server packets.
 ///
@@ -70,7 +70,7 @@ impl ServerPacket {
 	/// Gets the id of the packet associated
 	/// with the current packet type.
 	pub fn variant_id(&self) -> u8 {
-		use protocol::codes::server::*;
+		use codes::server::*;
 
 		match self {
 			&ServerPacket::Login(_) => LOGIN,
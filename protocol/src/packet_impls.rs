@@ -1,8 +1,8 @@
-use protocol::client::ClientPacket;
-use protocol::server::ServerPacket;
+use client::ClientPacket;
+use server::ServerPacket;
 
-use protocol::error;
-use protocol::serde_am::*;
+use error;
+use serde_am::*;
 
 fn ser_w_code<T>(code: u8, v: &T, ser: &mut Serializer) -> Result<(), SerError>
 where
@@ -14,7 +14,7 @@ where
 
 impl Serialize for ClientPacket {
 	fn serialize(&self, ser: &mut Serializer) -> Result<(), SerError> {
-		use protocol::codes::client::*;
+		use codes::client::*;
 
 		match self {
 			&ClientPacket::Login(ref p) => ser_w_code(LOGIN, p, ser),
@@ -36,8 +36,8 @@ impl Serialize for ClientPacket {
 }
 impl<'de> Deserialize<'de> for ClientPacket {
 	fn deserialize(de: &mut Deserializer<'de>) -> Result<ClientPacket, DeError> {
-		use protocol::client::*;
-		use protocol::codes::client::*;
+		use client::*;
+		use codes::client::*;
 
 		Ok(match de.deserialize_u8()? {
 			LOGIN => ClientPacket::Login(Login::deserialize(de)?),
@@ -61,7 +61,7 @@ impl<'de> Deserialize<'de> for ClientPacket {
 
 impl Serialize for ServerPacket {
 	fn serialize(&self, ser: &mut Serializer) -> Result<(), SerError> {
-		use protocol::codes::server::*;
+		use codes::server::*;
 
 		match self {
 			&ServerPacket::Login(ref p) => ser_w_code(LOGIN, p, ser),
@@ -115,9 +115,9 @@ impl Serialize for ServerPacket {
 }
 impl<'de> Deserialize<'de> for ServerPacket {
 	fn deserialize(de: &mut Deserializer<'de>) -> Result<ServerPacket, DeError> {
-		use protocol::codes::server::*;
-		use protocol::server;
-		use protocol::server::*;
+		use codes::server::*;
+		use server;
+		use server::*;
 
 		Ok(match de.deserialize_u8()? {
 			LOGIN => ServerPacket::Login(Login::deserialize(de)?),
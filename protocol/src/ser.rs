@@ -1,4 +1,4 @@
-use protocol::serde_am::{SerError, Serialize};
+use serde_am::{SerError, Serialize};
 
 use std::mem;
 use std::result;
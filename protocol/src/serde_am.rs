@@ -1,6 +1,6 @@
-pub use protocol::de::Deserializer;
-pub use protocol::error::{DeError, SerError};
-pub use protocol::ser::Serializer;
+pub use de::Deserializer;
+pub use error::{DeError, SerError};
+pub use ser::Serializer;
 
 pub trait Serialize {
 	fn serialize(&self, ser: &mut Serializer) -> Result<(), SerError>;
@@ -0,0 +1,12 @@
+//! Entities are the ids of players, mobs
+//! and missiles.
+//!
+//! These are the ids that are sent over the
+//! wire. Servers that keep their own entities
+//! decide which id each of them is sent as.
+
+/// The id of an entity as it was
+/// sent over the wire.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Entity(pub u16);
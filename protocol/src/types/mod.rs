@@ -0,0 +1,10 @@
+//! Units and other types used within
+//! packet fields.
+
+mod entity;
+mod units;
+mod vector2;
+
+pub use self::entity::Entity;
+pub use self::units::*;
+pub use self::vector2::*;
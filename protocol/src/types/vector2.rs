@@ -1,7 +1,8 @@
 use std::ops::*;
 
 use dimensioned::Sqrt;
-use specs::*;
+#[cfg(feature = "specs")]
+use specs::{Component, VecStorage};
 
 /// Required trait to allow specialized impls for self
 /// TODO: Use specialization instead?
@@ -13,6 +14,7 @@ pub auto trait NotVec {}
 impl<T> !NotVec for Vector2<T> {}
 
 #[derive(Default, Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vector2<T> {
 	pub x: T,
 	pub y: T,
@@ -173,6 +175,7 @@ where
 	}
 }
 
+#[cfg(feature = "specs")]
 impl<T: 'static + Send + Sync> Component for Vector2<T> {
 	type Storage = VecStorage<Vector2<T>>;
 }
@@ -0,0 +1,101 @@
+//! Check that the packet codes in `codes.rs`
+//! match the ones declared in `airmash.prtcl`.
+//!
+//! The packet structs are generated from the
+//! spec but the codes and the packet enums are
+//! written by hand, so nothing else keeps them
+//! from drifting apart.
+
+extern crate airmash_protocol;
+extern crate specgen;
+
+use airmash_protocol::codes::{client, server};
+use airmash_protocol::*;
+
+const SPEC: &'static [u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/airmash.prtcl"));
+
+macro_rules! codes {
+	($module:ident: $( $name:ident ),* $(,)*) => {
+		vec![ $( (stringify!($name), $module::$name) ),* ]
+	}
+}
+
+/// `PLAYER_NEW` and `PlayerNew` both become `playernew`.
+fn normalize(name: &str) -> String {
+	name.replace("_", "").to_lowercase()
+}
+
+/// All packets within `spec` as `(name, code)`, sorted by code.
+fn spec_codes(spec: &str) -> Vec<(String, u8)> {
+	let protocol = specgen::parse(SPEC).unwrap();
+	let spec = protocol
+		.specs
+		.iter()
+		.find(|s| s.name == spec)
+		.expect("spec is missing from airmash.prtcl");
+
+	let mut codes = spec
+		.defs
+		.iter()
+		.filter_map(|d| d.code.map(|c| (normalize(d.name), c as u8)))
+		.collect::<Vec<_>>();
+	codes.sort_by_key(|&(_, c)| c);
+	codes
+}
+
+fn normalized(codes: Vec<(&str, u8)>) -> Vec<(String, u8)> {
+	let mut codes = codes
+		.into_iter()
+		.map(|(n, c)| (normalize(n), c))
+		.collect::<Vec<_>>();
+	codes.sort_by_key(|&(_, c)| c);
+	codes
+}
+
+#[test]
+fn server_codes_match_spec() {
+	let codes = codes!(server:
+		LOGIN, BACKUP, PING, PING_RESULT, ACK, ERROR, COMMAND_REPLY,
+		PLAYER_NEW, PLAYER_LEAVE, PLAYER_UPDATE, PLAYER_FIRE, PLAYER_HIT,
+		PLAYER_RESPAWN, PLAYER_FLAG, PLAYER_KILL, PLAYER_UPGRADE, PLAYER_TYPE,
+		PLAYER_POWERUP, PLAYER_LEVEL, PLAYER_RETEAM,
+		GAME_FLAG, GAME_SPECTATE, GAME_PLAYERSALIVE, GAME_FIREWALL,
+		EVENT_REPEL, EVENT_BOOST, EVENT_BOUNCE, EVENT_STEALTH, EVENT_LEAVEHORIZON,
+		MOB_UPDATE, MOB_UPDATE_STATIONARY, MOB_DESPAWN, MOB_DESPAWN_COORDS,
+		CHAT_PUBLIC, CHAT_TEAM, CHAT_SAY, CHAT_WHISPER, CHAT_VOTEMUTEPASSED,
+		CHAT_VOTEMUTED, SCORE_UPDATE, SCORE_BOARD, SCORE_DETAILED_FFA,
+		SCORE_DETAILED_CTF, SCORE_DETAILED_BTR, SERVER_MESSAGE, SERVER_CUSTOM,
+	);
+
+	assert_eq!(normalized(codes), spec_codes("server"));
+}
+
+#[test]
+fn client_codes_match_spec() {
+	let codes = codes!(client:
+		LOGIN, BACKUP, HORIZON, ACK, PONG, KEY, COMMAND, SCORE_DETAILED,
+		CHAT, WHISPER, SAY, TEAMCHAT, VOTEMUTE, LOCALPING,
+	);
+
+	assert_eq!(normalized(codes), spec_codes("client"));
+}
+
+#[test]
+fn every_server_code_is_decoded() {
+	for (name, code) in spec_codes("server") {
+		match from_bytes::<ServerPacket>(&[code]) {
+			Err(DeError::InvalidPacketType) => panic!("{} ({}) is not decoded", name, code),
+			Ok(packet) => assert_eq!(packet.variant_id(), code, "{}", name),
+			Err(_) => (),
+		}
+	}
+}
+
+#[test]
+fn every_client_code_is_decoded() {
+	for (name, code) in spec_codes("client") {
+		if let Err(DeError::InvalidPacketType) = from_bytes::<ClientPacket>(&[code]) {
+			panic!("{} ({}) is not decoded", name, code);
+		}
+	}
+}
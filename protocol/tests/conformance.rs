@@ -0,0 +1,1264 @@
+//! Byte-for-byte fixtures for every packet.
+//!
+//! Each fixture was worked out by hand from
+//! `airmash.prtcl` and the codecs in `field.rs`.
+//! If one of these fails then what goes over
+//! the wire has changed, which will break
+//! compatibility with existing clients.
+
+extern crate airmash_protocol;
+
+use airmash_protocol::client::*;
+use airmash_protocol::server::*;
+use airmash_protocol::types::AccelScalar;
+use airmash_protocol::*;
+
+use airmash_protocol::client::Login as ClientLogin;
+use airmash_protocol::server::Login as ServerLogin;
+
+fn pos(x: f32, y: f32) -> Position {
+	Position::new(Distance::new(x), Distance::new(y))
+}
+
+fn vel(x: f32, y: f32) -> Velocity {
+	Velocity::new(Speed::new(x), Speed::new(y))
+}
+
+fn accel(x: f32, y: f32) -> Accel {
+	Accel::new(AccelScalar::new(x), AccelScalar::new(y))
+}
+
+fn upgrades(speed: u8, shield: bool, inferno: bool) -> Upgrades {
+	Upgrades {
+		speed,
+		shield,
+		inferno,
+	}
+}
+
+/// Check that `packet` encodes to exactly
+/// `bytes` and that decoding `bytes` gives
+/// back the same packet.
+fn server(packet: ServerPacket, bytes: &[u8]) -> ServerPacket {
+	assert_eq!(to_bytes(&packet).unwrap(), bytes);
+	assert_eq!(packet.variant_id(), bytes[0]);
+
+	let decoded = from_bytes::<ServerPacket>(bytes).unwrap();
+	assert_eq!(decoded.variant_id(), bytes[0]);
+	assert_eq!(to_bytes(&decoded).unwrap(), bytes);
+
+	decoded
+}
+
+fn client(packet: ClientPacket, bytes: &[u8]) -> ClientPacket {
+	assert_eq!(to_bytes(&packet).unwrap(), bytes);
+
+	let decoded = from_bytes::<ClientPacket>(bytes).unwrap();
+	assert_eq!(to_bytes(&decoded).unwrap(), bytes);
+
+	decoded
+}
+
+// Server packets
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_login() {
+	let packet = ServerLogin {
+		success: true,
+		id: Entity(1),
+		team: Team(1),
+		clock: 0x01020304,
+		token: "tk".to_owned(),
+		ty: GameType::FFA,
+		room: "ab".to_owned(),
+		players: vec![LoginPlayer {
+			id: Entity(1),
+			status: PlayerStatus::Alive,
+			level: Level(2),
+			name: "x".to_owned(),
+			ty: PlaneType::Predator,
+			team: Team(1),
+			pos: pos(1000.0, -500.0),
+			rot: Rotation::new(1.5),
+			flag: FlagCode::UnitedNations,
+			upgrades: upgrades(1, false, true),
+		}],
+	};
+
+	server(
+		ServerPacket::Login(packet),
+		&[
+			0x00,
+			0x01,                   // success
+			0x01, 0x00,             // id
+			0x01, 0x00,             // team
+			0x04, 0x03, 0x02, 0x01, // clock
+			0x02, 0x74, 0x6B,       // token: "tk"
+			0x01,                   // type: FFA
+			0x02, 0x61, 0x62,       // room: "ab"
+			0x01, 0x00,             // players: u16 length
+			0x01, 0x00,             //   id
+			0x00,                   //   status: Alive
+			0x02,                   //   level
+			0x01, 0x78,             //   name: "x"
+			0x01,                   //   type: Predator
+			0x01, 0x00,             //   team
+			0xD0, 0x87, 0x30, 0x78, //   pos: (1000, -500)
+			0x66, 0x26,             //   rot: 1.5
+			0x0A, 0x00,             //   flag: UnitedNations
+			0x11,                   //   upgrades
+		],
+	);
+}
+
+#[test]
+fn server_backup() {
+	server(ServerPacket::Backup, &[0x01]);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_ping() {
+	let packet = Ping {
+		clock: 0x01020304,
+		num: 7,
+	};
+
+	server(
+		ServerPacket::Ping(packet),
+		&[0x05, 0x04, 0x03, 0x02, 0x01, 0x07, 0x00, 0x00, 0x00],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_ping_result() {
+	let packet = PingResult {
+		ping: 100,
+		players_total: 20,
+		players_game: 10,
+	};
+
+	server(
+		ServerPacket::PingResult(packet),
+		&[0x06, 0x64, 0x00, 0x14, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00],
+	);
+}
+
+#[test]
+fn server_ack() {
+	server(ServerPacket::Ack, &[0x07]);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_player_new() {
+	let packet = PlayerNew {
+		id: Entity(2),
+		status: PlayerStatus::Dead,
+		name: "p".to_owned(),
+		ty: PlaneType::Goliath,
+		team: Team(3),
+		pos: pos(-1000.0, 500.0),
+		rot: Rotation::new(3.0),
+		flag: FlagCode::UnitedStates,
+		upgrades: upgrades(0, false, false),
+	};
+
+	server(
+		ServerPacket::PlayerNew(packet),
+		&[
+			0x0A,
+			0x02, 0x00,             // id
+			0x01,                   // status: Dead
+			0x01, 0x70,             // name: "p"
+			0x02,                   // type: Goliath
+			0x03, 0x00,             // team
+			0x30, 0x78, 0xD0, 0x87, // pos: (-1000, 500)
+			0xCC, 0x4C,             // rot: 3.0
+			0x0B, 0x00,             // flag: UnitedStates
+			0x00,                   // upgrades
+		],
+	);
+}
+
+#[test]
+fn server_player_leave() {
+	let packet = PlayerLeave { id: Entity(513) };
+
+	server(ServerPacket::PlayerLeave(packet), &[0x0B, 0x01, 0x02]);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_player_update() {
+	let packet = PlayerUpdate {
+		clock: 100,
+		id: Entity(2),
+		keystate: ServerKeyState {
+			up: true,
+			right: true,
+			boost: true,
+			..Default::default()
+		},
+		upgrades: upgrades(2, true, false),
+		pos: pos(1000.5, -2000.25),
+		rot: Rotation::new(1.0),
+		speed: vel(2.5, -1.0),
+	};
+
+	let decoded = server(
+		ServerPacket::PlayerUpdate(packet),
+		&[
+			0x0C,
+			0x64, 0x00, 0x00, 0x00, // clock
+			0x02, 0x00,             // id
+			0x19,                   // keystate: up | right | boost
+			0x0A,                   // upgrades: speed 2, shield
+			0xD1, 0x87, 0x00,       // pos.x: 1000.5
+			0x5F, 0x70, 0x80,       // pos.y: -2000.25
+			0x99, 0x19,             // rot: 1.0
+			0x00, 0x90, 0x9A, 0x79, // speed: (2.5, -1.0)
+		],
+	);
+
+	match decoded {
+		ServerPacket::PlayerUpdate(p) => {
+			assert!(p.keystate.up && p.keystate.right && p.keystate.boost);
+			assert!(!p.keystate.down && !p.keystate.strafe);
+			assert_eq!(p.upgrades, upgrades(2, true, false));
+		}
+		p => panic!("decoded as {:?}", p),
+	}
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_player_fire() {
+	let packet = PlayerFire {
+		clock: 1,
+		id: Entity(2),
+		energy: Energy::new(0.6),
+		energy_regen: EnergyRegen::new(0.001),
+		projectiles: vec![PlayerFireProjectile {
+			id: Entity(300),
+			ty: MobType::PredatorMissile,
+			pos: pos(0.0, 0.0),
+			speed: vel(5.0, -2.0),
+			accel: accel(0.25, -0.5),
+			max_speed: Speed::new(10.0),
+		}],
+	};
+
+	server(
+		ServerPacket::PlayerFire(packet),
+		&[
+			0x0D,
+			0x01, 0x00, 0x00, 0x00, // clock
+			0x02, 0x00,             // id
+			0x99,                   // energy: 0.6
+			0xE8, 0x83,             // energy_regen: 0.001
+			0x01,                   // projectiles: u8 length
+			0x2C, 0x01,             //   id
+			0x01,                   //   type: PredatorMissile
+			0x00, 0x80, 0x00, 0x80, //   pos: (0, 0)
+			0x00, 0xA0, 0x34, 0x73, //   speed: (5, -2)
+			0x00, 0xA0, 0x00, 0x40, //   accel: (0.25, -0.5)
+			0x00, 0xC0,             //   max_speed: 10
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_player_hit() {
+	let packet = PlayerHit {
+		id: Entity(300),
+		ty: MobType::GoliathMissile,
+		pos: pos(100.0, 200.0),
+		owner: Entity(2),
+		players: vec![PlayerHitPlayer {
+			id: Entity(3),
+			health: Health::new(0.4),
+			health_regen: HealthRegen::new(0.0005),
+		}],
+	};
+
+	server(
+		ServerPacket::PlayerHit(packet),
+		&[
+			0x0E,
+			0x2C, 0x01,             // id
+			0x02,                   // type: GoliathMissile
+			0xC8, 0x80, 0x20, 0x83, // pos: (100, 200)
+			0x02, 0x00,             // owner
+			0x01,                   // players: u8 length
+			0x03, 0x00,             //   id
+			0x66,                   //   health: 0.4
+			0xF4, 0x81,             //   health_regen: 0.0005
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_player_respawn() {
+	let packet = PlayerRespawn {
+		id: Entity(2),
+		pos: pos(1000.5, -2000.25),
+		rot: Rotation::new(0.5),
+		upgrades: upgrades(0, false, false),
+	};
+
+	server(
+		ServerPacket::PlayerRespawn(packet),
+		&[
+			0x0F,
+			0x02, 0x00,                         // id
+			0xD1, 0x87, 0x00, 0x5F, 0x70, 0x80, // pos
+			0xCC, 0x0C,                         // rot: 0.5
+			0x00,                               // upgrades
+		],
+	);
+}
+
+#[test]
+fn server_player_flag() {
+	let packet = PlayerFlag {
+		id: Entity(2),
+		flag: FlagCode::UnitedKingdom,
+	};
+
+	server(
+		ServerPacket::PlayerFlag(packet),
+		&[0x10, 0x02, 0x00, 0x61, 0x00],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_player_kill() {
+	let packet = PlayerKill {
+		id: Entity(3),
+		killer: Some(Entity(2)),
+		pos: pos(0.0, 0.0),
+	};
+
+	server(
+		ServerPacket::PlayerKill(packet),
+		&[0x11, 0x03, 0x00, 0x02, 0x00, 0x00, 0x80, 0x00, 0x80],
+	);
+}
+
+#[test]
+fn server_player_type() {
+	let packet = PlayerType {
+		id: Entity(2),
+		ty: PlaneType::Mohawk,
+	};
+
+	server(ServerPacket::PlayerType(packet), &[0x13, 0x02, 0x00, 0x03]);
+}
+
+#[test]
+fn server_player_level() {
+	let packet = PlayerLevel {
+		id: Entity(2),
+		ty: PlayerLevelType::LevelUp,
+		level: Level(5),
+	};
+
+	server(
+		ServerPacket::PlayerLevel(packet),
+		&[0x15, 0x02, 0x00, 0x01, 0x05],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_player_reteam() {
+	let packet = PlayerReteam {
+		players: vec![
+			PlayerReteamPlayer {
+				id: Entity(2),
+				team: Team(1),
+			},
+			PlayerReteamPlayer {
+				id: Entity(3),
+				team: Team(2),
+			},
+		],
+	};
+
+	server(
+		ServerPacket::PlayerReteam(packet),
+		&[
+			0x16,
+			0x02, 0x00,             // players: u16 length
+			0x02, 0x00, 0x01, 0x00,
+			0x03, 0x00, 0x02, 0x00,
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_game_flag() {
+	let packet = GameFlag {
+		ty: FlagUpdateType::Carrier,
+		flag: Team(2),
+		id: Some(Entity(2)),
+		pos: pos(1000.5, -2000.25),
+		blueteam: 1,
+		redteam: 2,
+	};
+
+	server(
+		ServerPacket::GameFlag(packet),
+		&[
+			0x1E,
+			0x02,                               // type: Carrier
+			0x02,                               // flag: a single byte
+			0x02, 0x00,                         // id
+			0xD1, 0x87, 0x00, 0x5F, 0x70, 0x80, // pos
+			0x01,                               // blueteam
+			0x02,                               // redteam
+		],
+	);
+}
+
+#[test]
+fn server_game_spectate() {
+	let packet = GameSpectate { id: Entity(2) };
+
+	server(ServerPacket::GameSpectate(packet), &[0x1F, 0x02, 0x00]);
+}
+
+#[test]
+fn server_game_players_alive() {
+	let packet = GamePlayersAlive { players: 17 };
+
+	server(ServerPacket::GamePlayersAlive(packet), &[0x20, 0x11, 0x00]);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_event_repel() {
+	let packet = EventRepel {
+		clock: 5,
+		id: Entity(2),
+		pos: pos(10.0, 20.0),
+		rot: Rotation::new(2.0),
+		speed: vel(0.5, 0.0),
+		energy: Energy::new(0.2),
+		energy_regen: EnergyRegen::new(-0.002),
+		players: vec![EventRepelPlayer {
+			id: Entity(3),
+			keystate: ServerKeyState {
+				left: true,
+				stealth: true,
+				..Default::default()
+			},
+			pos: pos(-10.0, -20.0),
+			rot: Rotation::new(4.0),
+			speed: vel(1.0, -1.0),
+			energy: Energy::new(0.8),
+			energy_regen: EnergyRegen::new(0.002),
+			health: Health::new(1.0),
+			health_regen: HealthRegen::new(0.0),
+		}],
+		mobs: vec![EventRepelMob {
+			id: Entity(301),
+			ty: MobType::ProwlerMissile,
+			pos: pos(0.0, 0.0),
+			speed: vel(0.0, 0.0),
+			accel: accel(0.0, 0.0),
+			max_speed: Speed::new(5.0),
+		}],
+	};
+
+	server(
+		ServerPacket::EventRepel(packet),
+		&[
+			0x28,
+			0x05, 0x00, 0x00, 0x00, // clock
+			0x02, 0x00,             // id
+			0x14, 0x80, 0x50, 0x80, // pos: (10, 20)
+			0x33, 0x33,             // rot: 2.0
+			0x33, 0x83, 0x00, 0x80, // speed: (0.5, 0)
+			0x33,                   // energy: 0.2
+			0x30, 0x78,             // energy_regen: -0.002
+			0x01,                   // players: u8 length
+			0x03, 0x00,             //   id
+			0x44,                   //   keystate: left | stealth
+			0xEC, 0x7F, 0xB0, 0x7F, //   pos: (-10, -20)
+			0x66, 0x66,             //   rot: 4.0
+			0x66, 0x86, 0x9A, 0x79, //   speed: (1, -1)
+			0xCC,                   //   energy: 0.8
+			0xD0, 0x87,             //   energy_regen: 0.002
+			0xFF,                   //   health: 1.0
+			0x00, 0x80,             //   health_regen: 0
+			0x01,                   // mobs: u8 length
+			0x2D, 0x01,             //   id
+			0x07,                   //   type: ProwlerMissile
+			0x00, 0x80, 0x00, 0x80, //   pos
+			0x00, 0x80, 0x00, 0x80, //   speed
+			0x00, 0x80, 0x00, 0x80, //   accel
+			0x00, 0xA0,             //   max_speed: 5
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_event_boost() {
+	let packet = EventBoost {
+		clock: 6,
+		id: Entity(2),
+		boost: true,
+		pos: pos(1000.5, -2000.25),
+		rot: Rotation::new(6.0),
+		speed: vel(10.0, 0.0),
+		energy: Energy::new(0.4),
+		energy_regen: EnergyRegen::new(0.004),
+	};
+
+	server(
+		ServerPacket::EventBoost(packet),
+		&[
+			0x29,
+			0x06, 0x00, 0x00, 0x00,             // clock
+			0x02, 0x00,                         // id
+			0x01,                               // boost
+			0xD1, 0x87, 0x00, 0x5F, 0x70, 0x80, // pos
+			0x99, 0x99,                         // rot: 6.0
+			0x00, 0xC0, 0x00, 0x80,             // speed: (10, 0)
+			0x66,                               // energy: 0.4
+			0xA0, 0x8F,                         // energy_regen: 0.004
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_event_bounce() {
+	let packet = EventBounce {
+		clock: 7,
+		id: Entity(2),
+		keystate: ServerKeyState {
+			down: true,
+			flagspeed: true,
+			..Default::default()
+		},
+		pos: pos(0.0, 0.0),
+		rot: Rotation::new(0.0),
+		speed: vel(-3.5, 2.5),
+	};
+
+	server(
+		ServerPacket::EventBounce(packet),
+		&[
+			0x2A,
+			0x07, 0x00, 0x00, 0x00,             // clock
+			0x02, 0x00,                         // id
+			0x82,                               // keystate: down | flagspeed
+			0x00, 0x80, 0x00, 0x00, 0x80, 0x00, // pos: (0, 0)
+			0x00, 0x00,                         // rot: 0
+			0x9A, 0x69, 0x00, 0x90,             // speed: (-3.5, 2.5)
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_event_stealth() {
+	let packet = EventStealth {
+		id: Entity(2),
+		state: true,
+		energy: Energy::new(1.0),
+		energy_regen: EnergyRegen::new(0.01),
+	};
+
+	server(
+		ServerPacket::EventStealth(packet),
+		&[0x2B, 0x02, 0x00, 0x01, 0xFF, 0x10, 0xA7],
+	);
+}
+
+#[test]
+fn server_event_leave_horizon() {
+	let packet = EventLeaveHorizon {
+		ty: LeaveHorizonType::Mob,
+		id: Entity(301),
+	};
+
+	server(
+		ServerPacket::EventLeaveHorizon(packet),
+		&[0x2C, 0x01, 0x2D, 0x01],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_mob_update() {
+	let packet = MobUpdate {
+		clock: 8,
+		id: Entity(301),
+		ty: MobType::ProwlerMissile,
+		pos: pos(100.0, -100.0),
+		speed: vel(1.0, 0.5),
+		accel: accel(0.25, 0.0),
+		max_speed: Speed::new(5.0),
+	};
+
+	server(
+		ServerPacket::MobUpdate(packet),
+		&[
+			0x3C,
+			0x08, 0x00, 0x00, 0x00, // clock
+			0x2D, 0x01,             // id
+			0x07,                   // type: ProwlerMissile
+			0xC8, 0x80, 0x70, 0x7E, // pos: (100, -100)
+			0x66, 0x86, 0x33, 0x83, // speed: (1, 0.5)
+			0x00, 0xA0, 0x00, 0x80, // accel: (0.25, 0)
+			0x00, 0xA0,             // max_speed: 5
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_mob_update_stationary() {
+	let packet = MobUpdateStationary {
+		id: Entity(302),
+		ty: MobType::Upgrade,
+		pos: pos(1.0, -2.5),
+	};
+
+	server(
+		ServerPacket::MobUpdateStationary(packet),
+		&[
+			0x3D,
+			0x2E, 0x01,             // id
+			0x04,                   // type: Upgrade
+			0x00, 0x00, 0x80, 0x3F, // pos.x: 1.0f32
+			0x00, 0x00, 0x20, 0xC0, // pos.y: -2.5f32
+		],
+	);
+}
+
+#[test]
+fn server_mob_despawn() {
+	let packet = MobDespawn {
+		id: Entity(302),
+		ty: MobType::Shield,
+	};
+
+	server(ServerPacket::MobDespawn(packet), &[0x3E, 0x2E, 0x01, 0x08]);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_mob_despawn_coords() {
+	let packet = MobDespawnCoords {
+		id: Entity(301),
+		ty: MobType::Inferno,
+		pos: pos(200.0, 300.0),
+	};
+
+	server(
+		ServerPacket::MobDespawnCoords(packet),
+		&[0x3F, 0x2D, 0x01, 0x09, 0x90, 0x81, 0xB0, 0x84],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_chat() {
+	let public = ChatPublic {
+		id: Entity(2),
+		text: "hi".to_owned(),
+	};
+	let team = ChatTeam {
+		id: Entity(2),
+		text: "go".to_owned(),
+	};
+	let say = ChatSay {
+		id: Entity(2),
+		text: "ok".to_owned(),
+	};
+	let whisper = ChatWhisper {
+		from: Entity(2),
+		to: Entity(3),
+		text: "yo".to_owned(),
+	};
+
+	server(ServerPacket::ChatPublic(public), &[0x46, 0x02, 0x00, 0x02, 0x68, 0x69]);
+	server(ServerPacket::ChatTeam(team), &[0x47, 0x02, 0x00, 0x02, 0x67, 0x6F]);
+	server(ServerPacket::ChatSay(say), &[0x48, 0x02, 0x00, 0x02, 0x6F, 0x6B]);
+	server(
+		ServerPacket::ChatWhisper(whisper),
+		&[0x49, 0x02, 0x00, 0x03, 0x00, 0x02, 0x79, 0x6F],
+	);
+}
+
+#[test]
+fn server_chat_votemute() {
+	let packet = ChatVoteMutePassed { id: Entity(3) };
+
+	server(
+		ServerPacket::ChatVoteMutePassed(packet),
+		&[0x4E, 0x03, 0x00],
+	);
+	server(ServerPacket::ChatVoteMuted, &[0x4F]);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_score_update() {
+	let packet = ScoreUpdate {
+		id: Entity(2),
+		score: Score(1000),
+		earnings: Score(500),
+		upgrades: 3,
+		total_kills: 4,
+		total_deaths: 5,
+	};
+
+	server(
+		ServerPacket::ScoreUpdate(packet),
+		&[
+			0x50,
+			0x02, 0x00,             // id
+			0xE8, 0x03, 0x00, 0x00, // score
+			0xF4, 0x01, 0x00, 0x00, // earnings
+			0x03, 0x00,             // upgrades
+			0x04, 0x00, 0x00, 0x00, // total_kills
+			0x05, 0x00, 0x00, 0x00, // total_deaths
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_score_board() {
+	let packet = ScoreBoard {
+		data: vec![ScoreBoardData {
+			id: Entity(2),
+			score: Score(1000),
+			level: Level(5),
+		}],
+		rankings: vec![ScoreBoardRanking {
+			id: Entity(2),
+			pos: pos(2560.0, -1280.0),
+		}],
+	};
+
+	server(
+		ServerPacket::ScoreBoard(packet),
+		&[
+			0x51,
+			0x01, 0x00,             // data: u16 length
+			0x02, 0x00,             //   id
+			0xE8, 0x03, 0x00, 0x00, //   score
+			0x05,                   //   level
+			0x01, 0x00,             // rankings: u16 length
+			0x02, 0x00,             //   id
+			0x94, 0x6C,             //   pos: (2560, -1280)
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_score_detailed() {
+	let ffa = ScoreDetailedFFA {
+		scores: vec![ScoreDetailedFFAScore {
+			id: Entity(2),
+			level: Level(5),
+			score: Score(1000),
+			kills: 4,
+			deaths: 5,
+			damage: 1.0,
+			ping: 50,
+		}],
+	};
+	let ctf = ScoreDetailedCTF {
+		scores: vec![ScoreDetailedCTFScore {
+			id: Entity(2),
+			level: Level(5),
+			captures: 1,
+			score: Score(1000),
+			kills: 4,
+			deaths: 5,
+			damage: 1.0,
+			ping: 50,
+		}],
+	};
+	let btr = ScoreDetailedBTR {
+		scores: vec![ScoreDetailedBTRScore {
+			id: Entity(2),
+			level: Level(5),
+			alive: true,
+			wins: 1,
+			score: Score(1000),
+			kills: 4,
+			deaths: 5,
+			damage: 1.0,
+			ping: 50,
+		}],
+	};
+
+	server(
+		ServerPacket::ScoreDetailedFFA(ffa),
+		&[
+			0x52,
+			0x01, 0x00,             // scores: u16 length
+			0x02, 0x00,             //   id
+			0x05,                   //   level
+			0xE8, 0x03, 0x00, 0x00, //   score
+			0x04, 0x00,             //   kills
+			0x05, 0x00,             //   deaths
+			0x00, 0x00, 0x80, 0x3F, //   damage: 1.0f32
+			0x32, 0x00,             //   ping
+		],
+	);
+	server(
+		ServerPacket::ScoreDetailedCTF(ctf),
+		&[
+			0x53,
+			0x01, 0x00,
+			0x02, 0x00,
+			0x05,
+			0x01, 0x00,             //   captures
+			0xE8, 0x03, 0x00, 0x00,
+			0x04, 0x00,
+			0x05, 0x00,
+			0x00, 0x00, 0x80, 0x3F,
+			0x32, 0x00,
+		],
+	);
+	server(
+		ServerPacket::ScoreDetailedBTR(btr),
+		&[
+			0x54,
+			0x01, 0x00,
+			0x02, 0x00,
+			0x05,
+			0x01,                   //   alive
+			0x01, 0x00,             //   wins
+			0xE8, 0x03, 0x00, 0x00,
+			0x04, 0x00,
+			0x05, 0x00,
+			0x00, 0x00, 0x80, 0x3F,
+			0x32, 0x00,
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_message() {
+	let packet = ServerMessage {
+		ty: ServerMessageType::ShutdownMessage,
+		duration: 5000,
+		text: "bye".to_owned(),
+	};
+
+	server(
+		ServerPacket::ServerMessage(packet),
+		&[
+			0x5A,
+			0x0F,                   // type: ShutdownMessage
+			0x88, 0x13, 0x00, 0x00, // duration
+			0x03, 0x00,             // text: u16 length
+			0x62, 0x79, 0x65,
+		],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_custom() {
+	let packet = ServerCustom {
+		ty: ServerCustomType::Extension,
+		data: "{}".to_owned(),
+	};
+
+	server(
+		ServerPacket::ServerCustom(packet),
+		&[
+			0x5B,
+			0xC8,       // type: Extension
+			0x02, 0x00, // data: u16 length
+			0x7B, 0x7D,
+		],
+	);
+}
+
+#[test]
+fn server_error() {
+	let packet = airmash_protocol::server::Error {
+		error: ErrorType::IncorrectProtocol,
+	};
+
+	server(ServerPacket::Error(packet), &[0x08, 0x09]);
+}
+
+/// These packets use enums that don't have
+/// any known values yet, so no valid packet
+/// of these types can be decoded.
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn server_unknown_enums() {
+	let packets: &[&[u8]] = &[
+		&[0x09, 0x00, 0x00, 0x00],                     // CommandReply
+		&[0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],   // PlayerUpgrade
+		&[0x14, 0x00, 0x00, 0x00, 0x00, 0x00],         // PlayerPowerup
+		&[0x21, 0x00, 0x00, 0x00, 0x80, 0x00, 0x80],   // GameFirewall
+	];
+
+	for bytes in packets {
+		match from_bytes::<ServerPacket>(bytes) {
+			Err(DeError::InvalidEnumValue(..)) => (),
+			r => panic!("{:?} decoded as {:?}", bytes, r),
+		}
+	}
+}
+
+// Client packets
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn client_login() {
+	let packet = ClientLogin {
+		protocol: 5,
+		name: "x".to_owned(),
+		session: "none".to_owned(),
+		horizon_x: 3000,
+		horizon_y: 2000,
+		flag: "GB".to_owned(),
+	};
+
+	client(
+		ClientPacket::Login(packet),
+		&[
+			0x00,
+			0x05,                         // protocol
+			0x01, 0x78,                   // name: "x"
+			0x04, 0x6E, 0x6F, 0x6E, 0x65, // session: "none"
+			0xB8, 0x0B,                   // horizon_x
+			0xD0, 0x07,                   // horizon_y
+			0x02, 0x47, 0x42,             // flag: "GB"
+		],
+	);
+}
+
+#[test]
+fn client_backup() {
+	let packet = Backup {
+		token: "tk".to_owned(),
+	};
+
+	client(ClientPacket::Backup(packet), &[0x01, 0x02, 0x74, 0x6B]);
+}
+
+#[test]
+fn client_horizon() {
+	let packet = Horizon {
+		horizon_x: 3000,
+		horizon_y: 2000,
+	};
+
+	client(
+		ClientPacket::Horizon(packet),
+		&[0x02, 0xB8, 0x0B, 0xD0, 0x07],
+	);
+}
+
+#[test]
+fn client_ack() {
+	client(ClientPacket::Ack, &[0x05]);
+}
+
+#[test]
+fn client_pong() {
+	let packet = Pong { num: 7 };
+
+	client(ClientPacket::Pong(packet), &[0x06, 0x07, 0x00, 0x00, 0x00]);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn client_key() {
+	let packet = Key {
+		seq: 9,
+		key: KeyCode::Fire,
+		state: true,
+	};
+
+	client(
+		ClientPacket::Key(packet),
+		&[0x0A, 0x09, 0x00, 0x00, 0x00, 0x05, 0x01],
+	);
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn client_command() {
+	let packet = Command {
+		com: "spectate".to_owned(),
+		data: "-1".to_owned(),
+	};
+
+	client(
+		ClientPacket::Command(packet),
+		&[
+			0x0B,
+			0x08, 0x73, 0x70, 0x65, 0x63, 0x74, 0x61, 0x74, 0x65, // "spectate"
+			0x02, 0x2D, 0x31,                                     // "-1"
+		],
+	);
+}
+
+#[test]
+fn client_score_detailed() {
+	client(ClientPacket::ScoreDetailed, &[0x0C]);
+}
+
+#[test]
+fn client_chat() {
+	let chat = Chat {
+		text: "hi".to_owned(),
+	};
+	let whisper = Whisper {
+		id: 3,
+		text: "yo".to_owned(),
+	};
+	let say = Say {
+		text: "ok".to_owned(),
+	};
+	let team = TeamChat {
+		text: "go".to_owned(),
+	};
+
+	client(ClientPacket::Chat(chat), &[0x14, 0x02, 0x68, 0x69]);
+	client(
+		ClientPacket::Whisper(whisper),
+		&[0x15, 0x03, 0x00, 0x02, 0x79, 0x6F],
+	);
+	client(ClientPacket::Say(say), &[0x16, 0x02, 0x6F, 0x6B]);
+	client(ClientPacket::TeamChat(team), &[0x17, 0x02, 0x67, 0x6F]);
+}
+
+#[test]
+fn client_votemute() {
+	let packet = VoteMute { id: Entity(3) };
+
+	client(ClientPacket::VoteMute(packet), &[0x19, 0x03, 0x00]);
+}
+
+#[test]
+fn client_local_ping() {
+	let packet = LocalPing { auth: 0xDEADBEEF };
+
+	client(
+		ClientPacket::LocalPing(packet),
+		&[0xFF, 0xEF, 0xBE, 0xAD, 0xDE],
+	);
+}
+
+// Individual field codecs
+
+#[test]
+fn pos24_decodes_exactly() {
+	// Anything that is a multiple of 1/512
+	// should survive the round trip.
+	let bytes = [
+		0x0F, 0x02, 0x00, 0xD1, 0x87, 0x00, 0x5F, 0x70, 0x80, 0xCC, 0x0C, 0x00,
+	];
+
+	match from_bytes::<ServerPacket>(&bytes).unwrap() {
+		ServerPacket::PlayerRespawn(p) => {
+			assert_eq!(p.pos.x.inner(), 1000.5);
+			assert_eq!(p.pos.y.inner(), -2000.25);
+		}
+		p => panic!("decoded as {:?}", p),
+	}
+}
+
+#[test]
+fn lowrespos_decodes_both_axes() {
+	let bytes = [0x51, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x94, 0x6C];
+
+	match from_bytes::<ServerPacket>(&bytes).unwrap() {
+		ServerPacket::ScoreBoard(p) => {
+			assert_eq!(p.rankings[0].pos.x.inner(), 2560.0);
+			assert_eq!(p.rankings[0].pos.y.inner(), -1280.0);
+		}
+		p => panic!("decoded as {:?}", p),
+	}
+}
+
+#[test]
+fn rotation_is_truncated() {
+	// 1.0 * 6553.6 truncates to 0x1999, which
+	// decodes to slightly less than 1.0
+	let bytes = [
+		0x0F, 0x02, 0x00, 0x00, 0x80, 0x00, 0x00, 0x80, 0x00, 0x99, 0x19, 0x00,
+	];
+
+	match from_bytes::<ServerPacket>(&bytes).unwrap() {
+		ServerPacket::PlayerRespawn(p) => {
+			assert_eq!(p.rot.inner(), 6553.0 / 6553.6);
+			assert_eq!(
+				to_bytes(&ServerPacket::PlayerRespawn(p)).unwrap(),
+				&bytes[..]
+			);
+		}
+		p => panic!("decoded as {:?}", p),
+	}
+}
+
+#[test]
+fn health_regen_is_offset() {
+	// 0x8000 is a regen of zero
+	let bytes = [
+		0x0E, 0x2C, 0x01, 0x02, 0x00, 0x80, 0x00, 0x80, 0x02, 0x00, 0x02, 0x03, 0x00, 0x66, 0xF4,
+		0x81, 0x04, 0x00, 0xFF, 0x18, 0x7C,
+	];
+
+	match from_bytes::<ServerPacket>(&bytes).unwrap() {
+		ServerPacket::PlayerHit(p) => {
+			assert_eq!(p.players[0].health_regen.inner(), 0.0005);
+			assert_eq!(p.players[1].health_regen.inner(), -0.001);
+			assert_eq!(p.players[1].health.inner(), 1.0);
+		}
+		p => panic!("decoded as {:?}", p),
+	}
+}
+
+#[test]
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn option_entity_uses_zero_for_none() {
+	let packet = PlayerKill {
+		id: Entity(3),
+		killer: None,
+		pos: pos(0.0, 0.0),
+	};
+
+	let decoded = server(
+		ServerPacket::PlayerKill(packet),
+		&[0x11, 0x03, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x80],
+	);
+
+	match decoded {
+		ServerPacket::PlayerKill(p) => assert_eq!(p.killer, None),
+		p => panic!("decoded as {:?}", p),
+	}
+
+	let bytes = [0x11, 0x03, 0x00, 0x02, 0x00, 0x00, 0x80, 0x00, 0x80];
+	match from_bytes::<ServerPacket>(&bytes).unwrap() {
+		ServerPacket::PlayerKill(p) => assert_eq!(p.killer, Some(Entity(2))),
+		p => panic!("decoded as {:?}", p),
+	}
+}
+
+#[test]
+fn text_length_prefix() {
+	// Text has a u8 length, so 255 bytes is the
+	// most that can be sent.
+	let text = "a".repeat(255);
+	let bytes = to_bytes(&ClientPacket::Chat(Chat { text: text.clone() })).unwrap();
+	assert_eq!(&bytes[..2], &[0x14, 0xFF]);
+	assert_eq!(bytes.len(), 2 + 255);
+
+	match to_bytes(&ClientPacket::Chat(Chat {
+		text: "a".repeat(256),
+	})) {
+		Err(Error::ArrayLengthTooBig) => (),
+		r => panic!("encoded as {:?}", r),
+	}
+
+	// TextBig has a u16 length
+	let packet = ServerMessage {
+		ty: ServerMessageType::FlagMessage,
+		duration: 0,
+		text: "a".repeat(256),
+	};
+	let bytes = to_bytes(&ServerPacket::ServerMessage(packet)).unwrap();
+	assert_eq!(&bytes[6..8], &[0x00, 0x01]);
+	assert_eq!(bytes.len(), 8 + 256);
+}
+
+#[test]
+fn array_length_prefix() {
+	let player = PlayerHitPlayer {
+		id: Entity(3),
+		health: Health::new(1.0),
+		health_regen: HealthRegen::new(0.0),
+	};
+	let hit = |players| PlayerHit {
+		id: Entity(300),
+		ty: MobType::GoliathMissile,
+		pos: pos(0.0, 0.0),
+		owner: Entity(2),
+		players,
+	};
+
+	// ArraySmall has a u8 length
+	let bytes = to_bytes(&ServerPacket::PlayerHit(hit(vec![player; 255]))).unwrap();
+	assert_eq!(bytes[10], 0xFF);
+
+	match to_bytes(&ServerPacket::PlayerHit(hit(vec![player; 256]))) {
+		Err(Error::ArrayLengthTooBig) => (),
+		r => panic!("encoded as {:?}", r),
+	}
+
+	// Array has a u16 length
+	let reteam = PlayerReteam {
+		players: vec![
+			PlayerReteamPlayer {
+				id: Entity(2),
+				team: Team(1),
+			};
+			256
+		],
+	};
+	let bytes = to_bytes(&ServerPacket::PlayerReteam(reteam)).unwrap();
+	assert_eq!(&bytes[1..3], &[0x00, 0x01]);
+	assert_eq!(bytes.len(), 3 + 256 * 4);
+}
+
+#[test]
+fn malformed_packets() {
+	match from_bytes::<ServerPacket>(&[0x46, 0x02, 0x00, 0x05, 0x68]) {
+		Err(DeError::Eof) => (),
+		r => panic!("decoded as {:?}", r),
+	}
+	match from_bytes::<ServerPacket>(&[0x07, 0x00]) {
+		Err(DeError::TrailingBytes) => (),
+		r => panic!("decoded as {:?}", r),
+	}
+	match from_bytes::<ClientPacket>(&[0x03]) {
+		Err(DeError::InvalidPacketType) => (),
+		r => panic!("decoded as {:?}", r),
+	}
+}
+
+#[test]
+fn largest_ids_round_trip() {
+	// The largest entity id and flag team that
+	// fit on the wire used to panic when the
+	// decoded packet was sent again.
+	let bytes = [0x0B, 0xFF, 0xFF];
+	let packet = from_bytes::<ServerPacket>(&bytes).unwrap();
+	assert_eq!(to_bytes(&packet).unwrap(), &bytes[..]);
+
+	let bytes = [
+		0x1E, 0x01, 0xFF, 0xFF, 0xFF, 0x00, 0x80, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00,
+	];
+	let packet = from_bytes::<ServerPacket>(&bytes).unwrap();
+	assert_eq!(to_bytes(&packet).unwrap(), &bytes[..]);
+}
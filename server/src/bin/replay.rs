@@ -0,0 +1,39 @@
+//! Serve a recorded match to spectating clients.
+//!
+//! Usage: `replay <file> [address]`
+
+extern crate airmash_server;
+extern crate log;
+extern crate simple_logger;
+
+use airmash_server::replay::ReplayServer;
+
+use std::env;
+use std::process;
+
+fn main() {
+	env::set_var("RUST_BACKTRACE", "1");
+
+	simple_logger::init_with_level(log::Level::Info).unwrap();
+
+	let mut args = env::args().skip(1);
+
+	let path = match args.next() {
+		Some(path) => path,
+		None => {
+			eprintln!("Usage: replay <file> [address]");
+			process::exit(1);
+		}
+	};
+	let addr = args.next().unwrap_or("0.0.0.0:3501".to_owned());
+
+	let server = match ReplayServer::load(&path) {
+		Ok(server) => server,
+		Err(e) => {
+			eprintln!("Unable to load replay {}: {}", path, e);
+			process::exit(1);
+		}
+	};
+
+	server.run(addr);
+}
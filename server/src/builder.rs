@@ -1,27 +1,33 @@
 use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, BufWriter};
 use std::net::ToSocketAddrs;
+use std::path::Path;
 use std::sync::mpsc::{channel, Receiver, Sender};
-use std::thread;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use rayon::ThreadPoolBuilder;
 use specs::Builder as SpecsBuilder;
 use specs::*;
 
-use futures;
-use websocket::OwnedMessage;
-
-use dispatch::Builder;
-use metrics;
-use server;
+use dispatch::{Builder, SystemTimings};
+use metrics::{self, MetricsHandler};
+use replay::RecordSystem;
+use server::WebsocketTransport;
 use systems;
 use timeloop::timeloop;
-use timers;
+use timers::PeriodicTimers;
 
 use types::event::ConnectionEvent;
-use types::{ConnectionId, Connections, FutureDispatcher, GameMode};
+use types::extension::{CustomMessage, ExtensionRegistry};
+use types::{
+	BothTransports, ConnectionId, ConnectionLimits, Connections, FutureDispatcher, GameMode,
+	LagCompensation, OutboundLimits, OutboundMessage, StatusHandle, Transport,
+};
 
 use component::event::TimerEvent;
-use component::time::{LastFrame, StartTime, ThisFrame};
+use component::time::{LastFrame, StartTime, ThisFrame, TickRate};
 
 use tokio::runtime::current_thread::Runtime;
 
@@ -41,28 +47,75 @@ impl<T> Channel<T> {
 	}
 }
 
-pub struct AirmashServer<'a, 'b, T>
-where
-	T: ToSocketAddrs + Debug + Send + 'static,
-{
+pub struct AirmashServer<'a, 'b> {
 	pub builder: Builder<'a, 'b>,
-	addr: T,
+	transport: Box<Transport>,
 
 	event: Channel<ConnectionEvent>,
 	timer: Channel<TimerEvent>,
-	msg: Channel<(ConnectionId, OwnedMessage)>,
+	msg: Channel<(ConnectionId, OutboundMessage)>,
 
 	pub world: World,
 }
 
-impl<T> AirmashServer<'static, 'static, T>
-where
-	T: ToSocketAddrs + Debug + Send + 'static,
-{
-	pub fn new(addr: T) -> Self {
+/// Present if systems were given their own
+/// thread pool to run on in parallel.
+struct Parallel;
+
+/// Run a single frame of the game loop.
+fn run_frame(world: &mut World, dispatcher: &mut Dispatcher, now: Instant) {
+	world.add_resource(ThisFrame(now));
+	if world.res.has_value::<Parallel>() {
+		dispatcher.dispatch_par(&mut world.res);
+	} else {
+		dispatcher.dispatch_seq(&mut world.res);
+	}
+	dispatcher.dispatch_thread_local(&mut world.res);
+	world.maintain();
+	world.add_resource(LastFrame(now));
+}
+
+fn frame_time() -> Duration {
+	Duration::from_nanos(16666667)
+}
+
+fn millis(d: Duration) -> u64 {
+	d.as_secs() * 1000 + d.subsec_millis() as u64
+}
+
+impl AirmashServer<'static, 'static> {
+	/// Create a server that accepts websocket
+	/// connections on `addr`.
+	pub fn new<T>(addr: T) -> Self
+	where
+		T: ToSocketAddrs + Debug + Send + 'static,
+	{
+		Self::from_websocket(WebsocketTransport::new(addr))
+	}
+
+	/// Create a server from a websocket transport
+	/// that has already been configured, e.g.
+	/// to also accept TLS connections.
+	pub fn from_websocket<T>(transport: WebsocketTransport<T>) -> Self
+	where
+		T: ToSocketAddrs + Debug + Send + 'static,
+	{
+		let status = StatusHandle::default();
+
+		let mut server = Self::from_transport(transport.with_status(status.clone()));
+		server.world.add_resource(status);
+		server
+	}
+
+	/// Create a server that gets its connections
+	/// from a custom transport.
+	pub fn from_transport<T>(transport: T) -> Self
+	where
+		T: Transport + 'static,
+	{
 		Self {
 			builder: Builder::new(),
-			addr: addr,
+			transport: Box::new(transport),
 
 			event: Channel::new(),
 			timer: Channel::new(),
@@ -72,10 +125,21 @@ where
 		}
 	}
 
+	/// Also accept connections from `transport`,
+	/// alongside the one the server was created
+	/// with.
+	pub fn with_transport<T>(mut self, transport: T) -> Self
+	where
+		T: Transport + 'static,
+	{
+		self.transport = Box::new(BothTransports(self.transport, transport));
+		self
+	}
+
 	pub fn with_base_systems(self) -> Self {
 		let Self {
 			builder,
-			addr,
+			transport,
 			event,
 			timer,
 			msg,
@@ -90,7 +154,7 @@ where
 
 		Self {
 			builder,
-			addr,
+			transport,
 			world,
 
 			event: Channel {
@@ -111,7 +175,7 @@ where
 	pub fn with_engine_systems(self) -> Self {
 		let Self {
 			builder,
-			addr,
+			transport,
 			event,
 			timer,
 			msg,
@@ -122,7 +186,7 @@ where
 
 		Self {
 			builder,
-			addr,
+			transport,
 			event,
 			timer,
 			msg,
@@ -133,7 +197,7 @@ where
 	pub fn with_engine_resources(self) -> Self {
 		let Self {
 			builder,
-			addr,
+			transport,
 			event,
 			timer,
 			msg,
@@ -141,13 +205,20 @@ where
 			..
 		} = self;
 
-		world.add_resource(metrics::handler());
+		// Might have already been added by with_metrics
+		if !world.res.has_value::<MetricsHandler>() {
+			world.add_resource(metrics::handler());
+		}
+		world.add_resource(SystemTimings::default());
+		if !world.res.has_value::<StatusHandle>() {
+			world.add_resource(StatusHandle::default());
+		}
 		world.add_resource(Connections::new(msg.send.unwrap()));
 		world.add_resource(FutureDispatcher::new(timer.send.as_ref().unwrap().clone()));
 
 		Self {
 			builder,
-			addr,
+			transport,
 			event,
 			timer,
 			world,
@@ -159,11 +230,12 @@ where
 		}
 	}
 
-	/// Add some dummmy entities so that there are no players with id 0, 1, or 2
-	/// this makes FFA team logic easier. StarMash also appears to
-	/// make all players mimic the player with id 0
+	/// Add a dummy entity so that no player is ever entity 0,
+	/// which terrain collisions use to stand in for the map. The
+	/// ids sent to clients are given out separately by `EntityIds`
+	/// and never go below `FIRST_ENTITY_ID`.
 	pub fn with_filler_entities(mut self) -> Self {
-		while self.world.create_entity().build().id() < 3 {}
+		while self.world.create_entity().build().id() < 1 {}
 
 		self
 	}
@@ -174,6 +246,94 @@ where
 			.with_filler_entities()
 	}
 
+	/// Override the default limits on how many
+	/// outbound messages may be queued for a
+	/// single connection.
+	pub fn with_outbound_limits(mut self, limits: OutboundLimits) -> Self {
+		self.world.add_resource(limits);
+		self
+	}
+
+	/// Limit how many connections and players
+	/// may come from a single address, and which
+	/// origins may connect at all.
+	pub fn with_connection_limits(mut self, limits: ConnectionLimits) -> Self {
+		self.world.add_resource(limits);
+		self
+	}
+
+	/// Override how far back missile hits may
+	/// be checked to make up for the shooter's
+	/// ping.
+	pub fn with_lag_compensation(mut self, limits: LagCompensation) -> Self {
+		self.world.add_resource(limits);
+		self
+	}
+
+	/// Serve metrics in the Prometheus text
+	/// format at `http://<addr>/metrics`.
+	pub fn with_metrics<A: ToSocketAddrs>(mut self, addr: A) -> io::Result<Self> {
+		if !self.world.res.has_value::<MetricsHandler>() {
+			self.world.add_resource(metrics::handler());
+		}
+
+		self.world.read_resource::<MetricsHandler>().serve(addr)?;
+
+		Ok(self)
+	}
+
+	/// Run `hz` frames a second instead of the
+	/// default of 60.
+	pub fn with_tick_rate(mut self, hz: u32) -> Self {
+		self.world.add_resource(TickRate(Duration::from_secs(1) / hz.max(1)));
+		self
+	}
+
+	/// Run systems in parallel on a pool of
+	/// `threads` threads. By default they run
+	/// one after another on the thread that
+	/// runs the game loop.
+	pub fn with_threads(mut self, threads: usize) -> io::Result<Self> {
+		let pool = ThreadPoolBuilder::new()
+			.num_threads(threads)
+			.build()
+			.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+		self.builder = self.builder.with_pool(Arc::new(pool));
+		self.world.add_resource(Parallel);
+
+		Ok(self)
+	}
+
+	/// Record everything sent by the server
+	/// into a replay file at `path`.
+	///
+	/// Replays can be played back with a
+	/// [`ReplayServer`](replay/struct.ReplayServer.html).
+	pub fn with_recording<P: AsRef<Path>>(mut self, path: P) -> io::Result<Self> {
+		let file = BufWriter::new(File::create(path)?);
+
+		self.builder = self
+			.builder
+			.with_thread_local_args::<RecordSystem, _>(Some(file));
+
+		Ok(self)
+	}
+
+	/// Allow `M` to be sent over the extension
+	/// channel to clients that ask for it.
+	///
+	/// See [`extension`](types/extension/index.html)
+	/// for more details.
+	pub fn with_extension<M: CustomMessage>(mut self) -> Self {
+		if !self.world.res.has_value::<ExtensionRegistry>() {
+			self.world.add_resource(ExtensionRegistry::default());
+		}
+
+		self.world.write_resource::<ExtensionRegistry>().register::<M>();
+		self
+	}
+
 	pub fn with_gamemode<G>(mut self, mode: G) -> Self
 	where
 		G: GameMode + 'static,
@@ -186,76 +346,195 @@ where
 		self
 	}
 
-	pub fn run(self) {
+	/// The time between frames set by
+	/// [`with_tick_rate`](#method.with_tick_rate).
+	fn tick(&self) -> Duration {
+		if self.world.res.has_value::<TickRate>() {
+			self.world.read_resource::<TickRate>().0
+		} else {
+			frame_time()
+		}
+	}
+
+	/// Start the transport and set up everything
+	/// needed before the first frame is run.
+	fn start(
+		self,
+		now: Instant,
+		tick: Duration,
+	) -> (World, Dispatcher<'static, 'static>, PeriodicTimers) {
 		let Self {
 			builder,
-			addr,
+			transport,
 			event,
 			timer,
 			mut world,
 			..
 		} = self;
 
-		info!("Starting server runtime!");
+		transport.start(event.send.unwrap());
 
-		// The acceptor needs to run on its own thread
-		// to avoid delaying packets
-		let server_thread = thread::spawn(move || {
-			server::run_acceptor(addr, event.send.unwrap());
-		});
+		// Systems read the frame clock instead of the
+		// wall clock, so it has to exist before setup.
+		world.add_resource(StartTime(now));
+		world.add_resource(ThisFrame(now));
+		world.add_resource(LastFrame(now));
+		world.add_resource(TickRate(tick));
 
-		world.add_resource(StartTime(Instant::now()));
+		if world.res.has_value::<FutureDispatcher>() {
+			world.write_resource::<FutureDispatcher>().exec_tasks(now);
+		}
 
 		let mut dispatcher = builder.build();
 		dispatcher.setup(&mut world.res);
 
-		world.add_resource(LastFrame(Instant::now()));
+		let timers = PeriodicTimers::new(timer.send.unwrap(), now);
 
-		let mut runtime = Runtime::new().unwrap();
+		(world, dispatcher, timers)
+	}
+
+	/// Build the server without starting the game
+	/// loop. Frames will only be run when requested
+	/// through the returned
+	/// [`SteppedServer`](struct.SteppedServer.html).
+	pub fn into_stepped(self) -> SteppedServer {
+		let tick = self.tick();
+		self.into_simulation(Instant::now(), tick)
+	}
+
+	/// Build the server to be driven by a virtual
+	/// clock that starts at `start` and advances by
+	/// exactly `tick` every frame, no matter how
+	/// long the frame actually took to run.
+	pub fn into_simulation(self, start: Instant, tick: Duration) -> SteppedServer {
+		let (world, dispatcher, timers) = self.start(start, tick);
+
+		SteppedServer {
+			world,
+			dispatcher,
+			timers,
+			start,
+			tick,
+			frame: 0,
+		}
+	}
+
+	pub fn run(self) {
+		info!("Starting server runtime!");
 
-		runtime.spawn(futures::lazy(move || {
-			timers::start_timer_events(timer.send.unwrap());
+		let tick = self.tick();
+		let (mut world, mut dispatcher, mut timers) = self.start(Instant::now(), tick);
 
-			Ok(())
-		}));
+		let mut runtime = Runtime::new().unwrap();
 
 		runtime.spawn(timeloop(
 			move |now| {
-				if Instant::now() - now > Duration::from_millis(30) {
-					warn!("Time has drifted more than 30 ms, skipping frame!");
+				if Instant::now() - now > tick * 2 {
+					warn!(
+						"Time has drifted more than {} ms, skipping frame!",
+						millis(tick * 2)
+					);
 					return;
 				}
 
-				world.add_resource(ThisFrame(now));
-				dispatcher.dispatch_seq(&mut world.res);
-				dispatcher.dispatch_thread_local(&mut world.res);
-				world.maintain();
-				world.add_resource(LastFrame(now));
+				timers.update(now);
+				run_frame(&mut world, &mut dispatcher, now);
 
 				let duration = Instant::now() - now;
-				if duration > Duration::from_millis(17) {
+				let timings = world.read_resource::<SystemTimings>();
+				if duration > tick {
 					warn!(
-						"Frame took {} ms! (longer than 16.67 ms)",
-						1000 * duration.as_secs() + (duration.subsec_millis() as u64)
+						"Frame took {} ms! (longer than {} ms)",
+						millis(duration),
+						millis(tick)
 					);
+					timings.log_frame();
 				} else {
 					trace!("Frame time: {} ms", duration.subsec_millis());
 				}
+				timings.end_frame(now);
 
-				// Don't crash server if metric recording fails
 				world
 					.res
-					.fetch::<metrics::MetricsHandler>()
-					.time_duration("frame-time", duration)
-					.unwrap();
+					.fetch::<MetricsHandler>()
+					.time_duration("frame-time", duration);
 			},
-			Duration::from_nanos(16666667),
+			tick,
 		));
 
 		runtime.run().unwrap();
 
 		// Shut down
 		info!("Exited game loop, shutting down");
-		server_thread.join().unwrap();
+	}
+}
+
+/// A server that runs on a virtual clock.
+///
+/// Nothing runs in the background, a frame is
+/// only run when the caller asks for one. Each
+/// frame advances the clock by exactly one tick,
+/// and all timers run off the same clock, so a
+/// given sequence of inputs always plays out the
+/// same way. Frames can be run as quickly as the
+/// caller likes, which makes this useful for
+/// tests and for simulating faster than real time.
+pub struct SteppedServer {
+	pub world: World,
+	dispatcher: Dispatcher<'static, 'static>,
+	timers: PeriodicTimers,
+	start: Instant,
+	tick: Duration,
+	frame: u32,
+}
+
+impl SteppedServer {
+	/// The time of the most recent frame.
+	pub fn now(&self) -> Instant {
+		self.start + self.tick * self.frame
+	}
+
+	/// The number of frames that have been run.
+	pub fn frame(&self) -> u32 {
+		self.frame
+	}
+
+	/// The length of a single frame.
+	pub fn tick(&self) -> Duration {
+		self.tick
+	}
+
+	/// Advance the clock by one tick and run a frame.
+	pub fn step(&mut self) {
+		self.frame += 1;
+
+		let now = self.now();
+		self.timers.update(now);
+		run_frame(&mut self.world, &mut self.dispatcher, now);
+
+		self.world.read_resource::<SystemTimings>().end_frame(now);
+	}
+
+	/// Run `n` frames in a row.
+	pub fn step_n(&mut self, n: usize) {
+		for _ in 0..n {
+			self.step();
+		}
+	}
+
+	/// Run as many frames as are needed for
+	/// at least `dur` to pass on the clock.
+	pub fn run_for(&mut self, dur: Duration) {
+		let end = self.now() + dur;
+
+		while self.now() < end {
+			self.step();
+		}
+	}
+
+	/// Queue a timer event to be handled
+	/// during the next frame.
+	pub fn send_timer_event(&self, evt: TimerEvent) {
+		self.timers.send(evt);
 	}
 }
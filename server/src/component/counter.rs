@@ -1,5 +1,6 @@
+use fnv::FnvHashMap;
 use specs::*;
-use types::Score;
+use types::{Score, Team};
 
 #[derive(Clone, Debug, Copy, Component, Default)]
 pub struct PlayersGame(pub u32);
@@ -12,3 +13,9 @@ pub struct TotalDeaths(pub u32);
 
 #[derive(Clone, Debug, Copy, Component, Default)]
 pub struct Earnings(pub Score);
+
+/// The score of each team, for game modes
+/// that keep one. This is only used to
+/// report scores in the server status.
+#[derive(Clone, Debug, Default)]
+pub struct TeamScores(pub FnvHashMap<Team, u32>);
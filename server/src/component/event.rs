@@ -4,7 +4,7 @@ use std::any::Any;
 use std::time::Instant;
 
 use types::collision::Collision;
-use types::{ConnectionId, Position};
+use types::{ConnectionId, Position, Team};
 use utils::timer::TimerEventType;
 
 #[derive(Copy, Clone, Debug, Default, Component)]
@@ -37,6 +37,14 @@ pub struct PlayerSpectate {
 	pub is_spec: bool,
 }
 
+/// A player has left their team to
+/// become a pure spectator.
+#[derive(Copy, Clone, Debug)]
+pub struct PlayerLeaveTeam {
+	pub player: Entity,
+	pub team: Team,
+}
+
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct PlayerTerrainCollision(pub Collision);
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -51,15 +59,3 @@ pub struct TimerEvent {
 	pub instant: Instant,
 	pub data: Option<Box<Any + Send + Sync>>,
 }
-
-impl Default for TimerEvent {
-	fn default() -> Self {
-		use consts::timer::INVALID;
-
-		Self {
-			ty: *INVALID,
-			instant: Instant::now(),
-			data: None,
-		}
-	}
-}
@@ -16,6 +16,13 @@ pub struct IsPowerup;
 #[storage(NullStorage)]
 pub struct IsSpectating;
 
+/// A spectator that isn't on any team, either
+/// from logging in as one or from the
+/// `spectator` command.
+#[derive(Copy, Clone, Debug, Default, Component)]
+#[storage(NullStorage)]
+pub struct IsPureSpectator;
+
 #[derive(Copy, Clone, Debug, Default, Component)]
 #[storage(NullStorage)]
 pub struct IsDead;
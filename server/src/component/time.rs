@@ -1,5 +1,5 @@
 use specs::*;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Debug, Copy)]
 pub struct LastFrame(pub Instant);
@@ -10,6 +10,10 @@ pub struct ThisFrame(pub Instant);
 #[derive(Clone, Debug, Copy)]
 pub struct StartTime(pub Instant);
 
+/// Time between frames.
+#[derive(Clone, Debug, Copy)]
+pub struct TickRate(pub Duration);
+
 #[derive(Clone, Debug, Copy, Component)]
 pub struct LastUpdate(pub Instant);
 
@@ -27,34 +31,3 @@ pub struct LastKeyTime(pub Instant);
 
 #[derive(Clone, Debug, Copy, Component)]
 pub struct JoinTime(pub Instant);
-
-impl Default for LastFrame {
-	fn default() -> Self {
-		LastFrame(Instant::now())
-	}
-}
-impl Default for ThisFrame {
-	fn default() -> Self {
-		ThisFrame(Instant::now())
-	}
-}
-impl Default for StartTime {
-	fn default() -> Self {
-		StartTime(Instant::now())
-	}
-}
-impl Default for LastUpdate {
-	fn default() -> Self {
-		LastUpdate(Instant::now())
-	}
-}
-impl Default for MobSpawnTime {
-	fn default() -> Self {
-		MobSpawnTime(Instant::now())
-	}
-}
-impl Default for SpectateStartTime {
-	fn default() -> Self {
-		SpectateStartTime(Instant::now())
-	}
-}
@@ -0,0 +1,7 @@
+/// The only protocol version that the
+/// server understands.
+pub const PROTOCOL_VERSION: u8 = 5;
+
+/// Longer names are cut down to this
+/// many characters.
+pub const MAX_NAME_LENGTH: usize = 20;
@@ -0,0 +1,12 @@
+use types::{Distance, Position};
+
+lazy_static! {
+	/// The map is centred on the origin and
+	/// goes this far out along each axis.
+	pub static ref MAP_SIZE: Position =
+		Position::new(Distance::new(16384.0), Distance::new(8192.0));
+	/// Planes are kept at least this close
+	/// to the origin along each axis.
+	pub static ref PLANE_BOUNDS: Position =
+		Position::new(Distance::new(16352.0), Distance::new(8160.0));
+}
@@ -1,7 +1,13 @@
 mod atomic;
+mod login;
+mod map;
+mod spectate;
 mod terrain;
 
 pub mod timer;
 
 pub use self::atomic::SHUTDOWN;
+pub use self::login::{MAX_NAME_LENGTH, PROTOCOL_VERSION};
+pub use self::map::{MAP_SIZE, PLANE_BOUNDS};
+pub use self::spectate::SPECTATOR_SESSION;
 pub use self::terrain::TERRAIN;
@@ -0,0 +1,4 @@
+/// Logging in with this as the session token
+/// joins the game as a pure spectator, without
+/// being put on a team.
+pub const SPECTATOR_SESSION: &'static str = "spectator";
@@ -1,6 +1,8 @@
+use rayon::ThreadPool;
 use specs::*;
 
 use std::any::Any;
+use std::sync::Arc;
 
 use dispatch::sysbuilder::*;
 use dispatch::sysinfo::*;
@@ -56,6 +58,14 @@ impl<'a, 'b> Builder<'a, 'b> {
 		}
 	}
 
+	/// Run systems on `pool` when the
+	/// dispatcher runs them in parallel.
+	pub fn with_pool(self, pool: Arc<ThreadPool>) -> Self {
+		Self {
+			builder: self.builder.with_pool(pool),
+		}
+	}
+
 	pub fn inner(self) -> DispatcherBuilder<'a, 'b> {
 		self.builder
 	}
@@ -1,8 +1,10 @@
 mod sysbuilder;
 mod sysinfo;
 mod syswrapper;
+mod timings;
 
 mod builder;
 
 pub use self::builder::Builder;
 pub use self::sysinfo::*;
+pub use self::timings::SystemTimings;
@@ -1,6 +1,7 @@
 use dispatch::sysinfo::*;
 use shred::*;
 
+use dispatch::timings::SystemTimings;
 use metrics::MetricsHandler;
 use std::time::Instant;
 
@@ -12,6 +13,7 @@ where
 	T::SystemData: DynamicSystemData<'a>,
 {
 	pub metrics: ReadExpect<'a, MetricsHandler>,
+	pub timings: ReadExpect<'a, SystemTimings>,
 	pub inner: T::SystemData,
 }
 
@@ -24,12 +26,14 @@ where
 
 	fn setup(acc: &Self::Accessor, res: &mut Resources) {
 		<ReadExpect<'a, MetricsHandler> as SystemData<'a>>::setup(res);
+		<ReadExpect<'a, SystemTimings> as SystemData<'a>>::setup(res);
 		T::SystemData::setup(acc, res);
 	}
 
 	fn fetch(acc: &Self::Accessor, res: &'a Resources) -> Self {
 		Self {
 			metrics: <ReadExpect<'a, MetricsHandler> as SystemData<'a>>::fetch(res),
+			timings: <ReadExpect<'a, SystemTimings> as SystemData<'a>>::fetch(res),
 			inner: T::SystemData::fetch(acc, res),
 		}
 	}
@@ -47,7 +51,11 @@ where
 	}
 
 	fn run(&mut self, data: Self::SystemData) {
-		let SystemWrapperData { metrics, inner } = data;
+		let SystemWrapperData {
+			metrics,
+			timings,
+			inner,
+		} = data;
 
 		let start = Instant::now();
 
@@ -55,7 +63,8 @@ where
 
 		let time = Instant::now() - start;
 
-		metrics.time_duration(T::name(), time).unwrap();
+		metrics.time_duration_labelled("system-time", Some(("system", T::name())), time);
+		timings.record(T::name(), time);
 
 		trace!(
 			"System '{}' took {}.{:3} ms",
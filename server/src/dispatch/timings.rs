@@ -0,0 +1,115 @@
+use fnv::FnvHashMap;
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often the slow systems report is logged.
+fn report_interval() -> Duration {
+	Duration::from_secs(60)
+}
+
+/// Systems that have taken longer than this
+/// to run since the last report show up in it.
+fn slow_threshold() -> Duration {
+	Duration::from_millis(2)
+}
+
+/// Number of systems listed when a single
+/// frame goes over budget.
+const FRAME_REPORT_LEN: usize = 5;
+
+#[derive(Default)]
+struct Stats {
+	runs: u32,
+	total: Duration,
+	max: Duration,
+}
+
+#[derive(Default)]
+struct Timings {
+	frame: Vec<(&'static str, Duration)>,
+	window: FnvHashMap<&'static str, Stats>,
+	window_start: Option<Instant>,
+}
+
+fn millis(d: Duration) -> f64 {
+	d.as_secs() as f64 * 1000.0 + d.subsec_nanos() as f64 / 1e6
+}
+
+/// How long each system has taken to run.
+///
+/// Every system wrapper records into this,
+/// and it is used to log which systems were
+/// responsible when a frame goes over budget.
+#[derive(Default)]
+pub struct SystemTimings(Mutex<Timings>);
+
+impl SystemTimings {
+	pub fn record(&self, name: &'static str, time: Duration) {
+		let mut timings = self.0.lock().unwrap();
+		timings.frame.push((name, time));
+
+		let stats = timings.window.entry(name).or_insert_with(Stats::default);
+		stats.runs += 1;
+		stats.total += time;
+		if time > stats.max {
+			stats.max = time;
+		}
+	}
+
+	/// Log the slowest systems in the
+	/// current frame.
+	pub fn log_frame(&self) {
+		let mut timings = self.0.lock().unwrap();
+		let frame = &mut timings.frame;
+
+		frame.sort_by(|a, b| b.1.cmp(&a.1));
+
+		for &(name, time) in frame.iter().take(FRAME_REPORT_LEN) {
+			warn!(
+				target: "server",
+				"System '{}' took {:.2} ms",
+				name,
+				millis(time)
+			);
+		}
+	}
+
+	/// Called once every frame, after all
+	/// systems have run. Logs the slow systems
+	/// report if it is due.
+	pub fn end_frame(&self, now: Instant) {
+		let mut guard = self.0.lock().unwrap();
+		let timings = &mut *guard;
+
+		timings.frame.clear();
+
+		let start = *timings.window_start.get_or_insert(now);
+		if now - start < report_interval() {
+			return;
+		}
+
+		{
+			let mut slow = timings
+				.window
+				.iter()
+				.filter(|&(_, stats)| stats.max > slow_threshold())
+				.collect::<Vec<_>>();
+			slow.sort_by(|a, b| b.1.max.cmp(&a.1.max));
+
+			for (name, stats) in slow {
+				info!(
+					target: "server",
+					"Slow system '{}': max {:.2} ms, mean {:.3} ms over {} runs",
+					name,
+					millis(stats.max),
+					millis(stats.total) / stats.runs as f64,
+					stats.runs
+				);
+			}
+		}
+
+		timings.window.clear();
+		timings.window_start = Some(now);
+	}
+}
@@ -4,8 +4,6 @@ use types::*;
 
 use airmash_protocol::client::Chat;
 use airmash_protocol::server::{ChatPublic, ServerPacket};
-use airmash_protocol::to_bytes;
-use websocket::OwnedMessage;
 
 pub struct ChatHandler {
 	reader: Option<ReaderId<(ConnectionId, Chat)>>,
@@ -46,13 +44,11 @@ impl<'a> System<'a> for ChatHandler {
 			};
 
 			let chat = ChatPublic {
-				id: player,
+				id: data.conns.entity_ids().wire(player),
 				text: evt.1.text.clone(),
 			};
 
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::ChatPublic(chat)).unwrap(),
-			));
+			data.conns.send_to_all(ServerPacket::ChatPublic(chat));
 		}
 	}
 }
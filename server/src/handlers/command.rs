@@ -3,9 +3,8 @@ use specs::*;
 use types::*;
 
 use protocol::client::Command;
-use protocol::server::{PlayerFlag, PlayerRespawn, PlayerType};
-use protocol::{to_bytes, FlagCode, ServerPacket, Upgrades as ProtocolUpgrades};
-use websocket::OwnedMessage;
+use protocol::server::{PlayerFlag, PlayerReteam, PlayerReteamPlayer, PlayerRespawn, PlayerType};
+use protocol::{FlagCode, ServerPacket, Upgrades as ProtocolUpgrades};
 
 pub struct CommandHandler {
 	reader: Option<ReaderId<(ConnectionId, Command)>>,
@@ -19,6 +18,9 @@ pub struct CommandHandlerData<'a> {
 	flags: WriteStorage<'a, Flag>,
 	isspec: WriteStorage<'a, IsSpectating>,
 	isdead: WriteStorage<'a, IsDead>,
+	pure_spec: WriteStorage<'a, IsPureSpectator>,
+	teams: WriteStorage<'a, Team>,
+	gamemode: GameModeWriter<'a, GameMode>,
 
 	pos: WriteStorage<'a, Position>,
 	rot: WriteStorage<'a, Rotation>,
@@ -52,13 +54,14 @@ impl<'a> System<'a> for CommandHandler {
 				None => continue,
 			};
 
+			let id = data.conns.entity_ids().wire(player);
 			let packet;
 
 			if evt.1.com == "flag" {
 				let flag = Flag::from_str(&evt.1.data).unwrap_or(FlagCode::UnitedNations);
 
 				packet = ServerPacket::PlayerFlag(PlayerFlag {
-					id: player,
+					id,
 					flag: flag,
 				});
 
@@ -73,6 +76,17 @@ impl<'a> System<'a> for CommandHandler {
 					None => continue,
 				};
 
+				// Pure spectators join a team again
+				// once they decide to play.
+				if data.pure_spec.remove(player).is_some() {
+					let team = data.gamemode.get_mut().assign_team(player, id.0);
+					data.teams.insert(player, team).unwrap();
+
+					data.conns.broadcast(&ServerPacket::PlayerReteam(PlayerReteam {
+						players: vec![PlayerReteamPlayer { id, team }],
+					}));
+				}
+
 				*data.pos.get_mut(player).unwrap() = Position::default();
 				*data.vel.get_mut(player).unwrap() = Velocity::default();
 				*data.rot.get_mut(player).unwrap() = Rotation::default();
@@ -82,22 +96,20 @@ impl<'a> System<'a> for CommandHandler {
 				data.isspec.remove(player);
 				data.isdead.remove(player);
 
-				data.conns.send_to_all(OwnedMessage::Binary(
-					to_bytes(&ServerPacket::PlayerRespawn(PlayerRespawn {
-						id: player,
+				data.conns
+					.send_to_all(ServerPacket::PlayerRespawn(PlayerRespawn {
+						id,
 						pos: *data.pos.get(player).unwrap(),
 						rot: *data.rot.get(player).unwrap(),
 						upgrades: ProtocolUpgrades::default(),
-					})).unwrap(),
-				));
+					}));
 
-				packet = ServerPacket::PlayerType(PlayerType { id: player, ty: ty });
+				packet = ServerPacket::PlayerType(PlayerType { id, ty: ty });
 			} else {
 				continue;
 			}
 
-			data.conns
-				.send_to_all(OwnedMessage::Binary(to_bytes(&packet).unwrap()));
+			data.conns.send_to_all(packet);
 		}
 	}
 }
@@ -1,15 +1,17 @@
 use airmash_protocol::client::Login;
-use airmash_protocol::{FlagCode, PlaneType};
+use airmash_protocol::server::Error;
+use airmash_protocol::{ErrorType, FlagCode, PlaneType, ServerPacket};
 use specs::*;
 use uuid::Uuid;
 
 use std::str::FromStr;
-use std::time::Instant;
 
 use component::channel::*;
 use component::counter::PlayersGame;
 use component::event::PlayerJoin;
 use component::time::*;
+use consts::{MAX_NAME_LENGTH, PROTOCOL_VERSION, SPECTATOR_SESSION};
+use metrics::MetricsHandler;
 use types::*;
 use utils::geoip;
 
@@ -33,17 +35,51 @@ pub struct LoginSystemData<'a> {
 	pub associated_conn: WriteStorage<'a, AssociatedConnection>,
 	pub lastupdate: WriteStorage<'a, LastUpdate>,
 	pub isplayer: WriteStorage<'a, IsPlayer>,
+	pub pure_spec: WriteStorage<'a, IsPureSpectator>,
 	pub pingdata: WriteStorage<'a, PingData>,
+	pub history: WriteStorage<'a, PositionHistory>,
 	pub playersgame: Write<'a, PlayersGame>,
 	pub lastshot: WriteStorage<'a, LastShotTime>,
 	pub energyregen: WriteStorage<'a, EnergyRegen>,
 
-	pub startime: Read<'a, StartTime>,
+	pub startime: ReadExpect<'a, StartTime>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 	pub player_join: Write<'a, OnPlayerJoin>,
 	pub config: Read<'a, Config>,
+	pub limits: Read<'a, ConnectionLimits>,
+	pub metrics: ReadExpect<'a, MetricsHandler>,
 	pub gamemode: GameModeWriter<'a, GameMode>,
 }
 
+/// Whether `c` shouldn't be allowed in a name,
+/// either because it can't be displayed or
+/// because it changes how nearby text is shown.
+fn is_hidden(c: char) -> bool {
+	match c {
+		'\u{200B}'...'\u{200F}' | '\u{202A}'...'\u{202E}' | '\u{2060}'...'\u{206F}' => true,
+		'\u{FEFF}' => true,
+		c => c.is_control(),
+	}
+}
+
+/// Remove hidden characters and extra
+/// whitespace from a name, and cut it
+/// down to `MAX_NAME_LENGTH` characters.
+fn sanitize_name(name: &str) -> String {
+	let name = name
+		.split_whitespace()
+		.map(|word| word.chars().filter(|&c| !is_hidden(c)).collect::<String>())
+		.filter(|word| !word.is_empty())
+		.collect::<Vec<_>>()
+		.join(" ");
+
+	name.chars()
+		.take(MAX_NAME_LENGTH)
+		.collect::<String>()
+		.trim_right()
+		.to_owned()
+}
+
 struct LoginInfo<'a> {
 	pub id: Entity,
 	pub login: &'a Login,
@@ -62,21 +98,156 @@ impl LoginHandler {
 		Self { reader: None }
 	}
 
+	/// Whether `conn` is allowed to log in another
+	/// player from its address.
+	fn within_limits<'a>(data: &LoginSystemData<'a>, conn: ConnectionId) -> bool {
+		let max = match data.limits.max_players_per_ip {
+			Some(max) => max,
+			None => return true,
+		};
+		let addr = data.conns.0[&conn].addr;
+
+		let players = data
+			.conns
+			.iter()
+			.filter(|c| c.ty == ConnectionType::Primary && c.player.is_some())
+			.filter(|c| c.addr == addr)
+			.count();
+
+		players < max
+	}
+
+	/// Make `name` different from the names of
+	/// all other players by adding a number to
+	/// the end of it.
+	fn unique_name<'a>(data: &LoginSystemData<'a>, name: String) -> String {
+		let taken = |name: &str| {
+			let name = name.to_lowercase();
+
+			(&data.name, &data.isplayer)
+				.join()
+				.any(|(other, _)| other.0.to_lowercase() == name)
+		};
+
+		if !taken(&name) {
+			return name;
+		}
+
+		(2..)
+			.map(|i| {
+				let suffix = format!("#{}", i);
+				let base = name
+					.chars()
+					.take(MAX_NAME_LENGTH - suffix.len())
+					.collect::<String>();
+
+				base.trim_right().to_owned() + &suffix
+			})
+			.find(|name| !taken(name))
+			.unwrap()
+	}
+
+	/// Send `error` to the connection and close it.
+	fn reject<'a>(data: &mut LoginSystemData<'a>, conn: ConnectionId, error: ErrorType) {
+		let packet = ServerPacket::Error(Error { error });
+		let msg = data.conns.serialize(&packet);
+
+		if let Some(mut conn) = data.conns.0.remove(&conn) {
+			match msg {
+				Some(msg) => conn.close_with(msg.into()),
+				None => conn.close(),
+			}
+		}
+	}
+
 	fn do_login<'a>(data: &mut LoginSystemData<'a>, conn: ConnectionId, login: Login) {
-		let entity = data.entities.create();
+		// The connection may have been rejected
+		// after this login was sent.
+		if !data.conns.0.contains_key(&conn) {
+			return;
+		}
+
+		// A second login on the same connection would
+		// create another player that nothing refers
+		// to. Closing the connection would leave the
+		// first player behind too, so only tell the
+		// client that it did something wrong.
+		if data.conns.0[&conn].player.is_some() {
+			let packet = ServerPacket::Error(Error {
+				error: ErrorType::InvalidLogin,
+			});
+
+			data.metrics
+				.count_labelled("logins-rejected", Some(("reason", "logged-in")), 1);
+			data.conns.send_to(conn, packet);
+			return;
+		}
 
-		if entity.id() > 0xFFFF {
-			error!(
+		if login.protocol != PROTOCOL_VERSION {
+			info!(
 				target: "server",
-				"Entity created with id greater than 0xFFFF. Aborting to avoid sending invalid packets."
+				"Rejected login of {} from {:?}, unsupported protocol version {}",
+				login.name, conn, login.protocol
 			);
-			panic!("Entity created with invalid id.");
+			data.metrics
+				.count_labelled("logins-rejected", Some(("reason", "protocol")), 1);
+
+			Self::reject(data, conn, ErrorType::IncorrectProtocol);
+			return;
 		}
 
+		if !Self::within_limits(data, conn) {
+			info!(
+				target: "server",
+				"Rejected login of {} from {:?}, too many players from {}",
+				login.name, conn, data.conns.0[&conn].addr
+			);
+			data.metrics
+				.count_labelled("logins-rejected", Some(("reason", "player-limit")), 1);
+
+			Self::reject(data, conn, ErrorType::InvalidLogin);
+			return;
+		}
+
+		let name = sanitize_name(&login.name);
+
+		if name.is_empty() {
+			info!(
+				target: "server",
+				"Rejected login from {:?}, invalid name {:?}",
+				conn, login.name
+			);
+			data.metrics
+				.count_labelled("logins-rejected", Some(("reason", "name")), 1);
+
+			Self::reject(data, conn, ErrorType::InvalidLogin);
+			return;
+		}
+
+		let name = Self::unique_name(data, name);
+		let entity = data.entities.create();
+
+		let id = match data.conns.entity_ids().allocate(entity) {
+			Some(id) => id,
+			None => {
+				error!(
+					target: "server",
+					"Rejected login of {} from {:?}, there are no entity ids left",
+					name, conn
+				);
+				data.metrics
+					.count_labelled("logins-rejected", Some(("reason", "no-ids")), 1);
+
+				data.entities.delete(entity).unwrap();
+				Self::reject(data, conn, ErrorType::InvalidLogin);
+				return;
+			}
+		};
+
 		info!(
 			target: "server",
 			"{:?} logging on as {} with id {}",
-			conn, login.name, entity.id()
+			conn, name, id
 		);
 
 		let flag = match FlagCode::from_str(&login.flag) {
@@ -84,6 +255,10 @@ impl LoginHandler {
 			None => geoip::locate(&data.conns.0[&conn].addr).unwrap_or(FlagCode::UnitedNations),
 		};
 
+		if login.session == SPECTATOR_SESSION {
+			data.pure_spec.insert(entity, IsPureSpectator).unwrap();
+		}
+
 		let session = match Uuid::from_str(&login.session) {
 			Ok(s) => Some(s),
 			Err(_) => None,
@@ -95,7 +270,7 @@ impl LoginHandler {
 		data.energy.insert(entity, Energy::new(1.0)).unwrap();
 		data.health.insert(entity, Health::new(1.0)).unwrap();
 		data.keystate.insert(entity, KeyState::default()).unwrap();
-		data.name.insert(entity, Name(login.name)).unwrap();
+		data.name.insert(entity, Name(name)).unwrap();
 		data.session.insert(entity, Session(session)).unwrap();
 		data.powerups.insert(entity, Powerups::default()).unwrap();
 		data.upgrades.insert(entity, Upgrades::default()).unwrap();
@@ -105,10 +280,13 @@ impl LoginHandler {
 			.insert(entity, AssociatedConnection(conn))
 			.unwrap();
 		data.lastupdate
-			.insert(entity, LastUpdate(Instant::now()))
+			.insert(entity, LastUpdate(data.thisframe.0))
 			.unwrap();
 		data.isplayer.insert(entity, IsPlayer {}).unwrap();
 		data.pingdata.insert(entity, PingData::default()).unwrap();
+		data.history
+			.insert(entity, PositionHistory::default())
+			.unwrap();
 		data.lastshot
 			.insert(entity, LastShotTime(data.startime.0))
 			.unwrap();
@@ -1,9 +1,8 @@
 use airmash_protocol::server::PlayerLeave;
-use airmash_protocol::{to_bytes, ServerPacket};
+use airmash_protocol::ServerPacket;
 use shrev::*;
 use specs::*;
 use types::*;
-use websocket::OwnedMessage;
 
 use component::channel::{OnClose, OnPlayerLeave};
 use component::counter::PlayersGame;
@@ -46,16 +45,20 @@ impl<'a> System<'a> for OnCloseHandler {
 	) {
 		if let Some(ref mut reader) = self.reader {
 			for evt in channel.read(reader) {
-				let (player, ty) = {
-					let conn = connections.0.get(&evt.conn).unwrap_or_else(|| {
-						error!(
+				let (player, ty) = match connections.0.get(&evt.conn) {
+					Some(conn) => (conn.player, conn.ty),
+					// Connections that were dropped by the
+					// server (e.g. slow consumers) will have
+					// already been removed by the time the
+					// socket actually closes.
+					None => {
+						debug!(
 							target: "server",
-							"Attempted to close non-existent connection {:?}",
+							"Connection {:?} was already closed",
 							evt.conn
 						);
-						panic!("Connection {:?} not found", evt.conn);
-					});
-					(conn.player, conn.ty)
+						continue;
+					}
 				};
 
 				if ty == ConnectionType::Primary {
@@ -64,13 +67,14 @@ impl<'a> System<'a> for OnCloseHandler {
 						players.0 -= 1;
 
 						// Send out PlayerLeave message
-						let player_leave = PlayerLeave { id: ent };
-						connections.send_to_all(OwnedMessage::Binary(
-							to_bytes(&ServerPacket::PlayerLeave(player_leave)).unwrap(),
-						));
+						let player_leave = PlayerLeave {
+							id: connections.entity_ids().wire(ent),
+						};
+						connections.send_to_all(ServerPacket::PlayerLeave(player_leave));
 
 						onleave.single_write(EvtPlayerLeave(ent));
 						// Delete player entity
+						connections.entity_ids().release(ent);
 						entities.delete(ent).unwrap();
 
 						// Log
@@ -5,6 +5,7 @@ use types::*;
 use std::mem;
 
 use dispatch::SystemInfo;
+use metrics::MetricsHandler;
 use types::event::ConnectionOpen;
 
 use systems::PacketHandler;
@@ -23,6 +24,8 @@ impl<'a> System<'a> for OnOpenHandler {
 	type SystemData = (
 		Read<'a, EventChannel<ConnectionOpen>>,
 		Write<'a, Connections>,
+		Read<'a, ConnectionLimits>,
+		ReadExpect<'a, MetricsHandler>,
 	);
 
 	fn setup(&mut self, res: &mut Resources) {
@@ -34,12 +37,42 @@ impl<'a> System<'a> for OnOpenHandler {
 		Self::SystemData::setup(res);
 	}
 
-	fn run(&mut self, (channel, mut connections): Self::SystemData) {
+	fn run(&mut self, (channel, mut connections, limits, metrics): Self::SystemData) {
 		if let Some(ref mut reader) = self.reader {
 			for evt in channel.read(reader) {
 				let sink = mem::replace(&mut *evt.sink.lock().unwrap(), None);
 
+				let reason = if !limits.allows_origin(evt.origin.as_ref().map(|s| &**s)) {
+					Some("origin")
+				} else if limits
+					.max_connections_per_ip
+					.map(|max| connections.iter().filter(|c| c.addr == evt.addr).count() >= max)
+					.unwrap_or(false)
+				{
+					Some("ip-limit")
+				} else {
+					None
+				};
+
 				connections.add(evt.conn, sink.unwrap(), evt.addr, evt.origin.clone());
+
+				let reason = match reason {
+					Some(reason) => reason,
+					None => continue,
+				};
+
+				info!(
+					target: "server",
+					"Rejected connection {:?} from {} with origin {:?} ({})",
+					evt.conn, evt.addr, evt.origin, reason
+				);
+				metrics.count_labelled("connections-rejected", Some(("reason", reason)), 1);
+
+				// The close event for this connection
+				// will find that it's already gone.
+				if let Some(mut conn) = connections.0.remove(&evt.conn) {
+					conn.close();
+				}
 			}
 		}
 	}
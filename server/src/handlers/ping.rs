@@ -1,13 +1,10 @@
 use specs::*;
 use types::*;
 
-use std::time::Instant;
-
 use consts::timer::PING_DISPATCH;
 
 use protocol::server::Ping as ServerPing;
-use protocol::{to_bytes, ServerPacket};
-use OwnedMessage;
+use protocol::ServerPacket;
 
 use component::channel::{OnTimerEvent, OnTimerEventReader};
 use component::time::*;
@@ -21,8 +18,8 @@ pub struct PingTimerHandlerData<'a> {
 	pub entities: Entities<'a>,
 	pub conns: Read<'a, Connections>,
 	pub channel: Read<'a, OnTimerEvent>,
-	pub thisframe: Read<'a, ThisFrame>,
-	pub starttime: Read<'a, StartTime>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
+	pub starttime: ReadExpect<'a, StartTime>,
 }
 
 impl PingTimerHandler {
@@ -41,7 +38,7 @@ impl<'a> System<'a> for PingTimerHandler {
 	}
 
 	fn run(&mut self, (data, mut pingdata): Self::SystemData) {
-		let clock = (Instant::now() - data.starttime.0).to_clock();
+		let clock = (data.thisframe.0 - data.starttime.0).to_clock();
 
 		for evt in data.channel.read(self.reader.as_mut().unwrap()) {
 			if evt.ty == *PING_DISPATCH {
@@ -55,12 +52,10 @@ impl<'a> System<'a> for PingTimerHandler {
 
 					data.conns.send_to_player(
 						ent,
-						OwnedMessage::Binary(
-							to_bytes(&ServerPacket::Ping(ServerPing {
-								clock,
-								num: ping.idx,
-							})).unwrap(),
-						),
+						ServerPacket::Ping(ServerPing {
+							clock,
+							num: ping.idx,
+						}),
 					)
 				});
 		}
@@ -4,12 +4,8 @@ use shrev::*;
 use specs::*;
 use types::*;
 
-use std::time::Instant;
-
 use airmash_protocol::client::Pong;
 use airmash_protocol::server::{PingResult, ServerPacket};
-use airmash_protocol::to_bytes;
-use websocket::OwnedMessage;
 
 pub struct PongHandler {
 	reader: Option<ReaderId<(ConnectionId, Pong)>>,
@@ -19,7 +15,7 @@ pub struct PongHandler {
 pub struct PongHandlerData<'a> {
 	channel: Read<'a, EventChannel<(ConnectionId, Pong)>>,
 	conns: Read<'a, Connections>,
-	thisframe: Read<'a, ThisFrame>,
+	thisframe: ReadExpect<'a, ThisFrame>,
 	playersgame: Read<'a, PlayersGame>,
 }
 
@@ -42,7 +38,7 @@ impl<'a> System<'a> for PongHandler {
 	}
 
 	fn run(&mut self, (data, mut pingdata): Self::SystemData) {
-		let now = Instant::now();
+		let now = data.thisframe.0;
 
 		for evt in data.channel.read(self.reader.as_mut().unwrap()) {
 			let player = match data.conns.0.get(&evt.0) {
@@ -68,10 +64,7 @@ impl<'a> System<'a> for PongHandler {
 				players_total: data.playersgame.0,
 			};
 
-			data.conns.send_to(
-				evt.0,
-				OwnedMessage::Binary(to_bytes(&ServerPacket::PingResult(result)).unwrap()),
-			);
+			data.conns.send_to(evt.0, ServerPacket::PingResult(result));
 		}
 	}
 }
@@ -4,8 +4,6 @@ use types::*;
 
 use airmash_protocol::client::Say;
 use airmash_protocol::server::{ChatSay, ServerPacket};
-use airmash_protocol::to_bytes;
-use websocket::OwnedMessage;
 
 pub struct SayHandler {
 	reader: Option<ReaderId<(ConnectionId, Say)>>,
@@ -46,13 +44,11 @@ impl<'a> System<'a> for SayHandler {
 			};
 
 			let chat = ChatSay {
-				id: player,
+				id: data.conns.entity_ids().wire(player),
 				text: evt.1.text.clone(),
 			};
 
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::ChatSay(chat)).unwrap(),
-			));
+			data.conns.send_to_all(ServerPacket::ChatSay(chat));
 		}
 	}
 }
@@ -2,20 +2,19 @@ use specs::*;
 use types::*;
 
 use consts::timer::SCORE_BOARD;
+use consts::MAP_SIZE;
 
 use component::channel::{OnTimerEvent, OnTimerEventReader};
 use component::flag::{IsDead, IsPlayer, IsSpectating};
 use component::time::JoinTime;
 
 use protocol::server::{ScoreBoard, ScoreBoardData, ScoreBoardRanking};
-use protocol::{to_bytes, ServerPacket};
-use OwnedMessage;
+use protocol::ServerPacket;
 
 use std::cmp::{Ordering, Reverse};
 
 lazy_static! {
-	static ref SPEC_POSITION: Position =
-		Position::new(Distance::new(-16384.0), Distance::new(-8192.0));
+	static ref SPEC_POSITION: Position = *MAP_SIZE * -1.0;
 }
 
 pub struct ScoreBoardTimerHandler {
@@ -58,6 +57,8 @@ impl<'a> System<'a> for ScoreBoardTimerHandler {
 				continue;
 			}
 
+			let ids = data.conns.entity_ids();
+
 			let mut packet_data = (
 				&*data.entities,
 				&data.scores,
@@ -68,7 +69,7 @@ impl<'a> System<'a> for ScoreBoardTimerHandler {
 				.map(|(ent, score, level, _, join_time)| {
 					(
 						ScoreBoardData {
-							id: ent,
+							id: ids.wire(ent),
 							score: *score,
 							level: *level,
 						},
@@ -101,7 +102,10 @@ impl<'a> System<'a> for ScoreBoardTimerHandler {
 						(ent, *pos)
 					}
 				})
-				.map(|(ent, pos)| ScoreBoardRanking { id: ent, pos: pos })
+				.map(|(ent, pos)| ScoreBoardRanking {
+					id: ids.wire(ent),
+					pos: pos,
+				})
 				.collect::<Vec<ScoreBoardRanking>>();
 
 			let score_board = ScoreBoard {
@@ -109,9 +113,8 @@ impl<'a> System<'a> for ScoreBoardTimerHandler {
 				rankings: rankings,
 			};
 
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::ScoreBoard(score_board)).unwrap(),
-			));
+			data.conns
+				.send_to_all(ServerPacket::ScoreBoard(score_board));
 		}
 	}
 }
@@ -1,3 +1,4 @@
+use component::time::ThisFrame;
 use consts::SHUTDOWN;
 use specs::*;
 use types::*;
@@ -7,8 +8,7 @@ use std::time::{Duration, Instant};
 
 use protocol::server::ServerMessage;
 use protocol::ServerMessageType;
-use protocol::{to_bytes, ServerPacket};
-use websocket::OwnedMessage;
+use protocol::ServerPacket;
 
 use std::process;
 
@@ -18,12 +18,12 @@ pub struct SignalHandler {
 }
 
 impl<'a> System<'a> for SignalHandler {
-	type SystemData = Read<'a, Connections>;
+	type SystemData = (Read<'a, Connections>, ReadExpect<'a, ThisFrame>);
 
-	fn run(&mut self, data: Self::SystemData) {
+	fn run(&mut self, (data, thisframe): Self::SystemData) {
 		if SHUTDOWN.swap(false, Ordering::Relaxed) {
 			if self.time.is_none() {
-				self.time = Some(Instant::now());
+				self.time = Some(thisframe.0);
 
 				let msg = ServerMessage {
 					duration: 15000,
@@ -31,9 +31,7 @@ impl<'a> System<'a> for SignalHandler {
 					text: "Server shutting down in 30 seconds!".to_string(),
 				};
 
-				data.send_to_all(OwnedMessage::Binary(
-					to_bytes(&ServerPacket::ServerMessage(msg)).unwrap(),
-				));
+				data.send_to_all(ServerPacket::ServerMessage(msg));
 
 				info!(
 					target:"server",
@@ -47,7 +45,7 @@ impl<'a> System<'a> for SignalHandler {
 		} else if self.time.is_some() {
 			let t = self.time.unwrap();
 
-			if Instant::now() - t > Duration::from_secs(30) {
+			if thisframe.0 - t > Duration::from_secs(30) {
 				process::exit(0);
 			}
 		}
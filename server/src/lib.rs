@@ -1,21 +1,17 @@
 #![allow(dead_code)]
-#![feature(optin_builtin_traits)]
 #![feature(specialization)]
 
 // Crates with macros
 #[macro_use]
 extern crate log;
 #[macro_use]
-extern crate dimensioned;
-#[macro_use]
 extern crate specs_derive;
 #[macro_use]
 extern crate shred_derive;
 #[macro_use]
 extern crate lazy_static;
-#[cfg_attr(feature = "serde", macro_use)]
-#[cfg(feature = "serde")]
-extern crate serde;
+
+pub extern crate airmash_protocol as protocol;
 
 // Regular Dependencies
 extern crate bit_field;
@@ -23,7 +19,7 @@ extern crate ctrlc;
 extern crate fnv;
 extern crate htmlescape;
 extern crate hyper;
-extern crate phf;
+extern crate native_tls;
 extern crate rand;
 extern crate rayon;
 extern crate shred;
@@ -42,6 +38,8 @@ mod builder;
 mod dispatch;
 mod handlers;
 mod metrics;
+mod proxy;
+mod raw;
 mod server;
 mod timeloop;
 mod timers;
@@ -49,20 +47,28 @@ mod utils;
 
 pub mod component;
 pub mod consts;
-pub mod protocol;
+pub mod options;
+pub mod replay;
 pub mod systems;
+pub mod testing;
 pub mod types;
 
 use protocol as airmash_protocol;
 
 pub use websocket::OwnedMessage;
 
-pub use builder::AirmashServer;
+pub use builder::{AirmashServer, SteppedServer};
+pub use raw::RawTransport;
+#[cfg(unix)]
+pub use raw::UnixTransport;
+pub use server::{ListeningTransport, WebsocketTransport};
 pub use metrics::MetricsHandler;
+pub use options::{OptionsError, ServerOptions};
+pub use proxy::{ForwardedHeader, IpNet, TrustedProxies, TrustedProxy};
 
 pub use dispatch::{Builder, SystemDeps, SystemInfo};
 
 pub use types::{
 	Accel, AccelScalar, Config, Connections, Distance, FutureDispatcher, GameMode, GameModeWriter,
-	KeyState, Level, Mob, Name, Plane, Position, Speed, Team, Time, Velocity,
+	KeyState, Level, Mob, Name, Plane, Position, Speed, Team, Time, Transport, Velocity,
 };
@@ -1,96 +1,280 @@
-/*extern crate cadence;
+//! Server metrics, exported in the
+//! Prometheus text format.
+//!
+//! Metrics are kept in memory until they
+//! are scraped from the HTTP endpoint started
+//! by [`MetricsHandler::serve`]. Names are
+//! prefixed with `airmash_` and anything that
+//! isn't valid within a Prometheus name is
+//! replaced with an underscore.
+//!
+//! Every metric is updated atomically on its
+//! own. The registry is only locked for writing
+//! the first time a metric is used, so systems
+//! running in parallel don't wait on each other.
 
-use std::error::Error;
-use std::net::UdpSocket;
+use hyper::header::ContentType;
+use hyper::server::{Request, Response, Server};
+use hyper::status::StatusCode;
+use hyper::uri::RequestUri;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io;
+use std::net::ToSocketAddrs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
 use std::time::Duration;
 
-pub use self::cadence::prelude::*;
-use self::cadence::{BufferedUdpMetricSink, QueuingMetricSink, StatsdClient, DEFAULT_PORT};
+const PREFIX: &'static str = "airmash_";
 
-#[derive(Clone)]
-pub struct MetricsHandler(StatsdClient);
+/// Upper bounds of the histogram buckets, in
+/// seconds. Most timings are compared against
+/// the 16.67 ms frame budget so the buckets are
+/// concentrated below that.
+const BUCKETS: [f64; 11] = [
+	0.0005, 0.001, 0.0025, 0.005, 0.01, 0.0167, 0.025, 0.05, 0.1, 0.25, 1.0,
+];
 
-pub fn handler() -> MetricsHandler {
-	let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
-	socket.set_nonblocking(true).unwrap();
+/// A metric name along with its label,
+/// if it has one.
+type Key = (String, Option<(&'static str, String)>);
 
-	let host = ("127.0.0.1", DEFAULT_PORT);
-	let udp_sink = BufferedUdpMetricSink::from(host, socket).unwrap();
-	let sink = QueuingMetricSink::from(udp_sink);
-	MetricsHandler(StatsdClient::from_sink("airmash", sink))
+#[derive(Default)]
+struct Histogram {
+	buckets: [AtomicUsize; 11],
+	count: AtomicUsize,
+	/// Kept in nanoseconds so that it
+	/// can be added to atomically.
+	sum: AtomicUsize,
 }
 
-impl MetricsHandler {
-	pub fn time_duration(&self, _name: &str, _time: Duration) -> Result<(), Box<Error>> {
-		//self.0.time_duration(&name.replace("::", "."), time)?;
-		Ok(())
+impl Histogram {
+	fn observe(&self, d: Duration) {
+		let val = seconds(d);
+
+		for (bucket, bound) in self.buckets.iter().zip(BUCKETS.iter()) {
+			if val <= *bound {
+				bucket.fetch_add(1, Ordering::Relaxed);
+			}
+		}
+
+		self.count.fetch_add(1, Ordering::Relaxed);
+		self.sum.fetch_add(nanos(d), Ordering::Relaxed);
 	}
+}
 
-	pub fn count(&self, _name: &str, _count: i64) -> Result<(), Box<Error>> {
-		//self.0.count(&name.replace("::", "."), count)?;
-		Ok(())
+type Metrics<T> = RwLock<BTreeMap<Key, T>>;
+
+#[derive(Default)]
+struct Registry {
+	counters: Metrics<AtomicUsize>,
+	gauges: Metrics<Mutex<f64>>,
+	histograms: Metrics<Histogram>,
+}
+
+/// Run `f` on the metric stored under `key`,
+/// creating it first if it doesn't exist yet.
+fn update<T: Default, F: Fn(&T)>(metrics: &Metrics<T>, key: Key, f: F) {
+	if let Some(metric) = metrics.read().unwrap().get(&key) {
+		return f(metric);
 	}
-}*/
 
-#![allow(unused)]
+	f(metrics.write().unwrap().entry(key).or_insert_with(T::default))
+}
 
-use std::fs::File;
-use std::io::{Error, Write};
-use std::sync::mpsc::*;
-use std::sync::{Arc, Mutex};
-use std::thread;
+fn sanitize(name: &str) -> String {
+	let mut out = String::with_capacity(PREFIX.len() + name.len());
+	out.push_str(PREFIX);
+	out.extend(name.chars().map(|c| match c {
+		'a'...'z' | 'A'...'Z' | '0'...'9' | '_' | ':' => c,
+		_ => '_',
+	}));
+	out
+}
 
-use std::time::Duration;
+fn escape(val: &str) -> String {
+	val.replace('\\', "\\\\")
+		.replace('"', "\\\"")
+		.replace('\n', "\\n")
+}
+
+fn key(name: &str, label: Option<(&'static str, &str)>) -> Key {
+	(sanitize(name), label.map(|(k, v)| (k, escape(v))))
+}
+
+/// Formats the labels of a sample, with an
+/// optional extra label used for histogram
+/// bucket bounds.
+fn labels(label: &Option<(&'static str, String)>, extra: Option<(&str, &str)>) -> String {
+	let mut parts = vec![];
+
+	if let Some((ref k, ref v)) = *label {
+		parts.push(format!("{}=\"{}\"", k, v));
+	}
+	if let Some((k, v)) = extra {
+		parts.push(format!("{}=\"{}\"", k, v));
+	}
+
+	if parts.is_empty() {
+		String::new()
+	} else {
+		format!("{{{}}}", parts.join(","))
+	}
+}
+
+fn seconds(d: Duration) -> f64 {
+	d.as_secs() as f64 + d.subsec_nanos() as f64 * 1e-9
+}
 
-enum Message {
-	Msg(String),
-	End,
+fn nanos(d: Duration) -> usize {
+	(d.as_secs() * 1_000_000_000 + d.subsec_nanos() as u64) as usize
 }
 
-#[derive(Clone)]
+impl Registry {
+	fn render(&self) -> String {
+		let mut out = String::new();
+		let mut last = None;
+
+		let counters = self.counters.read().unwrap();
+		for (&(ref name, ref label), val) in counters.iter() {
+			if last != Some(name) {
+				writeln!(out, "# TYPE {}_total counter", name).unwrap();
+				last = Some(name);
+			}
+			let val = val.load(Ordering::Relaxed);
+			writeln!(out, "{}_total{} {}", name, labels(label, None), val).unwrap();
+		}
+
+		last = None;
+		let gauges = self.gauges.read().unwrap();
+		for (&(ref name, ref label), val) in gauges.iter() {
+			if last != Some(name) {
+				writeln!(out, "# TYPE {} gauge", name).unwrap();
+				last = Some(name);
+			}
+			let val = *val.lock().unwrap();
+			writeln!(out, "{}{} {}", name, labels(label, None), val).unwrap();
+		}
+
+		last = None;
+		let histograms = self.histograms.read().unwrap();
+		for (&(ref name, ref label), hist) in histograms.iter() {
+			if last != Some(name) {
+				writeln!(out, "# TYPE {} histogram", name).unwrap();
+				last = Some(name);
+			}
+
+			for (count, bound) in hist.buckets.iter().zip(BUCKETS.iter()) {
+				let bound = bound.to_string();
+				let le = labels(label, Some(("le", &bound)));
+				let count = count.load(Ordering::Relaxed);
+				writeln!(out, "{}_bucket{} {}", name, le, count).unwrap();
+			}
+
+			let count = hist.count.load(Ordering::Relaxed);
+			let sum = hist.sum.load(Ordering::Relaxed) as f64 * 1e-9;
+			let inf = labels(label, Some(("le", "+Inf")));
+			writeln!(out, "{}_bucket{} {}", name, inf, count).unwrap();
+			writeln!(out, "{}_sum{} {}", name, labels(label, None), sum).unwrap();
+			writeln!(out, "{}_count{} {}", name, labels(label, None), count).unwrap();
+		}
+
+		out
+	}
+}
+
+/// Handle to the metrics registry.
+///
+/// This is available as a resource within
+/// the world. Clones share the same registry.
+#[derive(Clone, Default)]
 pub struct MetricsHandler {
-	send: Arc<Mutex<Sender<Message>>>,
-	thread: Arc<thread::JoinHandle<()>>,
+	registry: Arc<Registry>,
 }
 
 impl MetricsHandler {
-	pub fn time_duration(&self, tag: &str, d: Duration) -> Result<(), Error> {
-		let send = self.send.lock().unwrap().clone();
-		send.send(Message::Msg(format!(
-			"{}: {}.{:03}",
-			tag,
-			d.as_secs() * 1000 + (d.subsec_millis() as u64),
-			d.subsec_micros()
-		))).err();
-		Ok(())
+	/// Add `n` to a counter.
+	pub fn count(&self, name: &str, n: u64) {
+		self.count_labelled(name, None, n);
 	}
 
-	pub fn count(&self, tag: &str, d: i64) -> Result<(), Error> {
-		let send = self.send.lock().unwrap().clone();
-		send.send(Message::Msg(format!("{}: {}", tag, d))).err();
-		Ok(())
+	/// Add `n` to a counter with a label,
+	/// e.g. `("type", "10")`.
+	pub fn count_labelled(&self, name: &str, label: Option<(&'static str, &str)>, n: u64) {
+		update(&self.registry.counters, key(name, label), |c| {
+			c.fetch_add(n as usize, Ordering::Relaxed);
+		});
 	}
-}
 
-impl Drop for MetricsHandler {
-	fn drop(&mut self) {
-		let send = self.send.lock().unwrap().clone();
-		send.send(Message::End).err();
+	/// Set a gauge to `val`.
+	pub fn gauge(&self, name: &str, val: f64) {
+		update(&self.registry.gauges, key(name, None), |g| {
+			*g.lock().unwrap() = val;
+		});
 	}
-}
 
-pub fn handler() -> MetricsHandler {
-	let (send, recv) = channel();
+	/// Record a duration in a histogram,
+	/// measured in seconds.
+	pub fn time_duration(&self, name: &str, d: Duration) {
+		self.time_duration_labelled(name, None, d);
+	}
 
-	let handle = thread::spawn(move || {
-		//let mut file = File::create("logs.txt").unwrap();
-		while let Ok(Message::Msg(_s)) = recv.recv_timeout(Duration::from_secs(3600)) {
-			//writeln!(&mut file, "{}", s).err();
-		}
-	});
+	/// Record a duration in a histogram
+	/// with a label.
+	pub fn time_duration_labelled(
+		&self,
+		name: &str,
+		label: Option<(&'static str, &str)>,
+		d: Duration,
+	) {
+		update(&self.registry.histograms, key(name, label), |h| h.observe(d));
+	}
+
+	/// All metrics in the Prometheus
+	/// text exposition format.
+	pub fn render(&self) -> String {
+		self.registry.render()
+	}
 
-	MetricsHandler {
-		send: Arc::new(Mutex::new(send)),
-		thread: Arc::new(handle),
+	/// Serve the metrics over HTTP at `/metrics`.
+	///
+	/// The listener runs on a background thread
+	/// for the rest of the program.
+	pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> io::Result<()> {
+		let server = Server::http(addr).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+		let metrics = self.clone();
+
+		thread::spawn(move || {
+			let result = server.handle(move |req: Request, mut res: Response| {
+				match req.uri {
+					RequestUri::AbsolutePath(ref path) if path == "/metrics" => {
+						res.headers_mut().set(ContentType(
+							"text/plain; version=0.0.4".parse().unwrap(),
+						));
+						res.send(metrics.render().as_bytes()).err();
+					}
+					_ => {
+						*res.status_mut() = StatusCode::NotFound;
+						res.send(b"").err();
+					}
+				}
+			});
+
+			if let Err(e) = result {
+				error!(
+					target: "server",
+					"Metrics endpoint failed with error {}",
+					e
+				);
+			}
+		});
+
+		Ok(())
 	}
 }
+
+pub fn handler() -> MetricsHandler {
+	MetricsHandler::default()
+}
@@ -0,0 +1,412 @@
+//! Settings shared by all server binaries.
+//!
+//! Every option can be given as a command
+//! line flag, an environment variable, or a
+//! line in a config file. Later sources
+//! override earlier ones, in the order
+//!
+//! 1. config file
+//! 2. environment variables
+//! 3. command line flags
+//!
+//! The config file has one `option = value`
+//! per line, using the flag names without the
+//! leading dashes. Lines starting with `#`
+//! are comments.
+
+use log::Level;
+use simple_logger;
+
+use builder::AirmashServer;
+use proxy::TrustedProxies;
+use raw::RawTransport;
+#[cfg(unix)]
+use raw::UnixTransport;
+use server::WebsocketTransport;
+use types::{ConnectionLimits, LagCompensation, StatusHandle};
+
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+use std::time::Duration;
+
+const USAGE: &'static str = "\
+Options:
+    --addr <ADDR>          Address to listen on [env: AIRMASH_ADDR] [default: 0.0.0.0:3501]
+    --room <NAME>          Room name shown to clients [env: AIRMASH_ROOM]
+    --log-level <LEVEL>    error, warn, info, debug or trace [env: AIRMASH_LOG_LEVEL] [default: info]
+    --tick-rate <HZ>       Frames per second [env: AIRMASH_TICK_RATE] [default: 60]
+    --threads <N>          Run systems in parallel on N threads [env: AIRMASH_THREADS]
+                           [default: run them one at a time]
+    --config <PATH>        Read options from a file [env: AIRMASH_CONFIG]
+    --tls-addr <ADDR>      Also accept wss:// connections on ADDR [env: AIRMASH_TLS_ADDR]
+    --tls-identity <PATH>  PKCS #12 certificate and key for TLS [env: AIRMASH_TLS_IDENTITY]
+    --tls-password <PASS>  Password for the TLS identity [env: AIRMASH_TLS_PASSWORD]
+    --raw-addr <ADDR>      Also accept length-prefixed packets over plain TCP on ADDR
+                           [env: AIRMASH_RAW_ADDR]
+    --raw-socket <PATH>    Also accept length-prefixed packets on a Unix socket at PATH
+                           [env: AIRMASH_RAW_SOCKET]
+    --trusted-proxies <CIDRS>
+                           Comma separated proxies allowed to set X-Forwarded-For,
+                           add =forwarded to a range to read Forwarded instead
+                           [env: AIRMASH_TRUSTED_PROXIES]
+    --max-connections-per-ip <N>
+                           Open connections allowed from one address, each client
+                           opens two [env: AIRMASH_MAX_CONNECTIONS_PER_IP]
+    --max-players-per-ip <N>
+                           Players allowed from one address [env: AIRMASH_MAX_PLAYERS_PER_IP]
+    --allowed-origins <ORIGINS>
+                           Comma separated Origin headers allowed to connect,
+                           not with --raw-addr or --raw-socket
+                           [env: AIRMASH_ALLOWED_ORIGINS]
+    --metrics <ADDR>       Serve prometheus metrics on ADDR [env: AIRMASH_METRICS]
+    --record <PATH>        Record a replay of the match to PATH [env: AIRMASH_RECORD]
+    --max-rewind <MS>      Lag compensation limit, 0 to disable [env: AIRMASH_MAX_REWIND]
+    --help                 Print this message
+";
+
+/// Every option, along with the environment
+/// variable that sets it.
+const OPTIONS: &'static [(&'static str, &'static str)] = &[
+	("addr", "AIRMASH_ADDR"),
+	("room", "AIRMASH_ROOM"),
+	("log-level", "AIRMASH_LOG_LEVEL"),
+	("tick-rate", "AIRMASH_TICK_RATE"),
+	("threads", "AIRMASH_THREADS"),
+	("config", "AIRMASH_CONFIG"),
+	("tls-addr", "AIRMASH_TLS_ADDR"),
+	("tls-identity", "AIRMASH_TLS_IDENTITY"),
+	("tls-password", "AIRMASH_TLS_PASSWORD"),
+	("raw-addr", "AIRMASH_RAW_ADDR"),
+	("raw-socket", "AIRMASH_RAW_SOCKET"),
+	("trusted-proxies", "AIRMASH_TRUSTED_PROXIES"),
+	("max-connections-per-ip", "AIRMASH_MAX_CONNECTIONS_PER_IP"),
+	("max-players-per-ip", "AIRMASH_MAX_PLAYERS_PER_IP"),
+	("allowed-origins", "AIRMASH_ALLOWED_ORIGINS"),
+	("metrics", "AIRMASH_METRICS"),
+	("record", "AIRMASH_RECORD"),
+	("max-rewind", "AIRMASH_MAX_REWIND"),
+];
+
+/// Why options couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionsError {
+	/// `--help` was given
+	Help,
+	Invalid(String),
+}
+
+impl From<String> for OptionsError {
+	fn from(e: String) -> Self {
+		OptionsError::Invalid(e)
+	}
+}
+
+impl fmt::Display for OptionsError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			OptionsError::Help => write!(f, "help requested"),
+			OptionsError::Invalid(ref e) => write!(f, "{}", e),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct ServerOptions {
+	pub addr: String,
+	/// Overrides the game mode's room name
+	pub room: Option<String>,
+	pub log_level: Level,
+	pub tick_rate: u32,
+	/// Size of the thread pool that systems
+	/// run on. Without it they run one at a
+	/// time on the game loop's thread.
+	pub threads: Option<usize>,
+	pub config: Option<String>,
+	/// Address to accept TLS connections on,
+	/// requires `tls_identity`
+	pub tls_addr: Option<String>,
+	pub tls_identity: Option<String>,
+	pub tls_password: String,
+	/// Address to accept raw TCP connections on
+	pub raw_addr: Option<String>,
+	/// Path of a Unix socket to accept raw
+	/// connections on
+	pub raw_socket: Option<String>,
+	pub trusted_proxies: TrustedProxies,
+	pub limits: ConnectionLimits,
+	/// Address to serve metrics on
+	pub metrics: Option<String>,
+	/// File to record a replay to
+	pub record: Option<String>,
+	pub max_rewind: Option<Duration>,
+}
+
+impl Default for ServerOptions {
+	fn default() -> Self {
+		Self {
+			addr: "0.0.0.0:3501".to_owned(),
+			room: None,
+			log_level: Level::Info,
+			tick_rate: 60,
+			threads: None,
+			config: None,
+			tls_addr: None,
+			tls_identity: None,
+			tls_password: String::new(),
+			raw_addr: None,
+			raw_socket: None,
+			trusted_proxies: TrustedProxies::default(),
+			limits: ConnectionLimits::default(),
+			metrics: None,
+			record: None,
+			max_rewind: None,
+		}
+	}
+}
+
+impl ServerOptions {
+	/// Read the options for this process, exiting
+	/// with a usage message if they are invalid.
+	pub fn from_env() -> Self {
+		let vars = |name: &str| env::var(name).ok();
+		let bin = env::args().next().unwrap_or_default();
+
+		match Self::parse(env::args().skip(1), vars) {
+			Ok(options) => options,
+			Err(OptionsError::Help) => {
+				print!("Usage: {} [OPTIONS]\n\n{}", bin, USAGE);
+				process::exit(0);
+			}
+			Err(OptionsError::Invalid(e)) => {
+				eprintln!("error: {}\n\nUsage: {} [OPTIONS]\n\n{}", e, bin, USAGE);
+				process::exit(2);
+			}
+		}
+	}
+
+	/// Parse options from command line arguments
+	/// and a source of environment variables.
+	pub fn parse<I, E>(args: I, env: E) -> Result<Self, OptionsError>
+	where
+		I: IntoIterator<Item = String>,
+		E: Fn(&str) -> Option<String>,
+	{
+		let mut flags = vec![];
+		let mut args = args.into_iter();
+
+		while let Some(arg) = args.next() {
+			if arg == "--help" || arg == "-h" {
+				return Err(OptionsError::Help);
+			}
+
+			if !arg.starts_with("--") {
+				return Err(format!("unexpected argument {}", arg).into());
+			}
+
+			let name = arg[2..].to_owned();
+			let val = match args.next() {
+				Some(val) => val,
+				None => return Err(format!("missing value for {}", arg).into()),
+			};
+
+			flags.push((name, val));
+		}
+
+		let vars = OPTIONS
+			.iter()
+			.filter_map(|&(name, var)| env(var).map(|val| (name.to_owned(), val)))
+			.collect::<Vec<_>>();
+
+		// The config file has to be found before
+		// anything else can be applied.
+		let path = flags
+			.iter()
+			.chain(vars.iter())
+			.find(|&&(ref name, _)| name == "config")
+			.map(|&(_, ref val)| val.clone());
+
+		let mut options = Self::default();
+
+		if let Some(path) = path {
+			let file = read_config(&path).map_err(|e| format!("unable to read {}: {}", path, e))?;
+
+			for (name, val) in file {
+				options.set(&name, &val)?;
+			}
+		}
+
+		for (name, val) in vars.into_iter().chain(flags) {
+			options.set(&name, &val)?;
+		}
+
+		if options.tls_addr.is_some() && options.tls_identity.is_none() {
+			return Err("tls-addr requires tls-identity".to_owned().into());
+		}
+
+		// Raw connections never have an origin, so
+		// every one of them would be rejected.
+		let raw = options.raw_addr.is_some() || options.raw_socket.is_some();
+		if raw && options.limits.allowed_origins.is_some() {
+			return Err("allowed-origins can't be used with raw-addr or raw-socket"
+				.to_owned()
+				.into());
+		}
+
+		Ok(options)
+	}
+
+	fn set(&mut self, name: &str, val: &str) -> Result<(), String> {
+		let invalid = || format!("invalid value for {}: {}", name, val);
+
+		match name {
+			"addr" => self.addr = val.to_owned(),
+			"room" => self.room = Some(val.to_owned()),
+			"log-level" => self.log_level = val.parse().map_err(|_| invalid())?,
+			"tick-rate" => match val.parse() {
+				Ok(0) | Err(_) => return Err(invalid()),
+				Ok(hz) => self.tick_rate = hz,
+			},
+			"threads" => match val.parse() {
+				Ok(0) | Err(_) => return Err(invalid()),
+				Ok(n) => self.threads = Some(n),
+			},
+			"config" => self.config = Some(val.to_owned()),
+			"tls-addr" => self.tls_addr = Some(val.to_owned()),
+			"tls-identity" => self.tls_identity = Some(val.to_owned()),
+			"tls-password" => self.tls_password = val.to_owned(),
+			"raw-addr" => self.raw_addr = Some(val.to_owned()),
+			"raw-socket" => self.raw_socket = Some(val.to_owned()),
+			"trusted-proxies" => self.trusted_proxies = val.parse()?,
+			"max-connections-per-ip" => {
+				self.limits.max_connections_per_ip = Some(val.parse().map_err(|_| invalid())?)
+			}
+			"max-players-per-ip" => {
+				self.limits.max_players_per_ip = Some(val.parse().map_err(|_| invalid())?)
+			}
+			"allowed-origins" => self.limits.allowed_origins = Some(val.parse()?),
+			"metrics" => self.metrics = Some(val.to_owned()),
+			"record" => self.record = Some(val.to_owned()),
+			"max-rewind" => {
+				let ms = val.parse().map_err(|_| invalid())?;
+				self.max_rewind = Some(Duration::from_millis(ms));
+			}
+			_ => return Err(format!("unknown option {}", name)),
+		}
+
+		Ok(())
+	}
+
+	/// Start logging at the configured level.
+	pub fn init_logger(&self) {
+		simple_logger::init_with_level(self.log_level).unwrap();
+	}
+
+	/// The room to use, given the one that the
+	/// game mode would use by default.
+	pub fn room(&self, default: &str) -> String {
+		self.room.clone().unwrap_or_else(|| default.to_owned())
+	}
+
+	/// Create a server listening on the configured
+	/// addresses. Game systems still need to be
+	/// added, then call [`apply`](#method.apply)
+	/// before running it.
+	///
+	/// Every address is bound here, so a port that
+	/// is already in use is reported as an error.
+	pub fn server(&self) -> io::Result<AirmashServer<'static, 'static>> {
+		let status = StatusHandle::default();
+		let mut transport = WebsocketTransport::new(self.addr.clone())
+			.with_trusted_proxies(self.trusted_proxies.clone())
+			.with_status(status.clone());
+
+		if let (&Some(ref addr), &Some(ref path)) = (&self.tls_addr, &self.tls_identity) {
+			transport = transport.with_tls(addr.clone(), path, &self.tls_password)?;
+		}
+
+		let mut server = AirmashServer::from_transport(transport.bind()?);
+		server.world.add_resource(status);
+
+		if let Some(ref addr) = self.raw_addr {
+			server = server.with_transport(RawTransport::bind(&**addr)?);
+		}
+		if let Some(ref path) = self.raw_socket {
+			server = server.with_transport(unix_transport(path)?);
+		}
+
+		Ok(server)
+	}
+
+	/// Apply everything that isn't specific
+	/// to a game mode.
+	pub fn apply(
+		&self,
+		server: AirmashServer<'static, 'static>,
+	) -> io::Result<AirmashServer<'static, 'static>> {
+		let mut server = server
+			.with_tick_rate(self.tick_rate)
+			.with_connection_limits(self.limits.clone());
+
+		if let Some(threads) = self.threads {
+			server = server.with_threads(threads)?;
+		}
+
+		if let Some(max_rewind) = self.max_rewind {
+			server = server.with_lag_compensation(LagCompensation { max_rewind });
+		}
+		if let Some(ref addr) = self.metrics {
+			server = server.with_metrics(&**addr)?;
+		}
+		if let Some(ref path) = self.record {
+			server = server.with_recording(path)?;
+		}
+
+		Ok(server)
+	}
+}
+
+#[cfg(unix)]
+fn unix_transport(path: &str) -> io::Result<UnixTransport> {
+	UnixTransport::bind(path)
+}
+
+#[cfg(not(unix))]
+fn unix_transport(_: &str) -> io::Result<RawTransport> {
+	Err(io::Error::new(
+		io::ErrorKind::Other,
+		"unix sockets are not supported on this platform",
+	))
+}
+
+fn read_config(path: &str) -> io::Result<Vec<(String, String)>> {
+	let mut text = String::new();
+	File::open(path)?.read_to_string(&mut text)?;
+
+	let mut options = vec![];
+
+	for (i, line) in text.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		let mut parts = line.splitn(2, '=');
+		let name = parts.next().unwrap().trim();
+		let val = match parts.next() {
+			Some(val) => val.trim(),
+			None => {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidData,
+					format!("line {} is not of the form `option = value`", i + 1),
+				))
+			}
+		};
+
+		options.push((name.to_owned(), val.to_owned()));
+	}
+
+	Ok(options)
+}
@@ -0,0 +1,336 @@
+//! Finding the real address of clients
+//! that connect through reverse proxies.
+
+use hyper::header::{Header, HeaderFormat, Headers};
+use hyper::Error as HyperError;
+
+use std::fmt::{self, Formatter};
+use std::net::{IpAddr, SocketAddr};
+use std::str::{self, FromStr};
+
+/// A range of IP addresses in CIDR
+/// notation, e.g. `10.0.0.0/8`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct IpNet {
+	addr: IpAddr,
+	prefix: u8,
+}
+
+impl IpNet {
+	pub fn contains(&self, addr: &IpAddr) -> bool {
+		match (self.addr, *addr) {
+			(IpAddr::V4(net), IpAddr::V4(addr)) => {
+				let mask = mask(self.prefix, 32) as u32;
+				u32::from(net) & mask == u32::from(addr) & mask
+			}
+			(IpAddr::V6(net), IpAddr::V6(addr)) => {
+				let mask = mask(self.prefix, 128);
+				to_u128(net.octets()) & mask == to_u128(addr.octets()) & mask
+			}
+			_ => false,
+		}
+	}
+}
+
+fn mask(prefix: u8, bits: u8) -> u128 {
+	match prefix {
+		0 => 0,
+		p => !0u128 << (bits - p) & (!0u128 >> (128 - bits as u32)),
+	}
+}
+
+fn to_u128(octets: [u8; 16]) -> u128 {
+	octets.iter().fold(0, |acc, &b| acc << 8 | b as u128)
+}
+
+impl FromStr for IpNet {
+	type Err = String;
+
+	/// A bare address is treated as
+	/// a range containing only itself.
+	fn from_str(s: &str) -> Result<Self, String> {
+		let invalid = || format!("invalid address range {}", s);
+
+		let mut parts = s.trim().splitn(2, '/');
+		let addr: IpAddr = parts.next().unwrap().parse().map_err(|_| invalid())?;
+		let bits = match addr {
+			IpAddr::V4(_) => 32,
+			IpAddr::V6(_) => 128,
+		};
+
+		let prefix = match parts.next() {
+			Some(p) => p.parse().map_err(|_| invalid())?,
+			None => bits,
+		};
+		if prefix > bits {
+			return Err(invalid());
+		}
+
+		Ok(Self { addr, prefix })
+	}
+}
+
+/// The header that a proxy uses to
+/// pass on the address of the client.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ForwardedHeader {
+	XForwardedFor,
+	Forwarded,
+}
+
+impl Default for ForwardedHeader {
+	fn default() -> Self {
+		ForwardedHeader::XForwardedFor
+	}
+}
+
+impl FromStr for ForwardedHeader {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, String> {
+		let s = s.trim();
+
+		if s.eq_ignore_ascii_case("x-forwarded-for") {
+			Ok(ForwardedHeader::XForwardedFor)
+		} else if s.eq_ignore_ascii_case("forwarded") {
+			Ok(ForwardedHeader::Forwarded)
+		} else {
+			Err(format!("unknown forwarding header {}", s))
+		}
+	}
+}
+
+/// A range of proxies and the one
+/// header that they set.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TrustedProxy {
+	pub net: IpNet,
+	pub header: ForwardedHeader,
+}
+
+impl FromStr for TrustedProxy {
+	type Err = String;
+
+	/// Either `10.0.0.0/8`, which uses
+	/// `X-Forwarded-For`, or a range with the
+	/// header after it, e.g. `10.0.0.0/8=forwarded`.
+	fn from_str(s: &str) -> Result<Self, String> {
+		let mut parts = s.splitn(2, '=');
+		let net = parts.next().unwrap().parse()?;
+		let header = match parts.next() {
+			Some(header) => header.parse()?,
+			None => ForwardedHeader::default(),
+		};
+
+		Ok(Self { net, header })
+	}
+}
+
+/// Proxies that are allowed to tell us
+/// the real address of a client.
+///
+/// Only the header that the connecting proxy
+/// is configured with is read, any other
+/// forwarding header could have come from the
+/// client. The chain of forwarded addresses
+/// is walked from the right, skipping proxies
+/// that we trust, and the first address that
+/// isn't trusted is the client. Anything to
+/// the left of that could have been made up
+/// by the client.
+///
+/// By default no proxies are trusted and the
+/// forwarding headers are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct TrustedProxies(pub Vec<TrustedProxy>);
+
+impl TrustedProxies {
+	pub fn is_trusted(&self, addr: &IpAddr) -> bool {
+		self.proxy(addr).is_some()
+	}
+
+	fn proxy(&self, addr: &IpAddr) -> Option<&TrustedProxy> {
+		self.0.iter().find(|proxy| proxy.net.contains(addr))
+	}
+
+	/// Find the client address given the address
+	/// that connected to us and the forwarded
+	/// chain, from the original client first to
+	/// the latest proxy last.
+	///
+	/// A `None` within the chain is an address
+	/// that a proxy hid from us, if we get that
+	/// far the proxy that reported it is used.
+	pub fn resolve(&self, peer: IpAddr, chain: &[Option<IpAddr>]) -> IpAddr {
+		let mut client = peer;
+
+		for addr in chain.iter().rev() {
+			if !self.is_trusted(&client) {
+				break;
+			}
+
+			client = match *addr {
+				Some(addr) => addr,
+				None => break,
+			};
+		}
+
+		client
+	}
+
+	/// Find the client address from the headers
+	/// of a request, using only the header set
+	/// by the proxy that connected to us.
+	pub fn client_addr(&self, peer: IpAddr, headers: &Headers) -> IpAddr {
+		let header = match self.proxy(&peer) {
+			Some(proxy) => proxy.header,
+			None => return peer,
+		};
+
+		let chain = match header {
+			ForwardedHeader::XForwardedFor => headers.get::<XForwardedFor>().map(|x| &x.addrs),
+			ForwardedHeader::Forwarded => headers.get::<Forwarded>().map(|x| &x.addrs),
+		};
+
+		match chain {
+			Some(chain) => self.resolve(peer, chain),
+			None => peer,
+		}
+	}
+}
+
+impl FromStr for TrustedProxies {
+	type Err = String;
+
+	/// Parse a comma separated list of ranges.
+	fn from_str(s: &str) -> Result<Self, String> {
+		s.split(',')
+			.filter(|s| !s.trim().is_empty())
+			.map(str::parse)
+			.collect::<Result<_, _>>()
+			.map(TrustedProxies)
+	}
+}
+
+/// Parse a node from either header. These
+/// may or may not have a port attached.
+fn parse_node(s: &str) -> Option<IpAddr> {
+	let s = s.trim().trim_matches('"');
+
+	if let Ok(addr) = s.parse() {
+		return Some(addr);
+	}
+	if let Ok(addr) = s.parse::<SocketAddr>() {
+		return Some(addr.ip());
+	}
+
+	// IPv6 without a port is still bracketed
+	if s.starts_with('[') && s.ends_with(']') {
+		return s[1..s.len() - 1].parse().ok();
+	}
+
+	None
+}
+
+/// Proxies may add their own header line
+/// instead of appending to an existing one.
+fn joined_header(raw: &[Vec<u8>]) -> Result<String, HyperError> {
+	let lines = raw
+		.iter()
+		.map(|line| str::from_utf8(line).map_err(HyperError::Utf8))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(lines.join(","))
+}
+
+fn fmt_addrs(addrs: &[Option<IpAddr>]) -> String {
+	addrs
+		.iter()
+		.map(|x| match *x {
+			Some(ref x) => x.to_string(),
+			None => "unknown".to_owned(),
+		})
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+#[derive(Clone, Debug)]
+pub struct XForwardedFor {
+	pub addrs: Vec<Option<IpAddr>>,
+}
+
+impl Header for XForwardedFor {
+	fn header_name() -> &'static str {
+		"X-Forwarded-For"
+	}
+
+	fn parse_header(raw: &[Vec<u8>]) -> Result<Self, HyperError> {
+		let s = joined_header(raw)?;
+
+		Ok(Self {
+			addrs: s.split(',').map(parse_node).collect(),
+		})
+	}
+}
+
+impl HeaderFormat for XForwardedFor {
+	fn fmt_header(&self, fmt: &mut Formatter) -> fmt::Result {
+		write!(fmt, "{}", fmt_addrs(&self.addrs))
+	}
+}
+
+/// The `Forwarded` header from RFC 7239.
+///
+/// Only the `for` parameter is used.
+#[derive(Clone, Debug)]
+pub struct Forwarded {
+	pub addrs: Vec<Option<IpAddr>>,
+}
+
+impl Header for Forwarded {
+	fn header_name() -> &'static str {
+		"Forwarded"
+	}
+
+	fn parse_header(raw: &[Vec<u8>]) -> Result<Self, HyperError> {
+		let s = joined_header(raw)?;
+
+		// Each proxy adds an element, and each
+		// element is a list of key=value pairs.
+		let addrs = s
+			.split(',')
+			.map(|elem| {
+				elem.split(';')
+					.filter_map(|pair| {
+						let mut kv = pair.splitn(2, '=');
+						let key = kv.next().unwrap().trim();
+						if key.eq_ignore_ascii_case("for") {
+							kv.next()
+						} else {
+							None
+						}
+					})
+					.next()
+					.and_then(parse_node)
+			})
+			.collect();
+
+		Ok(Self { addrs })
+	}
+}
+
+impl HeaderFormat for Forwarded {
+	fn fmt_header(&self, fmt: &mut Formatter) -> fmt::Result {
+		let elems = self
+			.addrs
+			.iter()
+			.map(|x| match *x {
+				Some(IpAddr::V6(ref x)) => format!("for=\"[{}]\"", x),
+				Some(ref x) => format!("for={}", x),
+				None => "for=unknown".to_owned(),
+			})
+			.collect::<Vec<_>>();
+
+		write!(fmt, "{}", elems.join(", "))
+	}
+}
@@ -0,0 +1,361 @@
+//! Transports that carry packets over plain
+//! sockets instead of websockets.
+//!
+//! Packets are sent in both directions as
+//! frames made up of the length of the packet
+//! as a little-endian `u32` followed by the
+//! packet itself. The packets are exactly the
+//! same as the ones sent over websockets.
+//!
+//! These are meant for bots and tools that
+//! would rather not pull in a websocket stack.
+//! There is no TLS and no `Origin` header, so
+//! they can't be used along with a list of
+//! allowed origins, and they shouldn't be
+//! exposed to untrusted networks.
+
+use futures::{Async, AsyncSink, Poll, Sink, StartSend};
+use websocket::OwnedMessage;
+
+use types::event::*;
+use types::*;
+
+#[cfg(unix)]
+use std::fs;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+#[cfg(unix)]
+use std::net::Ipv4Addr;
+use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::mpsc::{sync_channel, Receiver, Sender, SyncSender, TrySendError};
+use std::sync::Mutex;
+use std::thread;
+
+/// The largest frame that a client may send.
+/// Client packets are tiny, so anything bigger
+/// than this means the stream is garbage.
+pub const MAX_CLIENT_FRAME: usize = 0x10000;
+
+/// Messages that can be waiting to be written
+/// to a socket before the sink stops accepting
+/// more. The rest wait in the outbound queue.
+const WRITE_BUFFER: usize = 256;
+
+/// A socket that frames can be sent over.
+trait RawStream: Read + Write + Send + Sized + 'static {
+	fn try_clone(&self) -> io::Result<Self>;
+	fn shutdown(&self) -> io::Result<()>;
+}
+
+impl RawStream for TcpStream {
+	fn try_clone(&self) -> io::Result<Self> {
+		TcpStream::try_clone(self)
+	}
+
+	fn shutdown(&self) -> io::Result<()> {
+		TcpStream::shutdown(self, Shutdown::Both)
+	}
+}
+
+#[cfg(unix)]
+impl RawStream for UnixStream {
+	fn try_clone(&self) -> io::Result<Self> {
+		UnixStream::try_clone(self)
+	}
+
+	fn shutdown(&self) -> io::Result<()> {
+		UnixStream::shutdown(self, Shutdown::Both)
+	}
+}
+
+/// Hands messages over to the thread that
+/// writes to the socket.
+struct RawSink(Mutex<SyncSender<OutboundMessage>>);
+
+impl Sink for RawSink {
+	type SinkItem = OutboundMessage;
+	type SinkError = io::Error;
+
+	fn start_send(&mut self, msg: OutboundMessage) -> StartSend<OutboundMessage, io::Error> {
+		match self.0.get_mut().unwrap().try_send(msg) {
+			Ok(()) => Ok(AsyncSink::Ready),
+			Err(TrySendError::Full(msg)) => Ok(AsyncSink::NotReady(msg)),
+			Err(TrySendError::Disconnected(_)) => Err(io::Error::new(
+				io::ErrorKind::BrokenPipe,
+				"connection closed",
+			)),
+		}
+	}
+
+	fn poll_complete(&mut self) -> Poll<(), io::Error> {
+		Ok(Async::Ready(()))
+	}
+}
+
+fn write_frame<W: Write>(w: &mut W, data: &[u8]) -> io::Result<()> {
+	let len = data.len() as u32;
+
+	w.write_all(&[
+		len as u8,
+		(len >> 8) as u8,
+		(len >> 16) as u8,
+		(len >> 24) as u8,
+	])?;
+	w.write_all(data)
+}
+
+/// Read the next frame. Returns `None` if the
+/// client closed the connection.
+fn read_frame<R: Read>(r: &mut R) -> io::Result<Option<Vec<u8>>> {
+	let mut len = [0u8; 4];
+
+	match r.read_exact(&mut len) {
+		Ok(()) => (),
+		Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+		Err(e) => return Err(e),
+	}
+
+	let len = len
+		.iter()
+		.rev()
+		.fold(0usize, |acc, &b| (acc << 8) | b as usize);
+
+	if len > MAX_CLIENT_FRAME {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("frame of {} bytes is too large", len),
+		));
+	}
+
+	let mut data = vec![0; len];
+	r.read_exact(&mut data)?;
+
+	Ok(Some(data))
+}
+
+/// Write messages to the socket until the
+/// server closes the connection or drops
+/// the sink.
+fn write_frames<S: RawStream>(stream: S, recv: Receiver<OutboundMessage>) -> io::Result<()> {
+	let mut out = BufWriter::new(stream);
+
+	while let Ok(msg) = recv.recv() {
+		let mut next = Some(msg);
+
+		// Write everything that is already
+		// waiting before flushing
+		while let Some(msg) = next {
+			match msg {
+				OutboundMessage::Owned(OwnedMessage::Close(_)) => {
+					out.flush()?;
+					return out.get_ref().shutdown();
+				}
+				// Pings and text messages only make
+				// sense for websockets
+				msg => {
+					if let Some(data) = msg.data() {
+						write_frame(&mut out, data)?;
+					}
+				}
+			}
+
+			next = recv.try_recv().ok();
+		}
+
+		out.flush()?;
+	}
+
+	// This also stops the reading thread
+	out.get_ref().shutdown()
+}
+
+/// Run a connection until either side
+/// closes it.
+fn handle<S: RawStream>(stream: S, addr: IpAddr, channel: Sender<ConnectionEvent>) {
+	let id = ConnectionId::new();
+
+	let writer = match stream.try_clone() {
+		Ok(writer) => writer,
+		Err(e) => {
+			info!("A client failed to connect with error: {}", e);
+			return;
+		}
+	};
+
+	let (send, recv) = sync_channel(WRITE_BUFFER);
+	thread::spawn(move || write_frames(writer, recv));
+
+	info!(
+		"Created new raw connection with id {} and addr {}",
+		id.0, addr
+	);
+
+	let sink: ConnectionSink = Box::new(RawSink(Mutex::new(send)));
+	let open = channel.send(ConnectionEvent::ConnectionOpen(ConnectionOpen {
+		conn: id,
+		sink: Mutex::new(Some(sink)),
+		addr,
+		origin: None,
+	}));
+
+	// The server is shutting down
+	if open.is_err() {
+		return;
+	}
+
+	let mut reader = BufReader::new(stream);
+
+	loop {
+		match read_frame(&mut reader) {
+			Ok(Some(data)) => {
+				let msg = ConnectionEvent::Message(Message {
+					conn: id,
+					msg: OwnedMessage::Binary(data),
+				});
+
+				if channel.send(msg).is_err() {
+					break;
+				}
+			}
+			Ok(None) => {
+				info!("Connection {:?} closed", id);
+				break;
+			}
+			Err(e) => {
+				info!("Connection {:?} closed with error: {}", id, e);
+				break;
+			}
+		}
+	}
+
+	reader.get_ref().shutdown().err();
+
+	channel
+		.send(ConnectionEvent::ConnectionClose(ConnectionClose {
+			conn: id,
+		}))
+		.map_err(|e| error!("Channel send error: {}", e))
+		.err();
+}
+
+/// Accepts length-prefixed packets over TCP.
+pub struct RawTransport(TcpListener);
+
+impl RawTransport {
+	/// Listen on `addr`. Nothing is accepted
+	/// until the server is started.
+	pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+		TcpListener::bind(addr).map(RawTransport)
+	}
+
+	/// The address that is being listened on,
+	/// useful when binding to port 0.
+	pub fn local_addr(&self) -> io::Result<SocketAddr> {
+		self.0.local_addr()
+	}
+}
+
+impl Transport for RawTransport {
+	fn start(self: Box<Self>, channel: Sender<ConnectionEvent>) {
+		info!(
+			target: "server",
+			"starting raw TCP server at {:?}",
+			self.0.local_addr()
+		);
+
+		thread::spawn(move || {
+			for stream in self.0.incoming() {
+				let stream = match stream {
+					Ok(stream) => stream,
+					Err(e) => {
+						info!("A client failed to connect with error: {}", e);
+						continue;
+					}
+				};
+
+				#[cfg(feature = "nodelay")]
+				stream.set_nodelay(true).err();
+
+				let addr = match stream.peer_addr() {
+					Ok(addr) => addr.ip(),
+					Err(_) => continue,
+				};
+
+				let channel = channel.clone();
+				thread::spawn(move || handle(stream, addr, channel));
+			}
+		});
+	}
+}
+
+/// Accepts length-prefixed packets over a
+/// Unix domain socket.
+///
+/// All connections appear to come from
+/// 127.0.0.1, so per-address limits apply
+/// to all of them together.
+#[cfg(unix)]
+pub struct UnixTransport(UnixListener);
+
+#[cfg(unix)]
+impl UnixTransport {
+	/// Listen on a socket at `path`. A socket left
+	/// behind by a server that has exited is
+	/// replaced, but this fails if anything else
+	/// is there, including a socket in use.
+	pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let path = path.as_ref();
+
+		if is_stale_socket(path) {
+			fs::remove_file(path)?;
+		}
+
+		UnixListener::bind(path).map(UnixTransport)
+	}
+}
+
+/// Whether `path` is a socket that
+/// nothing is listening on.
+#[cfg(unix)]
+fn is_stale_socket(path: &Path) -> bool {
+	let is_socket = fs::symlink_metadata(path)
+		.map(|meta| meta.file_type().is_socket())
+		.unwrap_or(false);
+
+	is_socket && match UnixStream::connect(path) {
+		Err(ref e) => e.kind() == io::ErrorKind::ConnectionRefused,
+		Ok(_) => false,
+	}
+}
+
+#[cfg(unix)]
+impl Transport for UnixTransport {
+	fn start(self: Box<Self>, channel: Sender<ConnectionEvent>) {
+		info!(
+			target: "server",
+			"starting unix socket server at {:?}",
+			self.0.local_addr()
+		);
+
+		thread::spawn(move || {
+			for stream in self.0.incoming() {
+				let stream = match stream {
+					Ok(stream) => stream,
+					Err(e) => {
+						info!("A client failed to connect with error: {}", e);
+						continue;
+					}
+				};
+
+				let addr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+				let channel = channel.clone();
+				thread::spawn(move || handle(stream, addr, channel));
+			}
+		});
+	}
+}
@@ -0,0 +1,279 @@
+//! The on-disk replay format.
+//!
+//! A replay file starts with a header
+//! ```text
+//! magic:    b"AMRP"
+//! version:  u8
+//! gametype: u8
+//! room:     varint length + utf8 bytes
+//! tick:     varint, µs between frames
+//! ```
+//! followed by any number of records
+//! ```text
+//! delta: varint, ms since the previous record
+//! target: u8 (0 = all, 1 = player, 2 = all but player)
+//! player: u16 LE, only for targets 1 and 2
+//! length: varint
+//! data:   the serialized packet
+//! ```
+//! Most packets are small, so keeping the
+//! per-record overhead down matters more
+//! than anything else here.
+
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use protocol::GameType;
+
+const MAGIC: &'static [u8; 4] = b"AMRP";
+const VERSION: u8 = 2;
+/// Version 1 had no tick in the header and
+/// was always recorded at 60 frames a second.
+const VERSION_NO_TICK: u8 = 1;
+
+/// Who a recorded packet was sent to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Target {
+	/// Every player
+	All,
+	/// A single player, by entity id
+	Player(u16),
+	/// Every player except this one
+	Except(u16),
+}
+
+impl Target {
+	/// Whether a client that isn't any of
+	/// the players would have seen this.
+	pub fn is_public(&self) -> bool {
+		match *self {
+			Target::All | Target::Except(_) => true,
+			Target::Player(_) => false,
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct ReplayHeader {
+	pub gametype: GameType,
+	pub room: String,
+	/// Time between frames of the server
+	/// that made the recording.
+	pub tick: Duration,
+}
+
+/// A single packet within a replay.
+#[derive(Clone, Debug)]
+pub struct Record {
+	/// Milliseconds since the start of the match.
+	pub time: u32,
+	pub target: Target,
+	pub data: Arc<[u8]>,
+}
+
+fn invalid(msg: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_varint<W: Write>(w: &mut W, mut val: u64) -> io::Result<()> {
+	loop {
+		let byte = (val & 0x7F) as u8;
+		val >>= 7;
+
+		if val == 0 {
+			return w.write_all(&[byte]);
+		}
+
+		w.write_all(&[byte | 0x80])?;
+	}
+}
+
+/// Returns `None` if the reader is already
+/// at the end of the file.
+fn read_varint<R: Read>(r: &mut R) -> io::Result<Option<u64>> {
+	let mut val = 0;
+	let mut shift = 0;
+
+	loop {
+		let mut byte = [0];
+		if r.read(&mut byte)? == 0 {
+			if shift == 0 {
+				return Ok(None);
+			}
+			return Err(io::ErrorKind::UnexpectedEof.into());
+		}
+		if shift >= 64 {
+			return Err(invalid("varint too long"));
+		}
+
+		val |= ((byte[0] & 0x7F) as u64) << shift;
+		shift += 7;
+
+		if byte[0] & 0x80 == 0 {
+			return Ok(Some(val));
+		}
+	}
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+	let mut buf = [0];
+	r.read_exact(&mut buf)?;
+	Ok(buf[0])
+}
+
+fn read_u16<R: Read>(r: &mut R) -> io::Result<u16> {
+	let mut buf = [0; 2];
+	r.read_exact(&mut buf)?;
+	Ok(buf[0] as u16 | (buf[1] as u16) << 8)
+}
+
+fn read_len<R: Read>(r: &mut R) -> io::Result<usize> {
+	match read_varint(r)? {
+		Some(len) => Ok(len as usize),
+		None => Err(io::ErrorKind::UnexpectedEof.into()),
+	}
+}
+
+pub struct ReplayWriter<W: Write> {
+	inner: W,
+	last: u32,
+}
+
+impl<W: Write> ReplayWriter<W> {
+	/// Write the header and prepare
+	/// to write records.
+	pub fn new(mut inner: W, header: &ReplayHeader) -> io::Result<Self> {
+		inner.write_all(MAGIC)?;
+		inner.write_all(&[VERSION, u8::from(header.gametype)])?;
+		write_varint(&mut inner, header.room.len() as u64)?;
+		inner.write_all(header.room.as_bytes())?;
+		write_varint(
+			&mut inner,
+			header.tick.as_secs() * 1_000_000 + header.tick.subsec_micros() as u64,
+		)?;
+
+		Ok(Self { inner, last: 0 })
+	}
+
+	/// Append a record. Records must be
+	/// written in time order.
+	pub fn write(&mut self, record: &Record) -> io::Result<()> {
+		let delta = match record.time.checked_sub(self.last) {
+			Some(delta) => delta,
+			None => return Err(invalid("replay records must be in time order")),
+		};
+		self.last = record.time;
+
+		write_varint(&mut self.inner, delta as u64)?;
+
+		match record.target {
+			Target::All => self.inner.write_all(&[0])?,
+			Target::Player(id) => self.inner.write_all(&[1, id as u8, (id >> 8) as u8])?,
+			Target::Except(id) => self.inner.write_all(&[2, id as u8, (id >> 8) as u8])?,
+		}
+
+		write_varint(&mut self.inner, record.data.len() as u64)?;
+		self.inner.write_all(&record.data)
+	}
+
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+pub struct ReplayReader<R: Read> {
+	inner: R,
+	header: ReplayHeader,
+	last: u32,
+}
+
+impl<R: Read> ReplayReader<R> {
+	/// Read the header of a replay.
+	pub fn new(mut inner: R) -> io::Result<Self> {
+		let mut magic = [0; 4];
+		inner.read_exact(&mut magic)?;
+		if &magic != MAGIC {
+			return Err(invalid("not a replay file"));
+		}
+
+		let version = read_u8(&mut inner)?;
+		if version != VERSION && version != VERSION_NO_TICK {
+			return Err(invalid("unsupported replay version"));
+		}
+
+		let gametype = match GameType::try_from(read_u8(&mut inner)?) {
+			Some(ty) => ty,
+			None => return Err(invalid("unknown game type")),
+		};
+
+		let mut room = vec![0; read_len(&mut inner)?];
+		inner.read_exact(&mut room)?;
+		let room = match String::from_utf8(room) {
+			Ok(room) => room,
+			Err(_) => return Err(invalid("room name is not valid utf8")),
+		};
+
+		let tick = if version == VERSION_NO_TICK {
+			Duration::from_nanos(16666667)
+		} else {
+			match read_varint(&mut inner)? {
+				Some(0) => return Err(invalid("tick must not be zero")),
+				Some(us) => Duration::from_micros(us),
+				None => return Err(io::ErrorKind::UnexpectedEof.into()),
+			}
+		};
+
+		Ok(Self {
+			inner,
+			header: ReplayHeader {
+				gametype,
+				room,
+				tick,
+			},
+			last: 0,
+		})
+	}
+
+	pub fn header(&self) -> &ReplayHeader {
+		&self.header
+	}
+
+	/// Read the next record, or `None` at
+	/// the end of the replay.
+	pub fn next_record(&mut self) -> io::Result<Option<Record>> {
+		let delta = match read_varint(&mut self.inner)? {
+			Some(delta) => delta as u32,
+			None => return Ok(None),
+		};
+		self.last += delta;
+
+		let target = match read_u8(&mut self.inner)? {
+			0 => Target::All,
+			1 => Target::Player(read_u16(&mut self.inner)?),
+			2 => Target::Except(read_u16(&mut self.inner)?),
+			_ => return Err(invalid("unknown record target")),
+		};
+
+		let mut data = vec![0; read_len(&mut self.inner)?];
+		self.inner.read_exact(&mut data)?;
+
+		Ok(Some(Record {
+			time: self.last,
+			target,
+			data: data.into(),
+		}))
+	}
+}
+
+impl<R: Read> Iterator for ReplayReader<R> {
+	type Item = io::Result<Record>;
+
+	fn next(&mut self) -> Option<io::Result<Record>> {
+		match self.next_record() {
+			Ok(Some(record)) => Some(Ok(record)),
+			Ok(None) => None,
+			Err(e) => Some(Err(e)),
+		}
+	}
+}
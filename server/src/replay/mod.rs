@@ -0,0 +1,18 @@
+//! Recording matches and playing them back.
+//!
+//! A server built with
+//! [`with_recording`](../struct.AirmashServer.html#method.with_recording)
+//! writes every packet that it sends, along
+//! with when and to whom it was sent, into a
+//! replay file. A [`ReplayServer`](struct.ReplayServer.html)
+//! can then serve that file to regular clients
+//! as if the match was happening live.
+
+mod file;
+mod playback;
+mod record;
+
+pub use self::file::{Record, ReplayHeader, ReplayReader, ReplayWriter, Target};
+pub use self::playback::ReplayServer;
+
+pub(crate) use self::record::RecordSystem;
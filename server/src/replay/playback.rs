@@ -0,0 +1,349 @@
+use websocket::OwnedMessage;
+
+use fnv::FnvHashMap;
+
+use protocol::client::Command;
+use protocol::server::{GameSpectate, Login, LoginPlayer, PlayerKill};
+use protocol::{codes, from_bytes, to_bytes, Entity};
+use protocol::{ClientPacket, FlagCode, PlaneType, PlayerStatus, ServerPacket, Upgrades};
+use server::WebsocketTransport;
+use types::event::ConnectionEvent;
+use types::*;
+
+use replay::{Record, ReplayHeader, ReplayReader};
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::net::ToSocketAddrs;
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use std::time::Instant;
+
+/// The id that viewers are given. Ids below
+/// `FIRST_ENTITY_ID` are never given to
+/// players so this can't clash with anyone
+/// in the recording.
+const VIEWER_ID: u16 = 2;
+/// The team that viewers are put on. CTF uses
+/// teams 1 and 2 and FFA teams are player ids,
+/// so no game mode will put anyone on team 0.
+const VIEWER_TEAM: Team = Team(0);
+
+struct Viewer {
+	conn: ConnectionData,
+	/// Set once the client has logged in
+	start: Option<Instant>,
+	/// Index of the next record to send
+	next: usize,
+	/// Players currently in the game
+	players: Vec<u16>,
+	target: Option<u16>,
+}
+
+/// Serves a recorded match to websocket clients.
+///
+/// Each client that logs in is put into
+/// spectate mode and sees the match from
+/// the beginning, at the same speed that it
+/// was played and at the tick rate of the
+/// server that recorded it. `spectate` commands switch
+/// between players the same way they would
+/// on a live server.
+pub struct ReplayServer {
+	header: ReplayHeader,
+	records: Vec<Record>,
+}
+
+impl ReplayServer {
+	/// Load a replay file written by a server
+	/// started with
+	/// [`with_recording`](struct.AirmashServer.html#method.with_recording).
+	pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let reader = ReplayReader::new(BufReader::new(File::open(path)?))?;
+		let header = reader.header().clone();
+
+		// Packets sent to a single player are
+		// kept in the file for reference but a
+		// viewer would never see them.
+		let records = reader
+			.filter(|r| match *r {
+				Ok(ref r) => r.target.is_public(),
+				Err(_) => true,
+			})
+			.collect::<io::Result<Vec<_>>>()?;
+
+		info!(
+			"Loaded replay of {} with {} packets",
+			header.room,
+			records.len()
+		);
+
+		Ok(Self { header, records })
+	}
+
+	/// Serve the replay over websockets on `addr`.
+	pub fn run<A>(self, addr: A)
+	where
+		A: ToSocketAddrs + Debug + Send + 'static,
+	{
+		self.run_with_transport(WebsocketTransport::new(addr))
+	}
+
+	/// Serve the replay to connections
+	/// from a custom transport.
+	pub fn run_with_transport<T: Transport + 'static>(self, transport: T) {
+		let (send, recv) = channel();
+		Box::new(transport).start(send);
+
+		let mut viewers = FnvHashMap::default();
+
+		loop {
+			let now = Instant::now();
+
+			if !self.poll(&recv, &mut viewers, now) {
+				info!("Transport has shut down, stopping replay");
+				return;
+			}
+
+			let mut closed = vec![];
+
+			for viewer in viewers.values_mut() {
+				self.advance(viewer, now);
+
+				if let Err(e) = viewer.conn.flush() {
+					info!("Replay connection failed with error {:?}", e);
+					closed.push(viewer.conn.id);
+				}
+			}
+
+			for id in closed {
+				viewers.remove(&id);
+			}
+
+			thread::sleep(self.header.tick);
+		}
+	}
+
+	fn send(&self, viewer: &mut Viewer, packet: ServerPacket) {
+		match to_bytes(&packet) {
+			Ok(data) => viewer.conn.queue.push(OwnedMessage::Binary(data).into()),
+			Err(e) => error!("Failed to serialize {:?} with error {:?}", packet, e),
+		}
+	}
+
+	/// Handle all connection events since the
+	/// last frame. Returns false if the transport
+	/// is gone.
+	fn poll(
+		&self,
+		recv: &Receiver<ConnectionEvent>,
+		viewers: &mut FnvHashMap<ConnectionId, Viewer>,
+		now: Instant,
+	) -> bool {
+		loop {
+			let evt = match recv.try_recv() {
+				Ok(evt) => evt,
+				Err(TryRecvError::Empty) => return true,
+				Err(TryRecvError::Disconnected) => return false,
+			};
+
+			match evt {
+				ConnectionEvent::ConnectionOpen(evt) => {
+					let sink = evt.sink.lock().unwrap().take().unwrap();
+
+					let conn = ConnectionData {
+						sink,
+						queue: OutboundQueue::new(),
+						id: evt.conn,
+						ty: ConnectionType::Inactive,
+						player: None,
+						addr: evt.addr,
+						origin: evt.origin,
+					};
+
+					viewers.insert(
+						evt.conn,
+						Viewer {
+							conn,
+							start: None,
+							next: 0,
+							players: vec![],
+							target: None,
+						},
+					);
+				}
+				ConnectionEvent::ConnectionClose(evt) => {
+					viewers.remove(&evt.conn);
+				}
+				ConnectionEvent::Message(evt) => {
+					let data = match evt.msg {
+						OwnedMessage::Binary(data) => data,
+						_ => continue,
+					};
+
+					let viewer = match viewers.get_mut(&evt.conn) {
+						Some(viewer) => viewer,
+						None => continue,
+					};
+
+					match from_bytes::<ClientPacket>(&data) {
+						Ok(ClientPacket::Login(_)) => self.login(viewer, now),
+						Ok(ClientPacket::Command(ref cmd)) => self.command(viewer, cmd),
+						// Everything else can't affect a replay
+						_ => (),
+					}
+				}
+			}
+		}
+	}
+
+	fn login(&self, viewer: &mut Viewer, now: Instant) {
+		if viewer.start.is_some() {
+			return;
+		}
+
+		let me = Entity(VIEWER_ID);
+		let team = VIEWER_TEAM;
+
+		let player = LoginPlayer {
+			id: me,
+			status: PlayerStatus::Dead,
+			level: Level(0),
+			name: "replay".to_owned(),
+			ty: PlaneType::Predator,
+			team,
+			pos: Position::default(),
+			rot: Rotation::new(0.0),
+			flag: FlagCode::UnitedNations,
+			upgrades: Upgrades::default(),
+		};
+
+		self.send(
+			viewer,
+			ServerPacket::Login(Login {
+				success: true,
+				id: me,
+				team,
+				clock: 0,
+				token: "none".to_owned(),
+				ty: self.header.gametype,
+				room: self.header.room.clone(),
+				players: vec![player],
+			}),
+		);
+
+		// Killing a player at the origin tells
+		// the client that it is now spectating.
+		self.send(
+			viewer,
+			ServerPacket::PlayerKill(PlayerKill {
+				id: me,
+				killer: None,
+				pos: Position::default(),
+			}),
+		);
+
+		viewer.start = Some(now);
+	}
+
+	fn command(&self, viewer: &mut Viewer, cmd: &Command) {
+		if cmd.com != "spectate" {
+			return;
+		}
+
+		let arg: i32 = match cmd.data.parse() {
+			Ok(v) => v,
+			Err(_) => return,
+		};
+
+		let len = viewer.players.len();
+		if len == 0 {
+			return;
+		}
+
+		let current = viewer
+			.target
+			.and_then(|t| viewer.players.iter().position(|p| *p == t));
+
+		let index = match (arg, current) {
+			(-1, Some(i)) => (i + 1) % len,
+			(-2, Some(i)) => (i + len - 1) % len,
+			(-3...-1, _) => 0,
+			(id, _) => match viewer.players.iter().position(|p| *p as i32 == id) {
+				Some(i) => i,
+				None => return,
+			},
+		};
+
+		let target = viewer.players[index];
+		self.spectate(viewer, target);
+	}
+
+	fn spectate(&self, viewer: &mut Viewer, target: u16) {
+		viewer.target = Some(target);
+
+		let id = Entity(target);
+		self.send(viewer, ServerPacket::GameSpectate(GameSpectate { id }));
+	}
+
+	/// Keep track of who is in the game so that
+	/// the viewer always has someone to watch.
+	fn track_players(&self, viewer: &mut Viewer, data: &[u8]) {
+		match data.first() {
+			Some(&codes::server::PLAYER_NEW) | Some(&codes::server::PLAYER_LEAVE) => (),
+			_ => return,
+		}
+
+		match from_bytes::<ServerPacket>(data) {
+			Ok(ServerPacket::PlayerNew(p)) => {
+				let id = p.id.0;
+				viewer.players.push(id);
+
+				if viewer.target.is_none() {
+					self.spectate(viewer, id);
+				}
+			}
+			Ok(ServerPacket::PlayerLeave(p)) => {
+				let id = p.id.0;
+				viewer.players.retain(|p| *p != id);
+
+				if viewer.target == Some(id) {
+					viewer.target = None;
+
+					if let Some(&next) = viewer.players.first() {
+						self.spectate(viewer, next);
+					}
+				}
+			}
+			Ok(_) => (),
+			Err(e) => warn!("Failed to decode recorded packet: {:?}", e),
+		}
+	}
+
+	/// Queue all records that are due by `now`.
+	fn advance(&self, viewer: &mut Viewer, now: Instant) {
+		let start = match viewer.start {
+			Some(start) => start,
+			None => return,
+		};
+
+		let elapsed = now - start;
+		let elapsed = elapsed.as_secs() * 1000 + elapsed.subsec_millis() as u64;
+
+		while let Some(record) = self.records.get(viewer.next) {
+			if record.time as u64 > elapsed {
+				break;
+			}
+
+			viewer.next += 1;
+			viewer.conn.queue.push(record.data.clone().into());
+			self.track_players(viewer, &record.data);
+
+			if viewer.next == self.records.len() {
+				info!("Replay finished for {:?}", viewer.conn.id);
+			}
+		}
+	}
+}
@@ -0,0 +1,143 @@
+use specs::prelude::*;
+
+use component::time::{StartTime, ThisFrame, TickRate};
+use types::gamemode::GameModeInternal;
+use types::{Connections, OutboundMessage};
+use websocket::OwnedMessage;
+
+use replay::{Record, ReplayHeader, ReplayWriter, Target};
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::mem;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+pub type FileWriter = ReplayWriter<BufWriter<File>>;
+
+/// Writes every packet sent by the server
+/// into a replay file.
+///
+/// The file is flushed every frame so that
+/// a replay is still usable if the server
+/// goes down mid-match.
+pub struct RecordSystem {
+	file: Option<BufWriter<File>>,
+	writer: Option<FileWriter>,
+	send: Option<Sender<(Target, OutboundMessage)>>,
+	recv: Receiver<(Target, OutboundMessage)>,
+}
+
+impl RecordSystem {
+	pub fn new(file: BufWriter<File>) -> Self {
+		let (send, recv) = channel();
+
+		Self {
+			file: Some(file),
+			writer: None,
+			send: Some(send),
+			recv,
+		}
+	}
+}
+
+#[derive(SystemData)]
+pub struct RecordSystemData<'a> {
+	pub thisframe: ReadExpect<'a, ThisFrame>,
+	pub start: ReadExpect<'a, StartTime>,
+}
+
+impl<'a> System<'a> for RecordSystem {
+	type SystemData = RecordSystemData<'a>;
+
+	fn setup(&mut self, res: &mut Resources) {
+		Self::SystemData::setup(res);
+
+		let header = {
+			let mode = res.fetch::<GameModeInternal>();
+			let mode = mode.0.as_gamemode_ref();
+
+			ReplayHeader {
+				gametype: mode.gametype(),
+				room: mode.room(),
+				tick: res.fetch::<TickRate>().0,
+			}
+		};
+
+		self.writer = match ReplayWriter::new(self.file.take().unwrap(), &header) {
+			Ok(writer) => Some(writer),
+			Err(e) => {
+				error!(
+					target: "server",
+					"Failed to start recording replay: {}",
+					e
+				);
+				return;
+			}
+		};
+
+		res.fetch_mut::<Connections>()
+			.record_to(self.send.take().unwrap());
+	}
+
+	fn run(&mut self, data: Self::SystemData) {
+		let elapsed = data.thisframe.0 - data.start.0;
+		let time = elapsed.as_secs() as u32 * 1000 + elapsed.subsec_millis();
+
+		let result = match self.writer {
+			Some(ref mut writer) => {
+				let mut result = Ok(());
+
+				while let Ok((target, msg)) = self.recv.try_recv() {
+					// Only binary messages contain packets
+					let data = match msg {
+						OutboundMessage::Shared(data) => data,
+						OutboundMessage::Owned(OwnedMessage::Binary(data)) => data.into(),
+						_ => continue,
+					};
+
+					result = writer.write(&Record { time, target, data });
+					if result.is_err() {
+						break;
+					}
+				}
+
+				result.and_then(|_| writer.flush())
+			}
+			None => return,
+		};
+
+		if let Err(e) = result {
+			error!(
+				target: "server",
+				"Failed to write to replay, recording stopped: {}",
+				e
+			);
+
+			// Dropping the receiver means that
+			// connections will stop sending to it.
+			self.writer = None;
+			self.recv = channel().1;
+		}
+	}
+}
+
+use dispatch::SystemInfo;
+use std::any::Any;
+
+impl SystemInfo for RecordSystem {
+	type Dependencies = ();
+
+	fn name() -> &'static str {
+		concat!(module_path!(), "::", line!())
+	}
+
+	fn new() -> Self {
+		unimplemented!();
+	}
+
+	fn new_args(mut a: Box<Any>) -> Self {
+		let file = a.downcast_mut::<Option<BufWriter<File>>>().unwrap();
+
+		Self::new(mem::replace(file, None).unwrap())
+	}
+}
@@ -1,113 +1,353 @@
 //! Loop for accepting new connections
 //! and passing on all network packets
 
+use proxy::TrustedProxies;
 use types::event::*;
 use types::*;
 
 use std::fmt::Debug;
-use std::io::Write;
-use std::net::ToSocketAddrs;
-use std::sync::mpsc::Sender;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Mutex;
-
-use futures::{Future, Stream};
+use std::thread;
+
+use futures::{future, Future, Sink, Stream};
+use hyper::uri::RequestUri;
+use native_tls::{Pkcs12, TlsAcceptor};
+use websocket::async::MessageCodec;
+// Websocket hasn't updated, can't change this yet
+#[allow(deprecated)]
+use websocket::client::async::Framed;
+use websocket::codec::ws::Context;
+use websocket::result::WebSocketError;
 use websocket::server::async::Server;
+use websocket::server::upgrade::async::Upgrade;
+use websocket::server::InvalidConnection;
+use websocket::stream::async::Stream as AsyncStream;
 use websocket::OwnedMessage;
 
-#[cfg(feature = "proxied")]
-mod hyperuse {
-	pub use hyper::header::{Header, HeaderFormat};
-	use hyper::Error as HyperError;
-	use std::fmt::{Formatter, Result as FmtResult};
-	pub use std::net::IpAddr;
-	use std::str;
-
-	#[derive(Clone, Debug)]
-	pub struct XForwardedFor {
-		pub addrs: Vec<IpAddr>,
+use tokio_core::reactor::{Core, Handle};
+
+/// Build a response to a plain HTTP request.
+///
+/// `/` and `/status` return the server
+/// status as JSON, anything else is a 404.
+fn http_response(path: Option<&str>, status: &StatusHandle) -> Vec<u8> {
+	let (code, body) = match path {
+		Some("/") | Some("/status") => match status.get() {
+			Some(status) => ("200 OK", status.to_json()),
+			None => (
+				"503 Service Unavailable",
+				"{\"error\":\"starting\"}".to_owned(),
+			),
+		},
+		_ => ("404 Not Found", "{\"error\":\"not found\"}".to_owned()),
+	};
+
+	format!(
+		"HTTP/1.1 {}\r\n\
+		 Content-Type: application/json\r\n\
+		 Content-Length: {}\r\n\
+		 Access-Control-Allow-Origin: *\r\n\
+		 Connection: close\r\n\
+		 \r\n\
+		 {}",
+		code,
+		body.len(),
+		body
+	).into_bytes()
+}
+
+/// Connection sinks only report IO errors,
+/// anything else is turned into one.
+fn into_io_error(e: WebSocketError) -> io::Error {
+	match e {
+		WebSocketError::IoError(e) => e,
+		e => io::Error::new(io::ErrorKind::Other, e.to_string()),
 	}
+}
 
-	impl Header for XForwardedFor {
-		fn header_name() -> &'static str {
-			return "X-Forwarded-For";
-		}
+/// Load a certificate and private key from
+/// a PKCS #12 archive. One can be created from
+/// PEM files with
+/// ```text
+/// openssl pkcs12 -export -in cert.pem -inkey key.pem -out identity.p12
+/// ```
+fn load_identity(path: &Path, password: &str) -> io::Result<TlsAcceptor> {
+	let mut der = vec![];
+	File::open(path)?.read_to_end(&mut der)?;
+
+	Pkcs12::from_der(&der, password)
+		.and_then(TlsAcceptor::builder)
+		.and_then(|builder| builder.build())
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
 
-		fn parse_header(raw: &[Vec<u8>]) -> Result<Self, HyperError> {
-			if raw.len() != 1 {
-				return Err(HyperError::Header);
-			}
+/// Accepts websocket connections on a TCP address.
+///
+/// Plain HTTP requests made to the same address
+/// are answered with the server status.
+pub struct WebsocketTransport<A> {
+	addr: A,
+	tls: Option<(A, TlsAcceptor)>,
+	status: StatusHandle,
+	proxies: TrustedProxies,
+}
 
-			let s = match str::from_utf8(&raw[0]) {
-				Ok(s) => s,
-				Err(e) => return Err(HyperError::Utf8(e)),
-			};
+impl<A> WebsocketTransport<A> {
+	pub fn new(addr: A) -> Self {
+		Self {
+			addr,
+			tls: None,
+			status: StatusHandle::default(),
+			proxies: TrustedProxies::default(),
+		}
+	}
 
-			let mut addrs = vec![];
+	/// Believe the forwarding headers set by
+	/// these proxies when working out the
+	/// address of a client.
+	pub fn with_trusted_proxies(mut self, proxies: TrustedProxies) -> Self {
+		self.proxies = proxies;
+		self
+	}
 
-			for s in s.split(',') {
-				addrs.push(match s.parse() {
-					Ok(v) => v,
-					Err(_) => return Err(HyperError::Header),
-				});
-			}
+	/// Also accept `wss://` connections on a
+	/// separate address, using the certificate
+	/// and key in the PKCS #12 archive at `path`.
+	pub fn with_tls<P>(mut self, addr: A, path: P, password: &str) -> io::Result<Self>
+	where
+		P: AsRef<Path>,
+	{
+		self.tls = Some((addr, load_identity(path.as_ref(), password)?));
+		Ok(self)
+	}
 
-			Ok(Self { addrs })
+	/// Serve the status from `status` to
+	/// non-websocket requests.
+	pub fn with_status(mut self, status: StatusHandle) -> Self {
+		self.status = status;
+		self
+	}
+}
+
+impl<A> WebsocketTransport<A>
+where
+	A: ToSocketAddrs + Debug + Send + 'static,
+{
+	/// Start listening on every address now, so
+	/// that errors are reported here instead of
+	/// when the server starts.
+	pub fn bind(self) -> io::Result<ListeningTransport> {
+		let (ready, bound) = channel();
+		let (start, events) = channel();
+
+		// The acceptor needs to run on its own thread
+		// to avoid delaying packets
+		thread::spawn(move || run_acceptor(self, ready, events));
+
+		match bound.recv() {
+			Ok(Ok((addr, tls_addr))) => Ok(ListeningTransport {
+				start,
+				addr,
+				tls_addr,
+			}),
+			Ok(Err(e)) => Err(e),
+			Err(_) => Err(io::Error::new(
+				io::ErrorKind::Other,
+				"websocket acceptor stopped",
+			)),
 		}
 	}
+}
 
-	impl HeaderFormat for XForwardedFor {
-		fn fmt_header(&self, fmt: &mut Formatter) -> FmtResult {
-			let strs = self
-				.addrs
-				.iter()
-				.map(|x| x.to_string())
-				.collect::<Vec<String>>();
-			write!(fmt, "{}", strs.join(", "))
+impl<A> Transport for WebsocketTransport<A>
+where
+	A: ToSocketAddrs + Debug + Send + 'static,
+{
+	fn start(self: Box<Self>, channel: Sender<ConnectionEvent>) {
+		match self.bind() {
+			Ok(transport) => Box::new(transport).start(channel),
+			Err(e) => error!(
+				target: "server",
+				"Unable to start websocket server: {}",
+				e
+			),
 		}
 	}
 }
 
-#[cfg(feature = "proxied")]
-use self::hyperuse::*;
+/// A [`WebsocketTransport`](struct.WebsocketTransport.html)
+/// that is already listening. Connections are
+/// accepted once the server is started.
+pub struct ListeningTransport {
+	start: Sender<Sender<ConnectionEvent>>,
+	addr: SocketAddr,
+	tls_addr: Option<SocketAddr>,
+}
 
-use tokio_core::reactor::Core;
+impl ListeningTransport {
+	/// The address that websockets are accepted
+	/// on, useful when binding to port 0.
+	pub fn local_addr(&self) -> SocketAddr {
+		self.addr
+	}
 
-const RESPONSE_STR: &'static [u8] = b"\
-418 IM_A_TEAPOT\n\
-Content-Type: text/html\n\
-\n\
-<body>\
-	<img src=\"https://upload.wikimedia.org/wikipedia/commons/thumb/4/44/Black_tea_pot_cropped.jpg/330px-Black_tea_pot_cropped.jpg\"\
-</body>";
+	/// The address that TLS connections are
+	/// accepted on, if there is one.
+	pub fn tls_addr(&self) -> Option<SocketAddr> {
+		self.tls_addr
+	}
+}
 
-pub fn run_acceptor<A>(addr: A, channel: Sender<ConnectionEvent>)
-where
+impl Transport for ListeningTransport {
+	fn start(self: Box<Self>, channel: Sender<ConnectionEvent>) {
+		// The acceptor has already stopped if this
+		// fails, the error has been logged there.
+		self.start.send(channel).err();
+	}
+}
+
+type Bound = io::Result<(SocketAddr, Option<SocketAddr>)>;
+
+fn run_acceptor<A>(
+	transport: WebsocketTransport<A>,
+	ready: Sender<Bound>,
+	start: Receiver<Sender<ConnectionEvent>>,
+) where
 	A: ToSocketAddrs + Debug,
 {
+	let WebsocketTransport {
+		addr,
+		tls,
+		status,
+		proxies,
+	} = transport;
+
 	info!(
 		target: "server",
 		"starting server at {:?}",
 		addr
 	);
 
-	let mut reactor = Core::new().unwrap();
+	let mut reactor = match Core::new() {
+		Ok(reactor) => reactor,
+		Err(e) => return ready.send(Err(e)).unwrap_or(()),
+	};
 	let handle = reactor.handle();
 
-	let socket = Server::bind(addr, &handle).unwrap();
+	let socket = match Server::bind(addr, &handle) {
+		Ok(socket) => socket,
+		Err(e) => return ready.send(Err(e)).unwrap_or(()),
+	};
+
+	let secure = match tls {
+		Some((addr, acceptor)) => {
+			info!(
+				target: "server",
+				"starting TLS server at {:?}",
+				addr
+			);
+
+			match Server::bind_secure(addr, acceptor, &handle) {
+				Ok(socket) => Some(socket),
+				Err(e) => return ready.send(Err(e)).unwrap_or(()),
+			}
+		}
+		None => None,
+	};
+
+	let addrs = socket.local_addr().and_then(|addr| match secure {
+		Some(ref secure) => Ok((addr, Some(secure.local_addr()?))),
+		None => Ok((addr, None)),
+	});
+	let failed = addrs.is_err();
+
+	if ready.send(addrs).is_err() || failed {
+		return;
+	}
+
+	// Wait for the server to start
+	let channel = match start.recv() {
+		Ok(channel) => channel,
+		Err(_) => return,
+	};
+
+	let incoming = socket.incoming();
+
+	// Make a best-effort attempt to
+	// set TCP_NODELAY. If this fails,
+	// then the client will just be
+	// using a less optimal stream.
+	#[cfg(feature = "nodelay")]
+	let incoming = incoming.map(|(upgrade, addr)| {
+		upgrade.stream.set_nodelay(true).err();
+		(upgrade, addr)
+	});
+
+	let mut listeners = vec![accept(incoming, &handle, &channel, &status, &proxies)];
+
+	if let Some(secure) = secure {
+		let incoming = secure.incoming();
+
+		#[cfg(feature = "nodelay")]
+		let incoming = incoming.map(|(upgrade, addr)| {
+			upgrade.stream.get_ref().get_ref().set_nodelay(true).err();
+			(upgrade, addr)
+		});
+
+		listeners.push(accept(incoming, &handle, &channel, &status, &proxies));
+	}
+
+	if let Err(()) = reactor.run(future::join_all(listeners)) {
+		error!(target: "server", "Websocket acceptor stopped");
+	}
+}
 
-	let f = socket
-		.incoming()
-		.map_err(|e| {
+/// Accept websocket connections from a listener
+/// and pass their packets on to the server.
+fn accept<S, B, I>(
+	incoming: I,
+	handle: &Handle,
+	channel: &Sender<ConnectionEvent>,
+	status: &StatusHandle,
+	proxies: &TrustedProxies,
+) -> Box<Future<Item = (), Error = ()>>
+where
+	S: AsyncStream + Send + 'static,
+	B: 'static,
+	I: Stream<Item = (Upgrade<S>, SocketAddr), Error = InvalidConnection<S, B>> + 'static,
+{
+	let handle = handle.clone();
+	let channel = channel.clone();
+	let status = status.clone();
+	let proxies = proxies.clone();
+
+	let f = incoming
+		.map_err(move |e| {
 			info!(
 				"A client failed to connect with error: {}",
 				e.error
 			);
 
 			if let Some(mut stream) = e.stream {
+				let path = match e.parsed {
+					Some(ref req) => match req.subject.1 {
+						RequestUri::AbsolutePath(ref path) => Some(path.clone()),
+						_ => None,
+					},
+					None => None,
+				};
+				// Ignore any query string
+				let path = path.as_ref().map(|p| p.split('?').next().unwrap());
+
 				// Make a best-effort attempt to
 				// send a response, if this fails
 				// we ignore it
-				stream.write_all(RESPONSE_STR).err();
+				stream.write_all(&http_response(path, &status)).err();
 			}
 		})
 		// The following two operators filter out
@@ -127,26 +367,9 @@ where
 		.for_each(move |(upgrade, addr)| {
 			let id = ConnectionId::new();
 
-			// Make a best-effort attempt to 
-			// set TCP_NODELAY. If this fails,
-			// then the client will just be 
-			// using a less optimal stream.
-			#[cfg(feature="nodelay")]
-			upgrade.stream.set_nodelay(true).err();
-
 			let origin = upgrade.origin().map(|x| x.to_owned());
 
-			#[cfg(feature="proxied")]
-			let realaddr = match upgrade.request.headers.get::<XForwardedFor>() {
-				Some(v) => match v.addrs.get(0) {
-					Some(v) => *v,
-					None => addr.ip()
-				},
-				None => addr.ip()
-			};
-
-			#[cfg(not(feature="proxied"))]
-			let realaddr = addr.ip();
+			let realaddr = proxies.client_addr(addr.ip(), &upgrade.request.headers);
 
 			let f = upgrade.accept()
 			.and_then({
@@ -157,7 +380,15 @@ where
 							id.0, realaddr
 						);
 
+					// Swap the codec for one that can write shared
+					// buffers directly. Decoding is unaffected.
+					#[allow(deprecated)]
+					let s = Framed::from_parts(
+						s.into_parts(),
+						MessageCodec::<OutboundMessage>::new(Context::Server)
+					);
 					let (sink, stream) = s.split();
+					let sink: ConnectionSink = Box::new(sink.sink_map_err(into_io_error));
 
 					channel.send(ConnectionEvent::ConnectionOpen(ConnectionOpen {
 							conn: id,
@@ -234,5 +465,5 @@ where
 			Ok(())
 		});
 
-	reactor.run(f).unwrap();
+	Box::new(f)
 }
@@ -6,8 +6,7 @@ use component::channel::OnPlayerTerrainCollisionReader;
 use component::time::{StartTime, ThisFrame};
 
 use airmash_protocol::server::EventBounce;
-use airmash_protocol::{to_bytes, ServerPacket};
-use websocket::OwnedMessage;
+use airmash_protocol::ServerPacket;
 
 pub struct BounceSystem {
 	reader: Option<OnPlayerTerrainCollisionReader>,
@@ -24,8 +23,8 @@ pub struct BounceSystemData<'a> {
 	pub conns: Read<'a, Connections>,
 	pub config: Read<'a, Config>,
 	pub channel: Read<'a, OnPlayerTerrainCollision>,
-	pub thisframe: Read<'a, ThisFrame>,
-	pub starttime: Read<'a, StartTime>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
+	pub starttime: ReadExpect<'a, StartTime>,
 }
 
 impl BounceSystem {
@@ -91,16 +90,14 @@ impl<'a> System<'a> for BounceSystem {
 
 				let packet = EventBounce {
 					clock: (data.thisframe.0 - data.starttime.0).to_clock(),
-					id: ent,
+					id: data.conns.entity_ids().wire(ent),
 					pos: *pos,
 					rot: *rot,
 					speed: vel,
 					keystate: state,
 				};
 
-				data.conns.send_to_all(OwnedMessage::Binary(
-					to_bytes(&ServerPacket::EventBounce(packet)).unwrap(),
-				));
+				data.conns.send_to_all(ServerPacket::EventBounce(packet));
 			}
 		}
 	}
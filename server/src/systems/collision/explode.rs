@@ -6,8 +6,6 @@ use types::*;
 use component::channel::*;
 
 use protocol::server::{MobDespawnCoords, ServerPacket};
-use protocol::to_bytes;
-use websocket::OwnedMessage;
 
 pub struct MissileExplodeSystem {
 	reader: Option<OnMissileTerrainCollisionReader>,
@@ -53,17 +51,17 @@ impl<'a> System<'a> for MissileExplodeSystem {
 				missile_ent = c1.ent;
 			}
 
+			data.conns.entity_ids().release(missile_ent);
 			data.entities.delete(missile_ent).unwrap();
 
 			let packet = MobDespawnCoords {
-				id: missile_ent,
+				id: data.conns.entity_ids().wire(missile_ent),
 				ty: *data.types.get(missile_ent).unwrap(),
 				pos: *data.pos.get(missile_ent).unwrap(),
 			};
 
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::MobDespawnCoords(packet)).unwrap(),
-			));
+			data.conns
+				.send_to_all(ServerPacket::MobDespawnCoords(packet));
 		}
 	}
 }
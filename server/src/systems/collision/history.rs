@@ -0,0 +1,64 @@
+use specs::prelude::*;
+
+use types::systemdata::IsAlive;
+use types::*;
+
+use component::time::{LastFrame, ThisFrame};
+
+/// Keeps track of where every plane has been
+/// so that missile hits can be checked against
+/// where the shooter saw their target.
+pub struct PositionHistorySystem;
+
+#[derive(SystemData)]
+pub struct PositionHistorySystemData<'a> {
+	pub thisframe: ReadExpect<'a, ThisFrame>,
+	pub lastframe: ReadExpect<'a, LastFrame>,
+	pub limits: Read<'a, LagCompensation>,
+	pub entities: Entities<'a>,
+	pub is_alive: IsAlive<'a>,
+
+	pub pos: ReadStorage<'a, Position>,
+	pub rot: ReadStorage<'a, Rotation>,
+	pub history: WriteStorage<'a, PositionHistory>,
+}
+
+impl<'a> System<'a> for PositionHistorySystem {
+	type SystemData = PositionHistorySystemData<'a>;
+
+	fn run(&mut self, mut data: Self::SystemData) {
+		let now = data.thisframe.0;
+		// Keep one extra frame so that there is always
+		// a snapshot at or before the furthest rewind.
+		let keep = data.limits.max_rewind + (now - data.lastframe.0);
+		let is_alive = &data.is_alive;
+
+		for (ent, pos, rot, history) in
+			(&*data.entities, &data.pos, &data.rot, &mut data.history).join()
+		{
+			// Dead planes shouldn't be hit at where they
+			// were before they respawned.
+			if !is_alive.get(ent) {
+				history.clear();
+				continue;
+			}
+
+			history.record(now, *pos, *rot, keep);
+		}
+	}
+}
+
+use dispatch::SystemInfo;
+use systems::PositionUpdate;
+
+impl SystemInfo for PositionHistorySystem {
+	type Dependencies = PositionUpdate;
+
+	fn name() -> &'static str {
+		concat!(module_path!(), "::", line!())
+	}
+
+	fn new() -> Self {
+		PositionHistorySystem
+	}
+}
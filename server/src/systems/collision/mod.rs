@@ -1,5 +1,6 @@
 mod bounce;
 mod explode;
+mod history;
 mod missile;
 mod plane;
 mod player_missile;
@@ -10,6 +11,7 @@ pub use self::register::register;
 
 pub use self::bounce::BounceSystem;
 pub use self::explode::MissileExplodeSystem;
+pub use self::history::PositionHistorySystem;
 pub use self::missile::MissileTerrainCollisionSystem;
 pub use self::plane::PlaneCollisionSystem;
 pub use self::player_missile::PlayerMissileCollisionSystem;
@@ -1,4 +1,4 @@
-use fnv::FnvHashSet;
+use fnv::{FnvHashMap, FnvHashSet};
 use specs::prelude::*;
 
 use types::collision::*;
@@ -7,13 +7,38 @@ use types::*;
 use component::channel::*;
 use component::event::PlayerMissileCollision;
 use component::flag::IsSpectating;
+use component::time::{MobSpawnTime, ThisFrame, TickRate};
 
+use std::time::Duration;
+
+fn nanos(d: Duration) -> u64 {
+	d.as_secs() * 1_000_000_000 + d.subsec_nanos() as u64
+}
+
+/// Number of frames to rewind planes by.
+///
+/// Rewinds are rounded down to a whole number
+/// of frames so that missiles that need about
+/// the same rewind can share buckets.
+fn rewind_frames(rewind: Duration, tick: Duration) -> u32 {
+	(nanos(rewind) / nanos(tick).max(1)) as u32
+}
+
+/// Checks missiles against planes.
+///
+/// Newly fired missiles are checked against
+/// where planes were when the shooter saw them,
+/// roughly one round trip in the past. That
+/// rewind shrinks as the missile ages, see
+/// [`MissileRewind`](../../types/struct.MissileRewind.html).
 pub struct PlayerMissileCollisionSystem;
 
 #[derive(SystemData)]
 pub struct PlayerMissileCollisionSystemData<'a> {
 	pub channel: Write<'a, OnPlayerMissileCollision>,
 	pub config: Read<'a, Config>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
+	pub tick: ReadExpect<'a, TickRate>,
 	pub ent: Entities<'a>,
 
 	pub pos: ReadStorage<'a, Position>,
@@ -23,9 +48,12 @@ pub struct PlayerMissileCollisionSystemData<'a> {
 	pub player_flag: ReadStorage<'a, IsPlayer>,
 	pub isspec: ReadStorage<'a, IsSpectating>,
 	pub isdead: ReadStorage<'a, IsDead>,
+	pub history: ReadStorage<'a, PositionHistory>,
 
 	pub mob: ReadStorage<'a, Mob>,
 	pub missile_flag: ReadStorage<'a, IsMissile>,
+	pub spawntime: ReadStorage<'a, MobSpawnTime>,
+	pub rewinds: ReadStorage<'a, MissileRewind>,
 }
 
 impl PlayerMissileCollisionSystem {
@@ -41,6 +69,8 @@ impl<'a> System<'a> for PlayerMissileCollisionSystem {
 		let Self::SystemData {
 			mut channel,
 			config,
+			thisframe,
+			tick,
 			ent,
 
 			pos,
@@ -50,39 +80,74 @@ impl<'a> System<'a> for PlayerMissileCollisionSystem {
 			player_flag,
 			isspec,
 			isdead,
+			history,
 
 			mob,
 			missile_flag,
+			spawntime,
+			rewinds,
 		} = data;
 
-		let mut buckets = Array2D::<Bucket>::new(BUCKETS_X, BUCKETS_Y);
-
-		(&*ent, &pos, &rot, &team, &plane, &player_flag)
-			.join()
-			.filter(|(ent, _, _, _, _, _)| isspec.get(*ent).is_none() && isdead.get(*ent).is_none())
-			.for_each(|(ent, pos, rot, team, plane, _)| {
-				let ref cfg = config.planes[*plane];
+		let rewind = |missile: Entity| -> u32 {
+			let age = match spawntime.get(missile) {
+				Some(spawntime) => thisframe.0 - spawntime.0,
+				None => Duration::from_secs(0),
+			};
+
+			match rewinds.get(missile) {
+				Some(rewind) => rewind_frames(rewind.at(age), tick.0),
+				None => 0,
+			}
+		};
+
+		// Place planes where they were `frames` frames ago
+		let build = |frames: u32| {
+			let mut buckets = Array2D::<Bucket>::new(BUCKETS_X, BUCKETS_Y);
+			let time = thisframe.0 - tick.0 * frames;
+
+			(&*ent, &pos, &rot, &team, &plane, &player_flag)
+				.join()
+				.filter(|(ent, _, _, _, _, _)| isspec.get(*ent).is_none() && isdead.get(*ent).is_none())
+				.for_each(|(ent, pos, rot, team, plane, _)| {
+					let ref cfg = config.planes[*plane];
+
+					let (pos, rot) = match history.get(ent).and_then(|h| h.at(time)) {
+						Some(ref snapshot) if frames != 0 => (snapshot.pos, snapshot.rot),
+						_ => (*pos, *rot),
+					};
 
-				cfg.hit_circles.iter().for_each(|hc| {
-					let offset = hc.offset.rotate(*rot);
+					cfg.hit_circles.iter().for_each(|hc| {
+						let offset = hc.offset.rotate(rot);
 
-					let circle = HitCircle {
-						pos: *pos + offset,
-						rad: hc.radius,
-						layer: team.0,
-						ent: ent,
-					};
+						let circle = HitCircle {
+							pos: pos + offset,
+							rad: hc.radius,
+							layer: team.0,
+							ent: ent,
+						};
 
-					for coord in intersected_buckets(circle.pos, circle.rad) {
-						buckets.get_or_insert(coord).push(circle);
-					}
+						for coord in intersected_buckets(circle.pos, circle.rad) {
+							buckets.get_or_insert(coord).push(circle);
+						}
+					});
 				});
-			});
+
+			buckets
+		};
+
+		let buckets = (&*ent, &missile_flag)
+			.join()
+			.map(|(ent, _)| rewind(ent))
+			.collect::<FnvHashSet<_>>()
+			.into_iter()
+			.map(|frames| (frames, build(frames)))
+			.collect::<FnvHashMap<_, _>>();
 
 		let collisions = (&*ent, &pos, &team, &mob, &missile_flag)
 			.par_join()
 			.map(|(ent, pos, team, mob, _)| {
 				let mut collisions = vec![];
+				let buckets = &buckets[&rewind(ent)];
 
 				for (offset, rad) in COLLIDERS[mob].iter() {
 					let hc = HitCircle {
@@ -111,10 +176,11 @@ impl<'a> System<'a> for PlayerMissileCollisionSystem {
 }
 
 use dispatch::SystemInfo;
+use systems::collision::PositionHistorySystem;
 use systems::PositionUpdate;
 
 impl SystemInfo for PlayerMissileCollisionSystem {
-	type Dependencies = PositionUpdate;
+	type Dependencies = (PositionUpdate, PositionHistorySystem);
 
 	fn name() -> &'static str {
 		concat!(module_path!(), "::", line!())
@@ -4,6 +4,7 @@ use dispatch::Builder;
 pub fn register<'a, 'b>(disp: Builder<'a, 'b>) -> Builder<'a, 'b> {
 	disp.with::<PlaneCollisionSystem>()
 		.with::<MissileTerrainCollisionSystem>()
+		.with::<PositionHistorySystem>()
 		.with::<PlayerMissileCollisionSystem>()
 		.with::<BounceSystem>()
 		.with::<MissileExplodeSystem>()
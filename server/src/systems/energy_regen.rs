@@ -8,8 +8,8 @@ pub struct EnergyRegenSystem;
 
 #[derive(SystemData)]
 pub struct EnergyRegenSystemData<'a> {
-	pub lastframe: Read<'a, LastFrame>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub lastframe: ReadExpect<'a, LastFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 	pub config: Read<'a, Config>,
 
 	pub energy: WriteStorage<'a, Energy>,
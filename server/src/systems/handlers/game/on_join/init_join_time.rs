@@ -14,7 +14,7 @@ pub struct InitJoinTime {
 #[derive(SystemData)]
 pub struct InitJoinTimeData<'a> {
 	pub channel: Read<'a, OnPlayerJoin>,
-	pub this_frame: Read<'a, ThisFrame>,
+	pub this_frame: ReadExpect<'a, ThisFrame>,
 
 	pub join_time: WriteStorage<'a, JoinTime>,
 }
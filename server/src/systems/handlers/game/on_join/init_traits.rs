@@ -19,6 +19,7 @@ pub struct InitTraits {
 pub struct InitTraitsData<'a> {
 	pub channel: Read<'a, OnPlayerJoin>,
 	pub gamemode: GameModeWriter<'a, GameMode>,
+	pub conns: Read<'a, Connections>,
 
 	pub powerups: WriteStorage<'a, Powerups>,
 	pub upgrades: WriteStorage<'a, Upgrades>,
@@ -27,6 +28,7 @@ pub struct InitTraitsData<'a> {
 	pub team: WriteStorage<'a, Team>,
 	pub plane: WriteStorage<'a, Plane>,
 	pub status: WriteStorage<'a, Status>,
+	pub pure_spec: ReadStorage<'a, IsPureSpectator>,
 }
 
 impl<'a> System<'a> for InitTraits {
@@ -42,6 +44,7 @@ impl<'a> System<'a> for InitTraits {
 		let Self::SystemData {
 			channel,
 			mut gamemode,
+			conns,
 
 			mut powerups,
 			mut upgrades,
@@ -50,10 +53,19 @@ impl<'a> System<'a> for InitTraits {
 			mut team,
 			mut plane,
 			mut status,
+			pure_spec,
 		} = data;
 
 		for evt in channel.read(self.reader.as_mut().unwrap()) {
-			let player_team = gamemode.get_mut().assign_team(evt.0);
+			let id = conns.entity_ids().wire(evt.0);
+
+			// Pure spectators get a team of their own
+			// so that the gamemode never counts them.
+			let player_team = if pure_spec.get(evt.0).is_some() {
+				Team(id.0)
+			} else {
+				gamemode.get_mut().assign_team(evt.0, id.0)
+			};
 
 			powerups.insert(evt.0, Powerups::default()).unwrap();
 			upgrades.insert(evt.0, Upgrades::default()).unwrap();
@@ -20,6 +20,7 @@ pub struct InitTransformData<'a> {
 	pub rot: WriteStorage<'a, Rotation>,
 	pub vel: WriteStorage<'a, Velocity>,
 	pub team: ReadStorage<'a, Team>,
+	pub pure_spec: ReadStorage<'a, IsPureSpectator>,
 }
 
 impl<'a> System<'a> for InitTransform {
@@ -40,12 +41,20 @@ impl<'a> System<'a> for InitTransform {
 			mut rot,
 			mut vel,
 			team,
+			pure_spec,
 		} = data;
 
 		for evt in channel.read(self.reader.as_mut().unwrap()) {
-			let player_pos = gamemode
-				.get_mut()
-				.spawn_pos(evt.0, *team.get(evt.0).unwrap());
+			// Spectators start with the camera at the
+			// centre of the map, the gamemode doesn't
+			// know where to spawn someone without a team.
+			let player_pos = if pure_spec.get(evt.0).is_some() {
+				Position::default()
+			} else {
+				gamemode
+					.get_mut()
+					.spawn_pos(evt.0, *team.get(evt.0).unwrap())
+			};
 
 			pos.insert(evt.0, player_pos).unwrap();
 			rot.insert(evt.0, Rotation::default()).unwrap();
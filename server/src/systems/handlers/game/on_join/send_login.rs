@@ -3,12 +3,11 @@ use types::*;
 
 use GameMode;
 use GameModeWriter;
-use OwnedMessage;
 use SystemInfo;
 
 use component::channel::*;
 use protocol::server::{Login, LoginPlayer};
-use protocol::{to_bytes, ServerPacket, Upgrades as ProtocolUpgrades};
+use protocol::{ServerPacket, Upgrades as ProtocolUpgrades};
 
 pub struct SendLogin {
 	reader: Option<OnPlayerJoinReader>,
@@ -59,7 +58,7 @@ impl SendLogin {
 					};
 
 					LoginPlayer {
-						id: ent,
+						id: data.conns.entity_ids().wire(ent),
 						status: *status,
 						level: *level,
 						name: name.0.clone(),
@@ -92,7 +91,7 @@ impl<'a> System<'a> for SendLogin {
 			// TODO: Correct clock value and pass session through
 			let packet = Login {
 				clock: 0,
-				id: evt.0,
+				id: data.conns.entity_ids().wire(evt.0),
 				room: data.gamemode.get().room(),
 				success: true,
 				token: "none".to_owned(),
@@ -101,10 +100,8 @@ impl<'a> System<'a> for SendLogin {
 				players: player_data,
 			};
 
-			data.conns.send_to_player(
-				evt.0,
-				OwnedMessage::Binary(to_bytes(&ServerPacket::Login(packet)).unwrap()),
-			);
+			data.conns
+				.send_to_player(evt.0, ServerPacket::Login(packet));
 		}
 	}
 }
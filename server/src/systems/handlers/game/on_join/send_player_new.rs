@@ -1,12 +1,11 @@
 use specs::*;
 use types::*;
 
-use OwnedMessage;
 use SystemInfo;
 
 use component::channel::*;
 use protocol::server::PlayerNew;
-use protocol::{to_bytes, ServerPacket, Upgrades as ProtocolUpgrades};
+use protocol::{ServerPacket, Upgrades as ProtocolUpgrades};
 
 pub struct SendPlayerNew {
 	reader: Option<OnPlayerJoinReader>,
@@ -63,7 +62,7 @@ impl<'a> System<'a> for SendPlayerNew {
 			};
 
 			let player_new = PlayerNew {
-				id: evt.0,
+				id: conns.entity_ids().wire(evt.0),
 				status: *status.get(evt.0).unwrap(),
 				name: name.get(evt.0).unwrap().0.clone(),
 				ty: *plane.get(evt.0).unwrap(),
@@ -74,10 +73,7 @@ impl<'a> System<'a> for SendPlayerNew {
 				upgrades,
 			};
 
-			conns.send_to_others(
-				evt.0,
-				OwnedMessage::Binary(to_bytes(&ServerPacket::PlayerNew(player_new)).unwrap()),
-			);
+			conns.send_to_others(evt.0, ServerPacket::PlayerNew(player_new));
 		}
 	}
 }
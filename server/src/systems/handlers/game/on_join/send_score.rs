@@ -3,14 +3,13 @@ use types::*;
 
 use super::*;
 
-use OwnedMessage;
 use SystemInfo;
 
 use component::channel::*;
 use component::counter::*;
 
 use protocol::server::ScoreUpdate;
-use protocol::{to_bytes, ServerPacket};
+use protocol::ServerPacket;
 
 pub struct SendScoreUpdate {
 	reader: Option<OnPlayerJoinReader>,
@@ -57,7 +56,7 @@ impl<'a> System<'a> for SendScoreUpdate {
 			let total_deaths = total_deaths.get(evt.0).unwrap();
 
 			let packet = ScoreUpdate {
-				id: evt.0,
+				id: conns.entity_ids().wire(evt.0),
 				score: *score,
 				earnings: earnings.0,
 				upgrades: upgrades.unused,
@@ -65,9 +64,7 @@ impl<'a> System<'a> for SendScoreUpdate {
 				total_deaths: total_deaths.0,
 			};
 
-			conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::ScoreUpdate(packet)).unwrap(),
-			));
+			conns.send_to_all(ServerPacket::ScoreUpdate(packet));
 		}
 	}
 }
@@ -12,8 +12,7 @@ use component::event::TimerEvent;
 use component::time::ThisFrame;
 
 use protocol::server::PlayerKill;
-use protocol::{to_bytes, ServerPacket};
-use websocket::OwnedMessage;
+use protocol::ServerPacket;
 
 pub struct DisplayMessage {
 	reader: Option<OnPlayerKilledReader>,
@@ -25,7 +24,7 @@ pub struct DisplayMessageData<'a> {
 	pub channel: Read<'a, OnPlayerKilled>,
 	pub conns: Read<'a, Connections>,
 	pub timerevent: Write<'a, EventChannel<TimerEvent>>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 }
 
 impl DisplayMessage {
@@ -45,20 +44,19 @@ impl<'a> System<'a> for DisplayMessage {
 
 	fn run(&mut self, mut data: Self::SystemData) {
 		for evt in data.channel.read(self.reader.as_mut().unwrap()) {
+			let ids = data.conns.entity_ids();
 			let packet = PlayerKill {
-				id: evt.player,
-				killer: Some(evt.killer),
+				id: ids.wire(evt.player),
+				killer: Some(ids.wire(evt.killer)),
 				pos: evt.pos,
 			};
 
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::PlayerKill(packet)).unwrap(),
-			));
+			data.conns.send_to_all(ServerPacket::PlayerKill(packet));
 
 			data.timerevent.single_write(TimerEvent {
 				ty: *SCORE_BOARD,
 				instant: data.thisframe.0,
-				..Default::default()
+				data: None,
 			});
 		}
 	}
@@ -11,8 +11,7 @@ use component::event::TimerEvent;
 use component::time::ThisFrame;
 
 use protocol::server::ScoreUpdate;
-use protocol::{to_bytes, ServerPacket};
-use websocket::OwnedMessage;
+use protocol::ServerPacket;
 
 pub struct UpdateScore {
 	reader: Option<OnPlayerKilledReader>,
@@ -24,7 +23,7 @@ pub struct UpdateScoreData<'a> {
 	pub channel: Read<'a, OnPlayerKilled>,
 	pub conns: Read<'a, Connections>,
 	pub timerevent: Write<'a, EventChannel<TimerEvent>>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 
 	pub score: WriteStorage<'a, Score>,
 	pub powerups: WriteStorage<'a, Powerups>,
@@ -47,16 +46,15 @@ impl UpdateScore {
 		let total_kills = data.total_kills.get(player).unwrap().0;
 		let total_deaths = data.total_deaths.get(player).unwrap().0;
 
-		data.conns.send_to_all(OwnedMessage::Binary(
-			to_bytes(&ServerPacket::ScoreUpdate(ScoreUpdate {
-				id: player,
+		data.conns
+			.send_to_all(ServerPacket::ScoreUpdate(ScoreUpdate {
+				id: data.conns.entity_ids().wire(player),
 				score,
 				earnings,
 				upgrades: upgrades.unused,
 				total_deaths,
 				total_kills,
-			})).unwrap(),
-		));
+			}));
 	}
 }
 
@@ -7,9 +7,8 @@ use component::channel::*;
 use systems::spectate::CommandHandler;
 
 use protocol::server::PlayerKill;
-use protocol::{to_bytes, ServerPacket};
+use protocol::ServerPacket;
 
-use OwnedMessage;
 use SystemInfo;
 
 pub struct SendKillPacket {
@@ -43,15 +42,13 @@ impl<'a> System<'a> for SendKillPacket {
 			// indicates to the client that this
 			// was a player going into spec.
 			let packet = PlayerKill {
-				id: evt.player,
+				id: data.conns.entity_ids().wire(evt.player),
 				killer: None,
 				pos: Position::default(),
 			};
 
-			data.conns.send_to_player(
-				evt.player,
-				OwnedMessage::Binary(to_bytes(&ServerPacket::PlayerKill(packet)).unwrap()),
-			);
+			data.conns
+				.send_to_player(evt.player, ServerPacket::PlayerKill(packet));
 		}
 	}
 }
@@ -7,9 +7,8 @@ use component::channel::*;
 use systems::spectate::CommandHandler;
 
 use protocol::server::GameSpectate;
-use protocol::{to_bytes, ServerPacket};
+use protocol::ServerPacket;
 
-use OwnedMessage;
 use SystemInfo;
 
 pub struct SendSpectatePacket {
@@ -40,13 +39,11 @@ impl<'a> System<'a> for SendSpectatePacket {
 			}
 
 			let packet = GameSpectate {
-				id: evt.target.unwrap(),
+				id: data.conns.entity_ids().wire(evt.target.unwrap()),
 			};
 
-			data.conns.send_to_player(
-				evt.player,
-				OwnedMessage::Binary(to_bytes(&ServerPacket::GameSpectate(packet)).unwrap()),
-			);
+			data.conns
+				.send_to_player(evt.player, ServerPacket::GameSpectate(packet));
 		}
 	}
 }
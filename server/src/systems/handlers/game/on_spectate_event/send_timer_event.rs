@@ -21,7 +21,7 @@ pub struct SendTimerEventData<'a> {
 	pub conns: Read<'a, Connections>,
 	pub timerchannel: Write<'a, OnTimerEvent>,
 
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 }
 
 impl<'a> System<'a> for SendTimerEvent {
@@ -14,8 +14,7 @@ use component::flag::IsDead;
 use component::time::ThisFrame;
 
 use protocol::server::MobDespawnCoords;
-use protocol::{to_bytes, ServerPacket};
-use websocket::OwnedMessage;
+use protocol::ServerPacket;
 
 pub struct PlayerKilledCleanup {
 	reader: Option<OnPlayerKilledReader>,
@@ -26,7 +25,7 @@ pub struct PlayerKilledCleanupData<'a> {
 	pub entities: Entities<'a>,
 	pub channel: Read<'a, OnPlayerKilled>,
 	pub conns: Read<'a, Connections>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 	pub timerchannel: Write<'a, OnTimerEvent>,
 
 	pub name: ReadStorage<'a, Name>,
@@ -57,14 +56,13 @@ impl<'a> System<'a> for PlayerKilledCleanup {
 			data.isdead.insert(evt.player, IsDead).unwrap();
 
 			let despawn_packet = MobDespawnCoords {
-				id: evt.missile,
+				id: data.conns.entity_ids().wire(evt.missile),
 				ty: *data.mob.get(evt.missile).unwrap(),
 				pos: evt.pos,
 			};
 
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::MobDespawnCoords(despawn_packet)).unwrap(),
-			));
+			data.conns
+				.send_to_all(ServerPacket::MobDespawnCoords(despawn_packet));
 
 			let player = evt.player;
 
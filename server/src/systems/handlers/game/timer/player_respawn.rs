@@ -10,9 +10,8 @@ use systems::TimerHandler;
 use SystemInfo;
 
 use protocol::server::PlayerRespawn;
+use protocol::ServerPacket;
 use protocol::Upgrades as ProtocolUpgrades;
-use protocol::{to_bytes, ServerPacket};
-use OwnedMessage;
 
 pub struct PlayerRespawnSystem {
 	reader: Option<OnTimerEventReader>,
@@ -68,14 +67,13 @@ impl<'a> System<'a> for PlayerRespawnSystem {
 			data.is_dead.remove(player);
 
 			if data.is_spec.get(player).is_none() {
-				data.conns.send_to_all(OwnedMessage::Binary(
-					to_bytes(&ServerPacket::PlayerRespawn(PlayerRespawn {
-						id: player,
+				data.conns
+					.send_to_all(ServerPacket::PlayerRespawn(PlayerRespawn {
+						id: data.conns.entity_ids().wire(player),
 						pos: *data.pos.get(player).unwrap(),
 						rot: *data.rot.get(player).unwrap(),
 						upgrades: ProtocolUpgrades::default(),
-					})).unwrap(),
-				));
+					}));
 			}
 		}
 	}
@@ -0,0 +1,81 @@
+use specs::*;
+use types::*;
+
+use protocol::server::ServerCustom;
+use protocol::{ServerCustomType, ServerPacket};
+
+use component::channel::*;
+use types::extension::{ClientExtensions, ExtensionRegistry, EXTENSIONS_COMMAND};
+
+/// Handles the `"extensions"` command that
+/// clients use to opt in to extension
+/// messages.
+pub struct ExtensionsHandler {
+	reader: Option<OnCommandReader>,
+}
+
+#[derive(SystemData)]
+pub struct ExtensionsHandlerData<'a> {
+	channel: Read<'a, OnCommand>,
+	conns: Read<'a, Connections>,
+	registry: Read<'a, ExtensionRegistry>,
+
+	extensions: WriteStorage<'a, ClientExtensions>,
+}
+
+impl ExtensionsHandler {
+	pub fn new() -> Self {
+		Self { reader: None }
+	}
+}
+
+impl<'a> System<'a> for ExtensionsHandler {
+	type SystemData = ExtensionsHandlerData<'a>;
+
+	fn setup(&mut self, res: &mut Resources) {
+		Self::SystemData::setup(res);
+
+		self.reader = Some(res.fetch_mut::<OnCommand>().register_reader());
+	}
+
+	fn run(&mut self, mut data: Self::SystemData) {
+		for (id, packet) in data.channel.read(self.reader.as_mut().unwrap()) {
+			if packet.com != EXTENSIONS_COMMAND {
+				continue;
+			}
+
+			let player = match data.conns.associated_player(*id) {
+				Some(p) => p,
+				None => continue,
+			};
+
+			data.extensions
+				.insert(player, ClientExtensions::parse(&packet.data))
+				.unwrap();
+
+			let hello = ServerPacket::ServerCustom(ServerCustom {
+				ty: ServerCustomType::Extension,
+				data: data.registry.hello(),
+			});
+
+			data.conns.send_to(*id, hello);
+		}
+	}
+}
+
+use dispatch::SystemInfo;
+use handlers::LoginHandler;
+
+impl SystemInfo for ExtensionsHandler {
+	// A client may send the handshake in the same
+	// frame as its login.
+	type Dependencies = LoginHandler;
+
+	fn new() -> Self {
+		Self::new()
+	}
+
+	fn name() -> &'static str {
+		concat!(module_path!(), "::", line!())
+	}
+}
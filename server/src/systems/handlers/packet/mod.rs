@@ -1,5 +1,7 @@
 pub use handlers::*;
 
+mod extensions;
 mod whisper;
 
+pub use self::extensions::ExtensionsHandler;
 pub use self::whisper::WhisperHandler;
@@ -4,8 +4,6 @@ use types::*;
 
 use protocol::client::Whisper;
 use protocol::server::{ChatWhisper, ServerPacket};
-use protocol::to_bytes;
-use OwnedMessage;
 
 use component::flag::IsPlayer;
 
@@ -51,7 +49,10 @@ impl<'a> System<'a> for WhisperHandler {
 				None => continue,
 			};
 
-			let to = data.entities.entity(evt.1.id as u32);
+			let to = match data.conns.entity_ids().entity(evt.1.id) {
+				Some(to) => to,
+				None => continue,
+			};
 
 			if !data.entities.is_alive(to) {
 				// The player doesn't exist
@@ -62,19 +63,18 @@ impl<'a> System<'a> for WhisperHandler {
 				continue;
 			}
 
+			let ids = data.conns.entity_ids();
 			let chat = ChatWhisper {
-				from: player,
-				to: to,
+				from: ids.wire(player),
+				to: ids.wire(to),
 				text: evt.1.text.clone(),
 			};
 
 			let packet = ServerPacket::ChatWhisper(chat);
 
-			data.conns
-				.send_to(evt.0, OwnedMessage::Binary(to_bytes(&packet).unwrap()));
+			data.conns.send_to(evt.0, &packet);
 
-			data.conns
-				.send_to_player(to, OwnedMessage::Binary(to_bytes(&packet).unwrap()));
+			data.conns.send_to_player(to, packet);
 		}
 	}
 }
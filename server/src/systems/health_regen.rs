@@ -16,8 +16,8 @@ pub struct HealthRegenSystemData<'a> {
 	pub plane: ReadStorage<'a, Plane>,
 
 	pub config: Read<'a, Config>,
-	pub lastframe: Read<'a, LastFrame>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub lastframe: ReadExpect<'a, LastFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 }
 
 impl<'a> System<'a> for HealthRegenSystem {
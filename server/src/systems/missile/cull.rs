@@ -5,8 +5,7 @@ use component::time::{MobSpawnTime, ThisFrame};
 use dispatch::SystemInfo;
 
 use airmash_protocol::server::MobDespawn;
-use airmash_protocol::{to_bytes, ServerPacket};
-use websocket::OwnedMessage;
+use airmash_protocol::ServerPacket;
 
 pub struct MissileCull;
 
@@ -16,7 +15,7 @@ pub struct MissileCullData<'a> {
 	pub spawntime: ReadStorage<'a, MobSpawnTime>,
 	pub mob: ReadStorage<'a, Mob>,
 	pub config: Read<'a, Config>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 	pub conns: Read<'a, Connections>,
 }
 
@@ -38,13 +37,15 @@ impl<'a> System<'a> for MissileCull {
 				}
 			})
 			.for_each(|(ent, mob)| {
+				data.conns.entity_ids().release(ent);
 				data.ents.delete(ent).unwrap();
 
-				let packet = MobDespawn { id: ent, ty: mob };
+				let packet = MobDespawn {
+					id: data.conns.entity_ids().wire(ent),
+					ty: mob,
+				};
 
-				data.conns.send_to_all(OwnedMessage::Binary(
-					to_bytes(&ServerPacket::MobDespawn(packet)).unwrap(),
-				));
+				data.conns.broadcast(&ServerPacket::MobDespawn(packet));
 			});
 	}
 }
@@ -6,8 +6,7 @@ use component::reference::PlayerRef;
 use component::time::*;
 
 use airmash_protocol::server::{PlayerFire, PlayerFireProjectile};
-use airmash_protocol::{to_bytes, ServerPacket};
-use websocket::OwnedMessage;
+use airmash_protocol::ServerPacket;
 
 pub struct MissileFireHandler;
 
@@ -26,10 +25,13 @@ pub struct MissileFireHandlerData<'a> {
 	pub mobs: WriteStorage<'a, Mob>,
 	pub owner: WriteStorage<'a, PlayerRef>,
 	pub conns: Read<'a, Connections>,
-	pub starttime: Read<'a, StartTime>,
-	pub thisframe: Read<'a, ThisFrame>,
+	pub starttime: ReadExpect<'a, StartTime>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
 	pub spawntime: WriteStorage<'a, MobSpawnTime>,
 	pub lastshot: WriteStorage<'a, LastShotTime>,
+	pub pingdata: ReadStorage<'a, PingData>,
+	pub limits: Read<'a, LagCompensation>,
+	pub rewind: WriteStorage<'a, MissileRewind>,
 }
 
 impl<'a> System<'a> for MissileFireHandler {
@@ -55,6 +57,9 @@ impl<'a> System<'a> for MissileFireHandler {
 			conns,
 			mut spawntime,
 			mut lastshot,
+			pingdata,
+			limits,
+			mut rewind,
 			..
 		} = data;
 
@@ -92,18 +97,27 @@ impl<'a> System<'a> for MissileFireHandler {
 					let m_vel = m_dir * (vel_par * missile.speed_factor + missile.base_speed);
 					let m_accel = m_dir * missile.accel;
 					let m_ent = ents.create();
+
+					// Clients can't be told about a missile
+					// without an id, so don't fire at all.
+					if conns.entity_ids().allocate(m_ent).is_none() {
+						ents.delete(m_ent).unwrap();
+						return None;
+					}
+
 					let m_pos = *pos + m_dir * info.missile_offset;
 
 					*energy -= info.fire_energy;
 					*lastshot = LastShotTime(thisframe.0);
 
+					let ids = conns.entity_ids();
 					let packet = PlayerFire {
 						clock: clock,
-						id: ent,
+						id: ids.wire(ent),
 						energy: *energy,
 						energy_regen: info.energy_regen,
 						projectiles: vec![PlayerFireProjectile {
-							id: m_ent,
+							id: ids.wire(m_ent),
 							accel: m_accel,
 							pos: m_pos,
 							speed: m_vel,
@@ -112,9 +126,7 @@ impl<'a> System<'a> for MissileFireHandler {
 						}],
 					};
 
-					conns.send_to_all(OwnedMessage::Binary(
-						to_bytes(&ServerPacket::PlayerFire(packet)).unwrap(),
-					));
+					conns.broadcast(&ServerPacket::PlayerFire(packet));
 
 					return Some((m_ent, info.missile_type, m_pos, m_vel, *team, ent));
 				},
@@ -135,6 +147,14 @@ impl<'a> System<'a> for MissileFireHandler {
 			teams.insert(v.0, v.4).unwrap();
 			owner.insert(v.0, PlayerRef(v.5)).unwrap();
 			spawntime.insert(v.0, MobSpawnTime(thisframe.0)).unwrap();
+
+			let rtt = pingdata
+				.get(v.5)
+				.and_then(|pingdata| pingdata.smoothed_rtt)
+				.unwrap_or_default();
+			rewind
+				.insert(v.0, MissileRewind(rtt.min(limits.max_rewind)))
+				.unwrap();
 		}
 	}
 }
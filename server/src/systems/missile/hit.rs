@@ -7,8 +7,7 @@ use component::event::PlayerKilled;
 use component::reference::PlayerRef;
 
 use protocol::server::{PlayerHit, PlayerHitPlayer};
-use protocol::{to_bytes, ServerPacket};
-use websocket::OwnedMessage;
+use protocol::ServerPacket;
 
 pub struct MissileHitSystem {
 	reader: Option<OnPlayerMissileCollisionReader>,
@@ -91,6 +90,7 @@ impl<'a> System<'a> for MissileHitSystem {
 				/ upgconf.defense.factor[upgrades.defense as usize];
 
 			data.hitmarker.insert(missile.ent, HitMarker {}).unwrap();
+			data.conns.entity_ids().release(missile.ent);
 			data.entities.delete(missile.ent).unwrap();
 
 			info!(
@@ -109,21 +109,20 @@ impl<'a> System<'a> for MissileHitSystem {
 				});
 			} 
 
+			let ids = data.conns.entity_ids();
 			let packet = PlayerHit {
-				id: missile.ent,
-				owner: owner.0,
+				id: ids.wire(missile.ent),
+				owner: ids.wire(owner.0),
 				pos: *pos,
 				ty: *mob,
 				players: vec![PlayerHitPlayer {
-					id: player.ent,
+					id: ids.wire(player.ent),
 					health: *health,
 					health_regen: planeconf.health_regen,
 				}],
 			};
 
-			data.conns.send_to_all(OwnedMessage::Binary(
-				to_bytes(&ServerPacket::PlayerHit(packet)).unwrap(),
-			));
+			data.conns.broadcast(&ServerPacket::PlayerHit(packet));
 		}
 	}
 }
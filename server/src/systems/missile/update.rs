@@ -3,12 +3,13 @@ use types::*;
 
 use component::flag::IsMissile;
 use component::time::{LastFrame, ThisFrame};
+use consts::MAP_SIZE;
 
 pub struct MissileUpdate;
 
 lazy_static! {
-	static ref BOUNDARY_X: Distance = Distance::new(16384.0);
-	static ref BOUNDARY_Y: Distance = Distance::new(8192.0);
+	static ref BOUNDARY_X: Distance = MAP_SIZE.x;
+	static ref BOUNDARY_Y: Distance = MAP_SIZE.y;
 	static ref SIZE_X: Distance = *BOUNDARY_X * 2.0;
 	static ref SIZE_Y: Distance = *BOUNDARY_Y * 2.0;
 }
@@ -19,8 +20,8 @@ pub struct MissileUpdateSystemData<'a> {
 	pub vel: WriteStorage<'a, Velocity>,
 	pub mob: ReadStorage<'a, Mob>,
 	pub flag: ReadStorage<'a, IsMissile>,
-	pub thisframe: Read<'a, ThisFrame>,
-	pub lastframe: Read<'a, LastFrame>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
+	pub lastframe: ReadExpect<'a, LastFrame>,
 }
 
 impl<'a> System<'a> for MissileUpdate {
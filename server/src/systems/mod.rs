@@ -4,7 +4,9 @@ mod packet_handler;
 mod poll_complete;
 mod position_update;
 mod register;
+mod release_ids;
 mod run_futures;
+mod status;
 mod timer_handler;
 
 pub mod collision;
@@ -18,6 +20,8 @@ pub use self::health_regen::HealthRegenSystem;
 pub use self::packet_handler::PacketHandler;
 pub use self::poll_complete::PollComplete;
 pub use self::position_update::PositionUpdate;
+pub use self::release_ids::ReleaseEntityIds;
+pub use self::status::UpdateStatus;
 pub use self::timer_handler::TimerHandler;
 
 pub use self::register::register;
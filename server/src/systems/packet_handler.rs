@@ -1,5 +1,6 @@
 use airmash_protocol::client::*;
 use airmash_protocol::from_bytes;
+use fnv::FnvHashMap;
 use shrev::EventChannel;
 use specs::*;
 use websocket::OwnedMessage;
@@ -11,6 +12,7 @@ use std::sync::mpsc::{channel, Receiver};
 use component::channel::*;
 use component::event::*;
 use dispatch::*;
+use metrics::MetricsHandler;
 use types::event::*;
 use types::*;
 
@@ -37,6 +39,8 @@ pub struct PacketHandlerData<'a> {
 	pub localping: Write<'a, OnLocalPing>,
 	pub scoredetailed: Write<'a, OnScoreDetailed>,
 	pub ack: Write<'a, OnAck>,
+
+	pub metrics: ReadExpect<'a, MetricsHandler>,
 }
 
 impl PacketHandler {
@@ -83,6 +87,8 @@ impl<'a> System<'a> for PacketHandler {
 	}
 
 	fn run(&mut self, mut sysdata: PacketHandlerData<'a>) {
+		let mut types = FnvHashMap::default();
+
 		while let Ok(evt) = self.channel.try_recv() {
 			match evt {
 				ConnectionEvent::ConnectionOpen(conn) => {
@@ -93,6 +99,10 @@ impl<'a> System<'a> for PacketHandler {
 				}
 				ConnectionEvent::Message(msg) => {
 					if let OwnedMessage::Binary(data) = msg.msg {
+						if let Some(&code) = data.first() {
+							*types.entry(code).or_insert(0) += 1;
+						}
+
 						match from_bytes::<ClientPacket>(&data) {
 							Ok(packet) => Self::dispatch(&mut sysdata, msg.conn, packet),
 							Err(_) => sysdata.onbinary.single_write(Message {
@@ -104,6 +114,12 @@ impl<'a> System<'a> for PacketHandler {
 				}
 			}
 		}
+
+		for (code, n) in types {
+			sysdata
+				.metrics
+				.count_labelled("packets-received", Some(("type", &code.to_string())), n);
+		}
 	}
 }
 
@@ -1,37 +1,53 @@
+use fnv::FnvHashMap;
 use metrics::*;
 use specs::prelude::*;
 use std::time::Instant;
-use tokio::prelude::Sink;
+use types::event::ConnectionClose;
 use types::*;
 
-use websocket::OwnedMessage;
+use component::channel::OnClose;
 
 use std::mem;
 use std::sync::mpsc::{channel, Receiver};
 
 pub struct PollComplete {
-	channel: Receiver<(ConnectionId, OwnedMessage)>,
+	channel: Receiver<(ConnectionId, OutboundMessage)>,
 }
 
 impl PollComplete {
-	pub fn new(channel: Receiver<(ConnectionId, OwnedMessage)>) -> Self {
+	pub fn new(channel: Receiver<(ConnectionId, OutboundMessage)>) -> Self {
 		Self { channel }
 	}
 }
 
+#[derive(SystemData)]
+pub struct PollCompleteData<'a> {
+	pub conns: Write<'a, Connections>,
+	pub limits: Read<'a, OutboundLimits>,
+	pub onclose: Write<'a, OnClose>,
+	pub metrics: ReadExpect<'a, MetricsHandler>,
+}
+
 impl<'a> System<'a> for PollComplete {
-	type SystemData = (Write<'a, Connections>, ReadExpect<'a, MetricsHandler>);
+	type SystemData = PollCompleteData<'a>;
 
-	fn run(&mut self, (mut conns, metrics): Self::SystemData) {
+	fn run(&mut self, mut data: Self::SystemData) {
 		let start = Instant::now();
-		let mut cnt = 0;
+		let mut bytes = 0;
+		let mut types = FnvHashMap::default();
+
 		while let Ok((id, msg)) = self.channel.try_recv() {
-			cnt += 1;
+			// The first byte of a packet is its type
+			if let Some(data) = msg.data() {
+				bytes += data.len() as u64;
 
-			match conns.0.get_mut(&id) {
-				Some(ref mut conn) => {
-					Connections::send_sink(&mut conn.sink, msg);
+				if let Some(&code) = data.first() {
+					*types.entry(code).or_insert(0) += 1;
 				}
+			}
+
+			match data.conns.0.get_mut(&id) {
+				Some(ref mut conn) => conn.queue.push_limited(msg, &data.limits),
 				// The connection probably closed,
 				// do nothing
 				None => trace!(
@@ -42,20 +58,58 @@ impl<'a> System<'a> for PollComplete {
 			}
 		}
 
-		metrics.count("packets-sent", cnt).unwrap();
+		data.metrics.count("bytes-sent", bytes);
+		for (code, n) in types {
+			data.metrics.count_labelled("packets-sent", Some(("type", &code.to_string())), n);
+		}
+
+		let mut dropped = 0;
+		let mut slow = vec![];
 
-		for conn in conns.iter_mut() {
-			conn.sink
-				.poll_complete()
+		for conn in data.conns.iter_mut() {
+			conn.flush()
 				.map_err(|e| {
 					info!("poll_complete failed with error {:?}", e);
 				})
 				.err();
+
+			if conn.queue.len() > data.limits.high_water {
+				dropped += conn.queue.coalesce();
+			}
+
+			if conn.queue.update_limit(&data.limits) {
+				if conn.queue.overflowed() {
+					warn!(
+						target: "server",
+						"Connection {:?} hit the limit of {} queued messages, disconnecting",
+						conn.id, data.limits.max_queued
+					);
+				} else {
+					warn!(
+						target: "server",
+						"Connection {:?} has had over {} messages queued for {} frames, disconnecting",
+						conn.id, data.limits.high_water, data.limits.max_frames_over
+					);
+				}
+
+				conn.close();
+				slow.push(conn.id);
+			}
 		}
 
-		metrics
-			.time_duration("poll-complete", Instant::now() - start)
-			.err();
+		data.metrics.count("packets-coalesced", dropped as u64);
+		data.metrics.count("slow-consumer-disconnects", slow.len() as u64);
+
+		let players = data.conns.iter().filter(|c| c.player.is_some()).count();
+		data.metrics.gauge("connections", data.conns.0.len() as f64);
+		data.metrics.gauge("players", players as f64);
+
+		// Closing the connection will be handled
+		// by OnCloseHandler within the next frame.
+		data.onclose
+			.iter_write(slow.into_iter().map(|conn| ConnectionClose { conn }));
+
+		data.metrics.time_duration("poll-complete", Instant::now() - start);
 	}
 }
 
@@ -75,7 +129,7 @@ impl SystemInfo for PollComplete {
 
 	fn new_args(mut a: Box<Any>) -> Self {
 		let r = a
-			.downcast_mut::<Receiver<(ConnectionId, OwnedMessage)>>()
+			.downcast_mut::<Receiver<(ConnectionId, OutboundMessage)>>()
 			.unwrap();
 		// Replace the channel within the box with a
 		// dummy one, which will be dropped immediately
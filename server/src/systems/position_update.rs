@@ -3,14 +3,14 @@ use types::systemdata::IsAlive;
 use types::*;
 
 use component::time::*;
+use consts::PLANE_BOUNDS;
 
 use std::f32::consts;
 use std::marker::PhantomData;
 use std::time::Duration;
 
 use airmash_protocol::server::{PlayerUpdate, ServerPacket};
-use airmash_protocol::{to_bytes, ServerKeyState, Upgrades as ServerUpgrades};
-use websocket::OwnedMessage;
+use airmash_protocol::{ServerKeyState, Upgrades as ServerUpgrades};
 
 const PI: Rotation = Rotation {
 	value_unsafe: consts::PI,
@@ -41,9 +41,9 @@ pub struct PositionUpdateData<'a> {
 	upgrades: ReadStorage<'a, Upgrades>,
 	powerups: ReadStorage<'a, Powerups>,
 	planes: ReadStorage<'a, Plane>,
-	lastframe: Read<'a, LastFrame>,
-	thisframe: Read<'a, ThisFrame>,
-	starttime: Read<'a, StartTime>,
+	lastframe: ReadExpect<'a, LastFrame>,
+	thisframe: ReadExpect<'a, ThisFrame>,
+	starttime: ReadExpect<'a, StartTime>,
 	entities: Entities<'a>,
 	conns: Read<'a, Connections>,
 	is_alive: IsAlive<'a>,
@@ -157,7 +157,7 @@ impl PositionUpdate {
 				*pos += oldspeed * delta + (*vel - oldspeed) * delta * 0.5;
 				*rot = (*rot % PIx2 + PIx2) % PIx2;
 
-				let bound = Position::new(Distance::new(16352.0), Distance::new(8160.0));
+				let bound = *PLANE_BOUNDS;
 
 				if pos.x.abs() > bound.x {
 					pos.x = pos.x.signum() * bound.x
@@ -203,7 +203,7 @@ impl PositionUpdate {
 
 					let packet = PlayerUpdate {
 						clock: (thisframe - starttime).to_clock(),
-						id: ent,
+						id: data.conns.entity_ids().wire(ent),
 						keystate: state,
 						pos: *pos,
 						rot: *rot,
@@ -213,9 +213,7 @@ impl PositionUpdate {
 
 					trace!(target: "server", "Update: {:?}", packet);
 
-					data.conns.send_to_all(OwnedMessage::Binary(
-						to_bytes(&ServerPacket::PlayerUpdate(packet)).unwrap(),
-					))
+					data.conns.broadcast(&ServerPacket::PlayerUpdate(packet))
 				},
 			)
 	}
@@ -256,7 +254,7 @@ impl PositionUpdate {
 
 					let packet = PlayerUpdate {
 						clock: (data.thisframe.0 - data.starttime.0).to_clock(),
-						id: ent,
+						id: data.conns.entity_ids().wire(ent),
 						keystate: state,
 						pos: *pos,
 						rot: *rot,
@@ -266,9 +264,7 @@ impl PositionUpdate {
 
 					trace!(target: "server", "Update: {:?}", packet);
 
-					data.conns.send_to_all(OwnedMessage::Binary(
-						to_bytes(&ServerPacket::PlayerUpdate(packet)).unwrap(),
-					))
+					data.conns.broadcast(&ServerPacket::PlayerUpdate(packet))
 				},
 			)
 	}
@@ -7,6 +7,7 @@ use dispatch::Builder;
 pub fn register<'a, 'b>(disp: Builder<'a, 'b>) -> Builder<'a, 'b> {
 	let disp = disp
 		.with::<run_futures::RunTimedFutures>()
+		.with::<ReleaseEntityIds>()
 
 		// Add handlers here
 		.with::<handlers::packet::OnOpenHandler>()
@@ -21,9 +22,11 @@ pub fn register<'a, 'b>(disp: Builder<'a, 'b>) -> Builder<'a, 'b> {
 		.with::<handlers::packet::CommandHandler>()
 		.with::<handlers::packet::SignalHandler>()
 		.with::<handlers::packet::WhisperHandler>()
+		.with::<handlers::packet::ExtensionsHandler>()
 
 		// Systems with dependencies on handlers
-		.with::<PositionUpdate>();
+		.with::<PositionUpdate>()
+		.with::<UpdateStatus>();
 
 	let disp = missile::register(disp)
 		// EnergyRegen depends on MissileHit
@@ -0,0 +1,36 @@
+use specs::prelude::*;
+
+use types::Connections;
+
+/// Frees the wire ids of entities that were
+/// deleted last frame so that they can be
+/// given out again.
+#[derive(Default)]
+pub struct ReleaseEntityIds;
+
+#[derive(SystemData)]
+pub struct ReleaseEntityIdsData<'a> {
+	pub conns: Read<'a, Connections>,
+}
+
+impl<'a> System<'a> for ReleaseEntityIds {
+	type SystemData = ReleaseEntityIdsData<'a>;
+
+	fn run(&mut self, data: Self::SystemData) {
+		data.conns.entity_ids().release_deleted();
+	}
+}
+
+use dispatch::SystemInfo;
+
+impl SystemInfo for ReleaseEntityIds {
+	type Dependencies = ();
+
+	fn name() -> &'static str {
+		concat!(module_path!(), "::", line!())
+	}
+
+	fn new() -> Self {
+		Self::default()
+	}
+}
@@ -8,7 +8,7 @@ use types::FutureDispatcher;
 pub struct RunTimedFutures;
 
 impl<'a> System<'a> for RunTimedFutures {
-	type SystemData = (WriteExpect<'a, FutureDispatcher>, Read<'a, ThisFrame>);
+	type SystemData = (WriteExpect<'a, FutureDispatcher>, ReadExpect<'a, ThisFrame>);
 
 	fn run(&mut self, (mut data, now): Self::SystemData) {
 		data.exec_tasks(now.0);
@@ -5,9 +5,11 @@ use types::*;
 use dispatch::SystemInfo;
 
 use component::channel::*;
-use component::event::PlayerSpectate;
-use component::flag::{IsPlayer, IsSpectating};
+use component::event::{PlayerLeaveTeam, PlayerSpectate};
+use component::flag::{IsPlayer, IsPureSpectator, IsSpectating};
 use component::reference::PlayerRef;
+use protocol::server::{PlayerReteam, PlayerReteamPlayer};
+use protocol::ServerPacket;
 
 use systems::PacketHandler;
 
@@ -20,11 +22,15 @@ pub struct CommandHandlerData<'a> {
 	pub channel: Read<'a, OnCommand>,
 	pub conns: Read<'a, Connections>,
 	pub specchannel: Write<'a, OnPlayerSpectate>,
+	pub leavechannel: Write<'a, OnPlayerLeaveTeam>,
 
 	pub is_spec: WriteStorage<'a, IsSpectating>,
 	pub is_dead: WriteStorage<'a, IsDead>,
+	pub pure_spec: WriteStorage<'a, IsPureSpectator>,
 	pub isplayer: ReadStorage<'a, IsPlayer>,
 	pub spectarget: ReadStorage<'a, PlayerRef>,
+	pub team: WriteStorage<'a, Team>,
+	pub pos: WriteStorage<'a, Position>,
 	pub entities: Entities<'a>,
 }
 
@@ -48,23 +54,36 @@ impl<'a> System<'a> for CommandHandler {
 			channel,
 			conns,
 			mut specchannel,
+			mut leavechannel,
 
 			is_spec,
 			is_dead,
+			mut pure_spec,
 			isplayer,
 			entities,
 			spectarget,
+			mut team,
+			mut pos,
 		} = data;
 
 		for (id, packet) in channel.read(self.reader.as_mut().unwrap()) {
-			if packet.com != "spectate" {
-				continue;
-			}
+			// `spectator` leaves the player's team and
+			// then works the same as force spectate.
+			// The client never sends it by itself.
+			let pure = match &*packet.com {
+				"spectate" => false,
+				"spectator" => true,
+				_ => continue,
+			};
 
-			let arg: i32 = match packet.data.parse() {
-				Ok(v) => v,
-				// Not a valid integer, ignore
-				Err(_) => continue,
+			let arg: i32 = if pure {
+				-3
+			} else {
+				match packet.data.parse() {
+					Ok(v) => v,
+					// Not a valid integer, ignore
+					Err(_) => continue,
+				}
 			};
 
 			let player = match conns.associated_player(*id) {
@@ -79,6 +98,26 @@ impl<'a> System<'a> for CommandHandler {
 				continue;
 			}
 
+			// Pure spectators are taken off of their
+			// team until they respawn.
+			if pure && pure_spec.get(player).is_none() {
+				let old = *team.get(player).unwrap();
+				let id = conns.entity_ids().wire(player);
+				let new = Team(id.0);
+
+				pure_spec.insert(player, IsPureSpectator).unwrap();
+				team.insert(player, new).unwrap();
+
+				leavechannel.single_write(PlayerLeaveTeam {
+					player,
+					team: old,
+				});
+
+				conns.broadcast(&ServerPacket::PlayerReteam(PlayerReteam {
+					players: vec![PlayerReteamPlayer { id, team: new }],
+				}));
+			}
+
 			let mut spec_event = PlayerSpectate {
 				player: player,
 				target: None,
@@ -159,7 +198,14 @@ impl<'a> System<'a> for CommandHandler {
 					}
 					// Spectate by specific player id
 					_ => {
-						let ent = entities.entity(arg as u32);
+						if arg > 0xFFFF {
+							continue;
+						}
+
+						let ent = match conns.entity_ids().entity(arg as u16) {
+							Some(ent) => ent,
+							None => continue,
+						};
 
 						// Requested an entity that doesn't exist
 						if !entities.is_alive(ent) {
@@ -171,6 +217,17 @@ impl<'a> System<'a> for CommandHandler {
 							continue;
 						}
 
+						// Spectating yourself is free-cam, the
+						// camera starts off wherever the player
+						// was looking before.
+						if ent == player {
+							let current = spectarget.get(player).unwrap().0;
+
+							if let Some(p) = pos.get(current).cloned() {
+								pos.insert(player, p).unwrap();
+							}
+						}
+
 						spec_event.target = Some(ent);
 					}
 				}
@@ -0,0 +1,138 @@
+use specs::*;
+
+use types::*;
+
+use dispatch::SystemInfo;
+
+use component::reference::PlayerRef;
+use component::time::{LastFrame, StartTime, ThisFrame};
+use consts::PLANE_BOUNDS;
+
+use protocol::server::PlayerUpdate;
+use protocol::{ServerPacket, Upgrades as ServerUpgrades};
+
+use systems::handlers::packet::KeyHandler;
+use systems::spectate::CommandHandler;
+
+use std::marker::PhantomData;
+
+/// How fast the camera moves in free-cam.
+/// This is a bit under 3x the top speed
+/// of a predator.
+const CAMERA_SPEED: Speed = Speed {
+	value_unsafe: 15.0,
+	_marker: PhantomData,
+};
+
+/// Moves the camera of spectators that aren't
+/// following anyone (they are spectating
+/// themselves) based on the keys they are
+/// holding down.
+///
+/// The camera is the spectator's own position,
+/// updates for it are only sent to them.
+pub struct FreeCam;
+
+#[derive(SystemData)]
+pub struct FreeCamData<'a> {
+	pub conns: Read<'a, Connections>,
+	pub thisframe: ReadExpect<'a, ThisFrame>,
+	pub lastframe: ReadExpect<'a, LastFrame>,
+	pub starttime: ReadExpect<'a, StartTime>,
+
+	pub pos: WriteStorage<'a, Position>,
+	pub rot: ReadStorage<'a, Rotation>,
+	pub plane: ReadStorage<'a, Plane>,
+	pub keystate: ReadStorage<'a, KeyState>,
+	pub upgrades: ReadStorage<'a, Upgrades>,
+	pub is_spec: ReadStorage<'a, IsSpectating>,
+	pub spectarget: ReadStorage<'a, PlayerRef>,
+	pub entities: Entities<'a>,
+}
+
+impl<'a> System<'a> for FreeCam {
+	type SystemData = FreeCamData<'a>;
+
+	fn run(&mut self, mut data: Self::SystemData) {
+		let delta = Time::from(data.thisframe.0 - data.lastframe.0);
+		let clock = (data.thisframe.0 - data.starttime.0).to_clock();
+		let bound = *PLANE_BOUNDS;
+
+		for (pos, rot, plane, keystate, upgrades, target, ent, _) in (
+			&mut data.pos,
+			&data.rot,
+			&data.plane,
+			&data.keystate,
+			&data.upgrades,
+			&data.spectarget,
+			&*data.entities,
+			&data.is_spec,
+		).join()
+		{
+			if target.0 != ent {
+				continue;
+			}
+
+			let mut dx: f32 = 0.0;
+			let mut dy: f32 = 0.0;
+
+			if keystate.left {
+				dx -= 1.0;
+			}
+			if keystate.right {
+				dx += 1.0;
+			}
+			if keystate.up {
+				dy -= 1.0;
+			}
+			if keystate.down {
+				dy += 1.0;
+			}
+
+			if dx == 0.0 && dy == 0.0 {
+				continue;
+			}
+
+			// Don't go faster along the diagonals
+			let len = (dx * dx + dy * dy).sqrt();
+			let step = CAMERA_SPEED * delta / len;
+
+			*pos += Position::new(step * dx, step * dy);
+
+			if pos.x.abs() > bound.x {
+				pos.x = pos.x.signum() * bound.x
+			}
+			if pos.y.abs() > bound.y {
+				pos.y = pos.y.signum() * bound.y
+			}
+
+			let packet = PlayerUpdate {
+				clock,
+				id: data.conns.entity_ids().wire(ent),
+				keystate: keystate.to_server(&plane),
+				pos: *pos,
+				rot: *rot,
+				speed: Velocity::default(),
+				upgrades: ServerUpgrades {
+					speed: upgrades.speed,
+					..Default::default()
+				},
+			};
+
+			data.conns
+				.send_to_player(ent, ServerPacket::PlayerUpdate(packet));
+		}
+	}
+}
+
+impl SystemInfo for FreeCam {
+	type Dependencies = (KeyHandler, CommandHandler);
+
+	fn name() -> &'static str {
+		concat!(module_path!(), "::", line!())
+	}
+
+	fn new() -> Self {
+		FreeCam
+	}
+}
@@ -0,0 +1,73 @@
+use specs::*;
+
+use types::*;
+
+use dispatch::SystemInfo;
+
+use component::channel::*;
+use component::event::{PlayerJoin, PlayerSpectate};
+use component::flag::{IsPlayer, IsPureSpectator, IsSpectating};
+
+use systems::handlers::game::on_join::SendLogin;
+
+/// Puts players that logged in as pure
+/// spectators straight into spectate.
+pub struct SpectateOnJoin {
+	reader: Option<OnPlayerJoinReader>,
+}
+
+#[derive(SystemData)]
+pub struct SpectateOnJoinData<'a> {
+	pub channel: Read<'a, OnPlayerJoin>,
+	pub specchannel: Write<'a, OnPlayerSpectate>,
+
+	pub pure_spec: ReadStorage<'a, IsPureSpectator>,
+	pub is_spec: ReadStorage<'a, IsSpectating>,
+	pub isplayer: ReadStorage<'a, IsPlayer>,
+	pub entities: Entities<'a>,
+}
+
+impl<'a> System<'a> for SpectateOnJoin {
+	type SystemData = SpectateOnJoinData<'a>;
+
+	fn setup(&mut self, res: &mut Resources) {
+		Self::SystemData::setup(res);
+
+		self.reader = Some(res.fetch_mut::<OnPlayerJoin>().register_reader());
+	}
+
+	fn run(&mut self, mut data: Self::SystemData) {
+		for PlayerJoin(player) in data.channel.read(self.reader.as_mut().unwrap()) {
+			if data.pure_spec.get(*player).is_none() {
+				continue;
+			}
+
+			let target = (&data.isplayer, &*data.entities)
+				.join()
+				.filter(|(_, ent)| *ent != *player && data.is_spec.get(*ent).is_none())
+				.map(|(_, ent)| ent)
+				.next();
+
+			data.specchannel.single_write(PlayerSpectate {
+				player: *player,
+				target,
+				is_dead: false,
+				is_spec: false,
+			});
+		}
+	}
+}
+
+impl SystemInfo for SpectateOnJoin {
+	// The login packet has to go out
+	// before the player is despawned.
+	type Dependencies = SendLogin;
+
+	fn name() -> &'static str {
+		concat!(module_path!(), "::", line!())
+	}
+
+	fn new() -> Self {
+		Self { reader: None }
+	}
+}
@@ -1,8 +1,12 @@
 mod register;
 
 mod command;
+mod freecam;
+mod join;
 mod respawn;
 
 pub use self::register::register;
 
 pub use self::command::CommandHandler;
+pub use self::freecam::FreeCam;
+pub use self::join::SpectateOnJoin;
@@ -3,5 +3,8 @@ use dispatch::Builder;
 use super::*;
 
 pub fn register<'a, 'b>(builder: Builder<'a, 'b>) -> Builder<'a, 'b> {
-	builder.with::<CommandHandler>()
+	builder
+		.with::<CommandHandler>()
+		.with::<SpectateOnJoin>()
+		.with::<FreeCam>()
 }
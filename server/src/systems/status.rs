@@ -0,0 +1,85 @@
+use specs::prelude::*;
+
+use component::counter::{PlayersGame, TeamScores};
+use component::time::{StartTime, ThisFrame};
+use protocol::GameType;
+use types::gamemode::GameModeInternal;
+use types::{ServerStatus, StatusHandle};
+
+use std::time::{Duration, Instant};
+
+/// Keeps the status served over HTTP
+/// up to date.
+#[derive(Default)]
+pub struct UpdateStatus {
+	/// Room and game type, these never change
+	mode: Option<(String, GameType)>,
+	last: Option<Instant>,
+}
+
+#[derive(SystemData)]
+pub struct UpdateStatusData<'a> {
+	pub thisframe: ReadExpect<'a, ThisFrame>,
+	pub start: ReadExpect<'a, StartTime>,
+	pub players: Read<'a, PlayersGame>,
+	pub scores: Read<'a, TeamScores>,
+	pub status: ReadExpect<'a, StatusHandle>,
+}
+
+impl<'a> System<'a> for UpdateStatus {
+	type SystemData = UpdateStatusData<'a>;
+
+	fn setup(&mut self, res: &mut Resources) {
+		Self::SystemData::setup(res);
+
+		let mode = res.fetch::<GameModeInternal>();
+		let mode = mode.0.as_gamemode_ref();
+
+		self.mode = Some((mode.room(), mode.gametype()));
+	}
+
+	fn run(&mut self, data: Self::SystemData) {
+		let now = data.thisframe.0;
+
+		// Nobody polls the status anywhere
+		// near once a frame
+		if let Some(last) = self.last {
+			if now - last < Duration::from_secs(1) {
+				return;
+			}
+		}
+		self.last = Some(now);
+
+		let (room, gametype) = self.mode.clone().unwrap();
+
+		let mut scores = data
+			.scores
+			.0
+			.iter()
+			.map(|(team, score)| (*team, *score))
+			.collect::<Vec<_>>();
+		scores.sort();
+
+		data.status.set(ServerStatus {
+			room,
+			gametype,
+			players: data.players.0,
+			scores,
+			uptime: now - data.start.0,
+		});
+	}
+}
+
+use dispatch::SystemInfo;
+
+impl SystemInfo for UpdateStatus {
+	type Dependencies = ();
+
+	fn name() -> &'static str {
+		concat!(module_path!(), "::", line!())
+	}
+
+	fn new() -> Self {
+		Self::default()
+	}
+}
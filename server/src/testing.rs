@@ -0,0 +1,241 @@
+//! Support for running a server in-process
+//! without any network connections.
+//!
+//! A [`TestServer`](struct.TestServer.html)
+//! wraps a [`SteppedServer`](../struct.SteppedServer.html)
+//! so that frames are only run when asked to,
+//! and creates [`FakeClient`](struct.FakeClient.html)s
+//! that send packets directly into the server
+//! and record everything that the server sends
+//! back to them.
+//!
+//! # Example
+//! ```ignore
+//! let mut server = TestServer::new(|server| {
+//!     server.with_engine().with_gamemode(MyGameMode)
+//! });
+//!
+//! let client = server.connect();
+//! client.login("test");
+//! server.step_n(2);
+//!
+//! for packet in server.received(&client) {
+//!     // Check packets here...
+//! }
+//! ```
+
+use futures::{Async, AsyncSink, Poll, Sink, StartSend};
+use specs::Entity;
+use websocket::OwnedMessage;
+
+use builder::{AirmashServer, SteppedServer};
+use consts::SPECTATOR_SESSION;
+use protocol::client::Login;
+use protocol::{self, from_bytes, to_bytes, ClientPacket, ServerPacket};
+use types::event::*;
+use types::{ConnectionId, ConnectionSink, Connections, OutboundMessage, Transport};
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr};
+use std::ops::{Deref, DerefMut};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+
+type Received = Arc<Mutex<Vec<OutboundMessage>>>;
+
+/// A sink that keeps everything written to it.
+struct FakeSink(Received);
+
+impl Sink for FakeSink {
+	type SinkItem = OutboundMessage;
+	type SinkError = io::Error;
+
+	fn start_send(&mut self, msg: OutboundMessage) -> StartSend<OutboundMessage, io::Error> {
+		self.0.lock().unwrap().push(msg);
+		Ok(AsyncSink::Ready)
+	}
+
+	fn poll_complete(&mut self) -> Poll<(), io::Error> {
+		Ok(Async::Ready(()))
+	}
+}
+
+/// A transport that never accepts connections
+/// by itself. It only keeps hold of the event
+/// channel so that connections can be created
+/// by the `TestServer`.
+#[derive(Clone, Default)]
+struct FakeTransport(Arc<Mutex<Option<Sender<ConnectionEvent>>>>);
+
+impl Transport for FakeTransport {
+	fn start(self: Box<Self>, channel: Sender<ConnectionEvent>) {
+		*self.0.lock().unwrap() = Some(channel);
+	}
+}
+
+/// A server driven entirely by the caller.
+///
+/// Derefs to the inner
+/// [`SteppedServer`](../struct.SteppedServer.html)
+/// for running frames and accessing the world.
+pub struct TestServer {
+	server: SteppedServer,
+	events: Sender<ConnectionEvent>,
+}
+
+impl TestServer {
+	/// Create a new server. `setup` should register
+	/// all systems and resources that the server
+	/// needs, the same as it would be done before
+	/// calling `AirmashServer::run`.
+	pub fn new<F>(setup: F) -> Self
+	where
+		F: FnOnce(AirmashServer<'static, 'static>) -> AirmashServer<'static, 'static>,
+	{
+		let transport = FakeTransport::default();
+		let server = setup(AirmashServer::from_transport(transport.clone())).into_stepped();
+		let events = transport.0.lock().unwrap().take().unwrap();
+
+		Self { server, events }
+	}
+
+	/// Open a new connection to the server. The
+	/// connection will be registered during the
+	/// next frame.
+	pub fn connect(&self) -> FakeClient {
+		self.connect_from(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), None)
+	}
+
+	/// Open a new connection as if it came from
+	/// `addr` with the given `Origin` header.
+	pub fn connect_from(&self, addr: IpAddr, origin: Option<&str>) -> FakeClient {
+		let conn = ConnectionId::new();
+		let received = Received::default();
+		let sink: ConnectionSink = Box::new(FakeSink(received.clone()));
+
+		self.events
+			.send(ConnectionEvent::ConnectionOpen(ConnectionOpen {
+				conn,
+				sink: Mutex::new(Some(sink)),
+				addr,
+				origin: origin.map(str::to_owned),
+			}))
+			.unwrap();
+
+		FakeClient {
+			conn,
+			events: self.events.clone(),
+			received,
+		}
+	}
+
+	/// The player that is logged in through `client`,
+	/// if there is one.
+	pub fn player(&self, client: &FakeClient) -> Option<Entity> {
+		self.world
+			.read_resource::<Connections>()
+			.associated_player(client.conn)
+	}
+
+	/// The id that clients know `ent` by, if it
+	/// has been sent to anyone yet.
+	pub fn entity_id(&self, ent: Entity) -> Option<protocol::Entity> {
+		self.world
+			.read_resource::<Connections>()
+			.entity_ids()
+			.get(ent)
+			.map(protocol::Entity)
+	}
+
+	/// Decode and remove all packets that have
+	/// been sent to `client` so far.
+	///
+	/// Panics if any packet fails to decode.
+	pub fn received(&self, client: &FakeClient) -> Vec<ServerPacket> {
+		client
+			.take()
+			.into_iter()
+			.filter_map(|msg| msg.data().map(|data| data.to_vec()))
+			.map(|data| {
+				from_bytes(&data)
+					.unwrap_or_else(|e| panic!("Failed to decode {:?}: {:?}", data, e))
+			})
+			.collect()
+	}
+}
+
+impl Deref for TestServer {
+	type Target = SteppedServer;
+
+	fn deref(&self) -> &SteppedServer {
+		&self.server
+	}
+}
+
+impl DerefMut for TestServer {
+	fn deref_mut(&mut self) -> &mut SteppedServer {
+		&mut self.server
+	}
+}
+
+/// A client connection that isn't backed
+/// by a socket.
+pub struct FakeClient {
+	conn: ConnectionId,
+	events: Sender<ConnectionEvent>,
+	received: Received,
+}
+
+impl FakeClient {
+	pub fn id(&self) -> ConnectionId {
+		self.conn
+	}
+
+	/// Send a packet to the server, it will
+	/// be handled during the next frame.
+	pub fn send(&self, packet: &ClientPacket) {
+		self.events
+			.send(ConnectionEvent::Message(Message {
+				conn: self.conn,
+				msg: OwnedMessage::Binary(to_bytes(packet).unwrap()),
+			}))
+			.unwrap();
+	}
+
+	/// Log in with the default settings
+	/// that a regular client would use.
+	pub fn login(&self, name: &str) {
+		self.login_with_session(name, "none");
+	}
+
+	/// Log in as a pure spectator.
+	pub fn login_spectator(&self, name: &str) {
+		self.login_with_session(name, SPECTATOR_SESSION);
+	}
+
+	fn login_with_session(&self, name: &str, session: &str) {
+		self.send(&ClientPacket::Login(Login {
+			protocol: 5,
+			name: name.to_owned(),
+			session: session.to_owned(),
+			horizon_x: 3000,
+			horizon_y: 3000,
+			flag: "UN".to_owned(),
+		}));
+	}
+
+	/// Close the connection as if the
+	/// client had disconnected.
+	pub fn close(&self) {
+		self.events
+			.send(ConnectionEvent::ConnectionClose(ConnectionClose {
+				conn: self.conn,
+			}))
+			.unwrap();
+	}
+
+	fn take(&self) -> Vec<OutboundMessage> {
+		let mut received = self.received.lock().unwrap();
+		received.drain(..).collect()
+	}
+}
@@ -1,44 +1,64 @@
 use std::sync::mpsc::Sender;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use component::event::TimerEvent;
 use consts::timer::*;
-use timeloop::timeloop;
+use utils::timer::TimerEventType;
 
-use tokio;
+struct Periodic {
+	ty: TimerEventType,
+	period: Duration,
+	next: Instant,
+}
 
-pub fn start_timer_events(channel: Sender<TimerEvent>) {
-	// 5s timer for ScoreBoard
-	tokio::spawn({
-		let channel = channel.clone();
-		timeloop(
-			move |instant| {
-				channel
-					.send(TimerEvent {
-						ty: *SCORE_BOARD,
-						instant: instant,
-						..Default::default()
-					})
-					.unwrap();
-			},
-			Duration::from_secs(5),
-		)
-	});
-
-	// 5s timer for ping packets
-	tokio::spawn({
-		let channel = channel.clone();
-		timeloop(
-			move |instant| {
-				channel
+/// Timer events that fire at regular intervals.
+///
+/// These run off the frame clock instead of the
+/// wall clock so that they stay in step with the
+/// rest of the game when frames are simulated.
+pub struct PeriodicTimers {
+	channel: Sender<TimerEvent>,
+	timers: Vec<Periodic>,
+}
+
+impl PeriodicTimers {
+	pub fn new(channel: Sender<TimerEvent>, start: Instant) -> Self {
+		let timer = |ty, period| Periodic {
+			ty,
+			period,
+			next: start,
+		};
+
+		Self {
+			channel,
+			timers: vec![
+				// 5s timer for ScoreBoard
+				timer(*SCORE_BOARD, Duration::from_secs(5)),
+				// 5s timer for ping packets
+				timer(*PING_DISPATCH, Duration::from_secs(5)),
+			],
+		}
+	}
+
+	/// Send all timer events that are due by `now`.
+	pub fn update(&mut self, now: Instant) {
+		for timer in self.timers.iter_mut() {
+			while timer.next <= now {
+				self.channel
 					.send(TimerEvent {
-						ty: *PING_DISPATCH,
-						instant: instant,
-						..Default::default()
+						ty: timer.ty,
+						instant: timer.next,
+						data: None,
 					})
 					.unwrap();
-			},
-			Duration::from_secs(5),
-		)
-	});
+
+				timer.next += timer.period;
+			}
+		}
+	}
+
+	/// Send a one-off timer event.
+	pub fn send(&self, evt: TimerEvent) {
+		self.channel.send(evt).unwrap();
+	}
 }
@@ -1,25 +1,29 @@
-use types::ConnectionId;
+use types::{ConnectionId, EntityIds, OutboundMessage, OutboundQueue};
 
 use fnv::FnvHashMap;
-use futures::stream::SplitSink;
-use futures::{AsyncSink, Sink};
+use futures::{Async, AsyncSink, Sink};
 use specs::Entity;
-use websocket::async::{MessageCodec, TcpStream};
-// Can't change this yet since websocket has not updated
-#[allow(deprecated)]
-use websocket::client::async::Framed;
 use websocket::OwnedMessage;
 
+use protocol::{to_bytes, ServerPacket};
+use replay::Target;
+
+use std::io;
 use std::net::IpAddr;
 use std::sync::mpsc::Sender;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
-// Websocket hasn't updated, can't change this yet
-#[allow(deprecated)]
-pub type ConnectionSink = SplitSink<Framed<TcpStream, MessageCodec<OwnedMessage>>>;
+/// The outbound half of a client connection.
+///
+/// Transports wrap whatever they write to
+/// in one of these, so errors are reported
+/// as plain IO errors.
+pub type ConnectionSink =
+	Box<Sink<SinkItem = OutboundMessage, SinkError = io::Error> + Send + Sync>;
 
 pub struct ConnectionData {
 	pub sink: ConnectionSink,
+	pub queue: OutboundQueue,
 	pub id: ConnectionId,
 	pub ty: ConnectionType,
 	pub player: Option<Entity>,
@@ -27,6 +31,50 @@ pub struct ConnectionData {
 	pub origin: Option<String>,
 }
 
+impl ConnectionData {
+	/// Write as many queued messages into the
+	/// sink as it will currently accept.
+	pub fn flush(&mut self) -> io::Result<()> {
+		loop {
+			while let Some(msg) = self.queue.pop() {
+				match self.sink.start_send(msg)? {
+					AsyncSink::Ready => (),
+					AsyncSink::NotReady(msg) => {
+						self.queue.unpop(msg);
+						break;
+					}
+				}
+			}
+
+			// If everything was flushed to the socket
+			// then the sink may accept more messages
+			match self.sink.poll_complete()? {
+				Async::Ready(()) if !self.queue.is_empty() => continue,
+				_ => return Ok(()),
+			}
+		}
+	}
+
+	/// Drop all pending messages and ask the
+	/// client to close the connection.
+	pub fn close(&mut self) {
+		self.queue.clear();
+
+		// Best effort, the connection is being
+		// dropped whether or not this works.
+		self.sink.start_send(OwnedMessage::Close(None).into()).err();
+		self.sink.poll_complete().err();
+	}
+
+	/// Like `close`, but send `msg` to the
+	/// client first.
+	pub fn close_with(&mut self, msg: OutboundMessage) {
+		self.queue.clear();
+		self.sink.start_send(msg).err();
+		self.close();
+	}
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum ConnectionType {
 	Primary,
@@ -34,9 +82,53 @@ pub enum ConnectionType {
 	Inactive,
 }
 
+/// Something that can be sent to a client.
+///
+/// Entities within packets must already be the
+/// ids from [`Connections::entity_ids`].
+///
+/// [`Connections::entity_ids`]: struct.Connections.html#method.entity_ids
+pub trait IntoMessage {
+	/// Returns `None` if the message couldn't
+	/// be serialized.
+	fn into_message(self, conns: &Connections) -> Option<OutboundMessage>;
+}
+
+impl IntoMessage for OutboundMessage {
+	fn into_message(self, _: &Connections) -> Option<OutboundMessage> {
+		Some(self)
+	}
+}
+
+impl IntoMessage for OwnedMessage {
+	fn into_message(self, _: &Connections) -> Option<OutboundMessage> {
+		Some(self.into())
+	}
+}
+
+impl IntoMessage for Arc<[u8]> {
+	fn into_message(self, _: &Connections) -> Option<OutboundMessage> {
+		Some(self.into())
+	}
+}
+
+impl IntoMessage for ServerPacket {
+	fn into_message(self, conns: &Connections) -> Option<OutboundMessage> {
+		(&self).into_message(conns)
+	}
+}
+
+impl<'a> IntoMessage for &'a ServerPacket {
+	fn into_message(self, conns: &Connections) -> Option<OutboundMessage> {
+		conns.serialize(self).map(Into::into)
+	}
+}
+
 pub struct Connections(
 	pub FnvHashMap<ConnectionId, ConnectionData>,
-	Mutex<Sender<(ConnectionId, OwnedMessage)>>,
+	Mutex<Sender<(ConnectionId, OutboundMessage)>>,
+	Option<Mutex<Sender<(Target, OutboundMessage)>>>,
+	EntityIds,
 );
 
 impl Default for Connections {
@@ -46,8 +138,50 @@ impl Default for Connections {
 }
 
 impl Connections {
-	pub fn new(channel: Sender<(ConnectionId, OwnedMessage)>) -> Self {
-		Connections(FnvHashMap::default(), Mutex::new(channel))
+	pub fn new(channel: Sender<(ConnectionId, OutboundMessage)>) -> Self {
+		Connections(
+			FnvHashMap::default(),
+			Mutex::new(channel),
+			None,
+			EntityIds::default(),
+		)
+	}
+
+	/// The ids that clients know each
+	/// entity by.
+	pub fn entity_ids(&self) -> &EntityIds {
+		&self.3
+	}
+
+	/// Serialize a packet, logging any
+	/// errors that occur.
+	pub fn serialize(&self, packet: &ServerPacket) -> Option<Arc<[u8]>> {
+		match to_bytes(packet) {
+			Ok(data) => Some(data.into()),
+			Err(e) => {
+				error!(
+					target: "server",
+					"Failed to serialize {:?} with error {:?}",
+					packet, e
+				);
+				None
+			}
+		}
+	}
+
+	/// Also send a copy of every outgoing
+	/// message to `channel`, along with who
+	/// it was sent to.
+	pub(crate) fn record_to(&mut self, channel: Sender<(Target, OutboundMessage)>) {
+		self.2 = Some(Mutex::new(channel));
+	}
+
+	fn record(&self, target: Target, msg: &OutboundMessage) {
+		if let Some(ref channel) = self.2 {
+			// The recorder going away shouldn't
+			// stop the game.
+			channel.lock().unwrap().send((target, msg.clone())).err();
+		}
 	}
 
 	pub fn add(
@@ -59,6 +193,7 @@ impl Connections {
 	) {
 		let data = ConnectionData {
 			sink: sink,
+			queue: OutboundQueue::new(),
 			ty: ConnectionType::Inactive,
 			player: None,
 			id: id,
@@ -108,22 +243,7 @@ impl Connections {
 		conn.ty = ty;
 	}
 
-	pub fn send_sink(conn: &mut ConnectionSink, msg: OwnedMessage) {
-		conn.start_send(msg)
-			.and_then(|x| {
-				match x {
-					AsyncSink::Ready => (),
-					AsyncSink::NotReady(item) => {
-						conn.poll_complete().unwrap();
-						conn.start_send(item).unwrap();
-					}
-				}
-				Ok(())
-			})
-			.unwrap();
-	}
-
-	pub fn send_to_player(&self, player: Entity, msg: OwnedMessage) {
+	pub fn send_to_player<M: IntoMessage>(&self, player: Entity, msg: M) {
 		let conn = self.0.iter().find(|(_, c)| {
 			c.player.is_some() && c.ty == ConnectionType::Primary && c.player.unwrap() == player
 		});
@@ -141,17 +261,37 @@ impl Connections {
 		self.send_to(*conn.unwrap().0, msg);
 	}
 
-	pub fn send_to(&self, id: ConnectionId, msg: OwnedMessage) {
+	pub fn send_to<M: IntoMessage>(&self, id: ConnectionId, msg: M) {
+		let msg = match msg.into_message(self) {
+			Some(msg) => msg,
+			None => return,
+		};
+
 		trace!(
 			target: "server",
 			"Sent message to {:?}: {:?}",
 			id, msg
 		);
 
+		// Messages to connections that haven't logged
+		// in yet aren't part of the match.
+		if let Some(player) = self.associated_player(id) {
+			if let Some(player) = self.3.get(player) {
+				self.record(Target::Player(player), &msg);
+			}
+		}
+
 		self.1.lock().unwrap().send((id, msg)).unwrap();
 	}
 
-	pub fn send_to_all(&self, msg: OwnedMessage) {
+	pub fn send_to_all<M: IntoMessage>(&self, msg: M) {
+		let msg = match msg.into_message(self) {
+			Some(msg) => msg,
+			None => return,
+		};
+
+		self.record(Target::All, &msg);
+
 		self.0
 			.iter()
 			.filter_map(|(id, ref conn)| {
@@ -167,7 +307,24 @@ impl Connections {
 			});
 	}
 
-	pub fn send_to_others(&self, player: Entity, msg: OwnedMessage) {
+	/// Serialize a packet once and send the
+	/// same buffer to all players.
+	pub fn broadcast(&self, packet: &ServerPacket) {
+		if let Some(data) = self.serialize(packet) {
+			self.send_to_all(data);
+		}
+	}
+
+	pub fn send_to_others<M: IntoMessage>(&self, player: Entity, msg: M) {
+		let msg = match msg.into_message(self) {
+			Some(msg) => msg,
+			None => return,
+		};
+
+		// 0 is never given out, so this is the same
+		// as sending to everyone if there's no id.
+		self.record(Target::Except(self.3.get(player).unwrap_or(0)), &msg);
+
 		self.0
 			.iter()
 			.filter_map(|(id, ref conn)| {
@@ -0,0 +1,176 @@
+use fnv::FnvHashMap;
+use protocol;
+use specs::Entity;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+
+/// Ids below this are never given out. Team
+/// ids are shared with player ids in FFA and
+/// CTF uses teams 1 and 2, while an id of 0
+/// means no entity at all.
+pub const FIRST_ENTITY_ID: u16 = 3;
+
+struct Slot {
+	/// The entity that was last given this id.
+	ent: Entity,
+	released: bool,
+}
+
+#[derive(Default)]
+struct IdMap {
+	/// Indexed by `id - FIRST_ENTITY_ID`.
+	slots: Vec<Slot>,
+	ids: FnvHashMap<Entity, u16>,
+	/// Ids of entities deleted this frame, in
+	/// the order that they were deleted.
+	deleted: Vec<u16>,
+	/// Released ids, oldest first.
+	free: VecDeque<u16>,
+}
+
+impl IdMap {
+	fn allocate(&mut self, ent: Entity) -> Option<u16> {
+		let id = if self.slots.len() < (0x10000 - FIRST_ENTITY_ID as usize) {
+			self.slots.push(Slot {
+				ent,
+				released: false,
+			});
+			(self.slots.len() - 1) as u16 + FIRST_ENTITY_ID
+		} else {
+			let id = self.free.pop_front()?;
+			let slot = &mut self.slots[(id - FIRST_ENTITY_ID) as usize];
+
+			// The old entity keeps its id until now so
+			// that packets about it can still be sent.
+			self.ids.remove(&slot.ent);
+			slot.ent = ent;
+			slot.released = false;
+			id
+		};
+
+		self.ids.insert(ent, id);
+		Some(id)
+	}
+}
+
+/// Maps entities to the 16-bit ids that
+/// clients know them by.
+///
+/// Ids are given out by [`allocate`] when an
+/// entity that clients need to know about is
+/// created. Once every id has been used the
+/// ones that have been released are reused,
+/// oldest first, so that an id takes as long
+/// as possible to come back around.
+///
+/// Entities are compared along with their
+/// generation, so an entity that has been
+/// deleted will never be mistaken for the
+/// entity that replaced it.
+///
+/// Systems that delete an entity must call
+/// [`release`] for it too, otherwise its id
+/// is never reused.
+///
+/// [`allocate`]: #method.allocate
+/// [`release`]: #method.release
+#[derive(Clone, Default)]
+pub struct EntityIds(Arc<RwLock<IdMap>>);
+
+impl EntityIds {
+	/// Give `ent` an id, or return the one it
+	/// already has. Returns `None` if all ids
+	/// are in use.
+	pub fn allocate(&self, ent: Entity) -> Option<u16> {
+		// Almost every lookup is for an entity that
+		// already has an id, so only those that need
+		// a new one have to wait for the write lock.
+		if let Some(id) = self.get(ent) {
+			return Some(id);
+		}
+
+		let mut map = self.0.write().unwrap();
+
+		match map.ids.get(&ent) {
+			Some(&id) => Some(id),
+			None => map.allocate(ent),
+		}
+	}
+
+	/// `ent` as it is sent within packets.
+	///
+	/// Entities that were never given an id, or
+	/// whose id has since gone to another entity,
+	/// are sent as 0, which is no entity.
+	pub fn wire(&self, ent: Entity) -> protocol::Entity {
+		protocol::Entity(self.get(ent).unwrap_or(0))
+	}
+
+	/// The id of `ent`, if it has one.
+	pub fn get(&self, ent: Entity) -> Option<u16> {
+		self.0.read().unwrap().ids.get(&ent).cloned()
+	}
+
+	/// The entity that was last given `id`. This
+	/// may be an entity that no longer exists.
+	pub fn entity(&self, id: u16) -> Option<Entity> {
+		let map = self.0.read().unwrap();
+
+		if id < FIRST_ENTITY_ID {
+			return None;
+		}
+
+		map.slots
+			.get((id - FIRST_ENTITY_ID) as usize)
+			.map(|slot| slot.ent)
+	}
+
+	/// Release the id of `ent`, which is being
+	/// deleted. The id isn't free to be given out
+	/// again until [`release_deleted`] runs at the
+	/// start of the next frame, so packets sent
+	/// about `ent` for the rest of this frame
+	/// still use it.
+	///
+	/// [`release_deleted`]: #method.release_deleted
+	pub fn release(&self, ent: Entity) {
+		let mut map = self.0.write().unwrap();
+
+		if let Some(&id) = map.ids.get(&ent) {
+			map.deleted.push(id);
+		}
+	}
+
+	/// Make the ids of all entities released
+	/// since the last call free for reuse, in
+	/// the order that they were released.
+	///
+	/// Released ids still refer to their old
+	/// entity until they are given out again.
+	pub fn release_deleted(&self) {
+		let mut map = self.0.write().unwrap();
+		let IdMap {
+			ref mut slots,
+			ref mut deleted,
+			ref mut free,
+			..
+		} = *map;
+
+		for id in deleted.drain(..) {
+			let slot = &mut slots[(id - FIRST_ENTITY_ID) as usize];
+
+			if !slot.released {
+				slot.released = true;
+				free.push_back(id);
+			}
+		}
+	}
+
+	/// Number of entities that currently
+	/// have an id.
+	pub fn len(&self) -> usize {
+		let map = self.0.read().unwrap();
+		map.ids.len() - map.free.len()
+	}
+}
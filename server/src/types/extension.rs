@@ -0,0 +1,228 @@
+//! Messages for features that the official
+//! client doesn't have, sent as `ServerCustom`
+//! packets with a type of `Extension`.
+//!
+//! Clients opt in by sending an `"extensions"`
+//! command after logging in that lists the
+//! message types they understand, e.g.
+//! `hud:1,marker:1`. The server replies with
+//! a `hello` message listing the message types
+//! in its [`ExtensionRegistry`]. Clients that
+//! never send the command get nothing, so the
+//! official client is unaffected.
+//!
+//! Game modes can add their own message types
+//! by implementing [`CustomMessage`] and
+//! registering them with
+//! `AirmashServer::with_extension`.
+
+use fnv::FnvHashMap;
+use specs::*;
+
+use types::{Position, Team};
+use utils::json::escape;
+
+use std::fmt::Write;
+
+/// The `com` field of the handshake command.
+pub const EXTENSIONS_COMMAND: &'static str = "extensions";
+
+/// A message that can be sent over the
+/// extension channel.
+pub trait CustomMessage {
+	/// Name of the message type, this is
+	/// what clients use to ask for it.
+	const NAME: &'static str;
+	/// Incremented whenever the format of the
+	/// message changes in a way that older
+	/// clients won't understand.
+	const VERSION: u32;
+
+	/// The message as a JSON value.
+	fn to_json(&self) -> String;
+}
+
+/// Wrap a message in the envelope that
+/// clients expect.
+pub fn envelope(name: &str, version: u32, data: &str) -> String {
+	format!(
+		"{{\"t\":\"{}\",\"v\":{},\"d\":{}}}",
+		escape(name),
+		version,
+		data
+	)
+}
+
+/// All the message types that the server
+/// may send, along with the newest version
+/// of each.
+#[derive(Clone, Debug)]
+pub struct ExtensionRegistry(pub FnvHashMap<&'static str, u32>);
+
+impl ExtensionRegistry {
+	/// A registry without any message types,
+	/// not even the built in ones.
+	pub fn empty() -> Self {
+		ExtensionRegistry(FnvHashMap::default())
+	}
+
+	pub fn register<M: CustomMessage>(&mut self) {
+		let version = self.0.entry(M::NAME).or_insert(M::VERSION);
+		*version = (*version).max(M::VERSION);
+	}
+
+	pub fn with<M: CustomMessage>(mut self) -> Self {
+		self.register::<M>();
+		self
+	}
+
+	pub fn version(&self, name: &str) -> Option<u32> {
+		self.0.get(name).cloned()
+	}
+
+	/// The `hello` message sent in reply
+	/// to the handshake.
+	pub fn hello(&self) -> String {
+		let mut names = self.0.iter().collect::<Vec<_>>();
+		names.sort();
+
+		let data = names
+			.into_iter()
+			.map(|(name, version)| format!("\"{}\":{}", escape(name), version))
+			.collect::<Vec<_>>();
+
+		envelope("hello", 1, &format!("{{{}}}", data.join(",")))
+	}
+}
+
+impl Default for ExtensionRegistry {
+	fn default() -> Self {
+		Self::empty()
+			.with::<HudText>()
+			.with::<ObjectiveMarker>()
+			.with::<MatchTimer>()
+	}
+}
+
+/// The message types that a client has
+/// asked for during the handshake. Players
+/// without this component haven't done the
+/// handshake.
+#[derive(Clone, Debug, Default, Component)]
+pub struct ClientExtensions(pub FnvHashMap<String, u32>);
+
+impl ClientExtensions {
+	/// Parse the data of the handshake command,
+	/// invalid entries are ignored.
+	pub fn parse(data: &str) -> Self {
+		let caps = data
+			.split(',')
+			.filter_map(|entry| {
+				let mut parts = entry.splitn(2, ':');
+				let name = parts.next()?.trim();
+				let version = parts.next()?.trim().parse().ok()?;
+
+				if name.is_empty() {
+					return None;
+				}
+
+				Some((name.to_owned(), version))
+			})
+			.collect();
+
+		ClientExtensions(caps)
+	}
+
+	pub fn supports<M: CustomMessage>(&self) -> bool {
+		self.0
+			.get(M::NAME)
+			.map(|&v| v >= M::VERSION)
+			.unwrap_or(false)
+	}
+}
+
+/// Text shown in a slot on the HUD. Sending
+/// an empty string clears the slot.
+#[derive(Clone, Debug, Default)]
+pub struct HudText {
+	/// Which slot to put the text in, text
+	/// sent to the same slot replaces what
+	/// was there before.
+	pub id: String,
+	pub text: String,
+}
+
+impl CustomMessage for HudText {
+	const NAME: &'static str = "hud";
+	const VERSION: u32 = 1;
+
+	fn to_json(&self) -> String {
+		format!(
+			"{{\"id\":\"{}\",\"text\":\"{}\"}}",
+			escape(&self.id),
+			escape(&self.text)
+		)
+	}
+}
+
+/// A marker on the map, e.g. for a point
+/// that needs to be captured.
+#[derive(Clone, Debug)]
+pub struct ObjectiveMarker {
+	/// Markers with the same id replace
+	/// each other.
+	pub id: String,
+	pub pos: Position,
+	pub label: String,
+	/// The team that owns the objective,
+	/// if any.
+	pub team: Option<Team>,
+	/// Set to false to remove the marker.
+	pub active: bool,
+}
+
+impl CustomMessage for ObjectiveMarker {
+	const NAME: &'static str = "marker";
+	const VERSION: u32 = 1;
+
+	fn to_json(&self) -> String {
+		let mut out = format!(
+			"{{\"id\":\"{}\",\"x\":{},\"y\":{},\"label\":\"{}\",\"active\":{}",
+			escape(&self.id),
+			self.pos.x.inner(),
+			self.pos.y.inner(),
+			escape(&self.label),
+			self.active
+		);
+
+		if let Some(team) = self.team {
+			write!(out, ",\"team\":{}", team.0).unwrap();
+		}
+
+		out.push('}');
+		out
+	}
+}
+
+/// A countdown shown on the HUD.
+#[derive(Clone, Debug, Default)]
+pub struct MatchTimer {
+	pub label: String,
+	/// Seconds until the timer runs out, the
+	/// client counts down on its own. Zero
+	/// hides the timer.
+	pub remaining: u32,
+}
+
+impl CustomMessage for MatchTimer {
+	const NAME: &'static str = "timer";
+	const VERSION: u32 = 1;
+
+	fn to_json(&self) -> String {
+		format!(
+			"{{\"label\":\"{}\",\"remaining\":{}}}",
+			escape(&self.label),
+			self.remaining
+		)
+	}
+}
@@ -34,6 +34,11 @@ impl Ord for Task {
 pub struct FutureDispatcher {
 	channel: Mutex<Sender<TimerEvent>>,
 	tasks: Mutex<BinaryHeap<Task>>,
+	/// Time of the current frame, delays
+	/// are measured from this instead of
+	/// the wall clock. Set when the server
+	/// is started.
+	now: Mutex<Option<Instant>>,
 }
 
 /// Allow running delayed tasks
@@ -42,15 +47,18 @@ impl FutureDispatcher {
 		Self {
 			channel: Mutex::new(channel),
 			tasks: Default::default(),
+			now: Mutex::new(None),
 		}
 	}
 
-	/// Runs the function after the
+	/// Runs the function once `dur` has
+	/// passed since the current frame.
 	pub fn run_delayed<F: 'static>(&self, dur: Duration, fun: F)
 	where
 		F: Send + Fn(Instant) -> Option<TimerEvent>,
 	{
-		let instant = Instant::now() + dur;
+		let now = self.now.lock().unwrap().expect("The server hasn't been started");
+		let instant = now + dur;
 
 		self.tasks.lock().unwrap().push(Task {
 			time: instant,
@@ -59,6 +67,8 @@ impl FutureDispatcher {
 	}
 
 	pub fn exec_tasks(&mut self, now: Instant) {
+		*self.now.get_mut().unwrap() = Some(now);
+
 		let tasks = self.tasks.get_mut().unwrap();
 
 		while !tasks.is_empty() && tasks.peek().unwrap().time < now {
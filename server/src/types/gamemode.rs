@@ -15,7 +15,10 @@ use std::ops::{Deref, DerefMut};
 use protocol::GameType;
 
 pub trait GameMode: Any + Sync + Send {
-	fn assign_team(&mut self, player: Entity) -> Team;
+	/// `id` is the id that clients know `player`
+	/// by. Modes that put every player on their
+	/// own team should use it as the team.
+	fn assign_team(&mut self, player: Entity, id: u16) -> Team;
 	fn spawn_pos(&mut self, player: Entity, team: Team) -> Position;
 
 	fn gametype(&self) -> GameType;
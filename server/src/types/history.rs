@@ -0,0 +1,89 @@
+use specs::*;
+
+use types::{Position, Rotation};
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Limits on how far back in time missile
+/// hits may be checked to make up for the
+/// latency of the player that fired them.
+#[derive(Copy, Clone, Debug)]
+pub struct LagCompensation {
+	/// The furthest that players will be
+	/// rewound, no matter how high the
+	/// shooter's ping is. Setting this to
+	/// zero disables lag compensation.
+	pub max_rewind: Duration,
+}
+
+impl Default for LagCompensation {
+	fn default() -> Self {
+		Self {
+			max_rewind: Duration::from_millis(200),
+		}
+	}
+}
+
+/// How far behind the server the shooter of a
+/// missile was when they fired it, capped by
+/// [`LagCompensation`](struct.LagCompensation.html).
+#[derive(Copy, Clone, Debug, Default, Component)]
+pub struct MissileRewind(pub Duration);
+
+impl MissileRewind {
+	/// How far planes should be rewound when
+	/// checking the missile once it is `age` old.
+	///
+	/// The shooter sees the missile just as late
+	/// as they see planes, so only what happened
+	/// before it was fired needs to be made up for.
+	pub fn at(&self, age: Duration) -> Duration {
+		self.0.checked_sub(age).unwrap_or_default()
+	}
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Snapshot {
+	pub time: Instant,
+	pub pos: Position,
+	pub rot: Rotation,
+}
+
+/// Where a plane has been over the
+/// last few frames.
+#[derive(Clone, Debug, Default, Component)]
+pub struct PositionHistory {
+	snapshots: VecDeque<Snapshot>,
+}
+
+impl PositionHistory {
+	/// Add the position of the plane at `time`
+	/// and forget everything older than `keep`.
+	pub fn record(&mut self, time: Instant, pos: Position, rot: Rotation, keep: Duration) {
+		self.snapshots.push_back(Snapshot { time, pos, rot });
+
+		while let Some(first) = self.snapshots.front().cloned() {
+			if time - first.time <= keep {
+				break;
+			}
+			self.snapshots.pop_front();
+		}
+	}
+
+	pub fn clear(&mut self) {
+		self.snapshots.clear();
+	}
+
+	/// Where the plane was at `time`. If the
+	/// history doesn't go back that far then
+	/// this is the oldest known position.
+	pub fn at(&self, time: Instant) -> Option<Snapshot> {
+		self.snapshots
+			.iter()
+			.rev()
+			.find(|s| s.time <= time)
+			.or_else(|| self.snapshots.front())
+			.cloned()
+	}
+}
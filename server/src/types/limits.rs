@@ -0,0 +1,64 @@
+use std::str::FromStr;
+
+/// Limits on who may connect to the server.
+///
+/// Everything is unlimited by default.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionLimits {
+	/// Maximum number of open connections
+	/// from a single IP address.
+	///
+	/// This counts every connection, including
+	/// the backup connection that each client
+	/// opens next to its main one, so allow
+	/// two per player.
+	pub max_connections_per_ip: Option<usize>,
+	/// Maximum number of logged in players
+	/// from a single IP address.
+	pub max_players_per_ip: Option<usize>,
+	/// Values of the `Origin` header that are
+	/// allowed to connect. If set, connections
+	/// without an `Origin` are rejected too.
+	pub allowed_origins: Option<AllowedOrigins>,
+}
+
+impl ConnectionLimits {
+	pub fn allows_origin(&self, origin: Option<&str>) -> bool {
+		match self.allowed_origins {
+			Some(ref allowed) => origin.map(|o| allowed.contains(o)).unwrap_or(false),
+			None => true,
+		}
+	}
+}
+
+/// A list of origins, e.g.
+/// `https://airma.sh`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AllowedOrigins(pub Vec<String>);
+
+impl AllowedOrigins {
+	/// Origins are compared ignoring case
+	/// and any trailing slash.
+	pub fn contains(&self, origin: &str) -> bool {
+		let origin = origin.trim_right_matches('/');
+
+		self.0
+			.iter()
+			.any(|o| o.trim_right_matches('/').eq_ignore_ascii_case(origin))
+	}
+}
+
+impl FromStr for AllowedOrigins {
+	type Err = String;
+
+	/// Parse a comma separated list of origins.
+	fn from_str(s: &str) -> Result<Self, String> {
+		Ok(AllowedOrigins(
+			s.split(',')
+				.map(str::trim)
+				.filter(|s| !s.is_empty())
+				.map(str::to_owned)
+				.collect(),
+		))
+	}
+}
@@ -1,18 +1,24 @@
 mod components;
 mod connection;
+mod entity_ids;
 mod flags;
 mod future;
+mod history;
 mod keystate;
+mod limits;
+mod outbound;
 mod pingdata;
 mod powerups;
+mod status;
+mod transport;
 mod units;
 mod upgrades;
-mod vector2;
 
 mod connection_events;
 
 pub mod collision;
 pub mod config;
+pub mod extension;
 pub mod systemdata;
 
 pub(crate) mod gamemode;
@@ -20,14 +26,21 @@ pub(crate) mod gamemode;
 pub use self::components::*;
 pub use self::config::Config;
 pub use self::connection::*;
+pub use self::entity_ids::{EntityIds, FIRST_ENTITY_ID};
 pub use self::flags::*;
 pub use self::future::FutureDispatcher;
+pub use self::history::*;
 pub use self::keystate::*;
+pub use self::limits::*;
+pub use self::outbound::*;
 pub use self::pingdata::*;
 pub use self::powerups::*;
+pub use self::status::{ServerStatus, StatusHandle};
+pub use self::transport::Transport;
+pub(crate) use self::transport::BothTransports;
 pub use self::units::*;
 pub use self::upgrades::*;
-pub use self::vector2::*;
+pub use protocol::types::Vector2;
 
 pub mod event {
 	pub use types::connection_events::*;
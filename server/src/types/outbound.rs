@@ -0,0 +1,307 @@
+use fnv::FnvHashSet;
+use websocket::dataframe::DataFrameable;
+use websocket::result::WebSocketResult;
+use websocket::ws::Message as MessageTrait;
+use websocket::{Message, OwnedMessage};
+
+use protocol::codes::server::PLAYER_UPDATE;
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::Arc;
+
+/// A message to be sent to a client.
+///
+/// Packets that are sent to many clients at
+/// once should be `Shared` so that they only
+/// need to be serialized and allocated once.
+#[derive(Clone, Debug)]
+pub enum OutboundMessage {
+	Owned(OwnedMessage),
+	Shared(Arc<[u8]>),
+}
+
+impl OutboundMessage {
+	/// The binary payload of the message,
+	/// if it is a binary message.
+	pub fn data(&self) -> Option<&[u8]> {
+		match *self {
+			OutboundMessage::Owned(OwnedMessage::Binary(ref b)) => Some(b),
+			OutboundMessage::Shared(ref b) => Some(b),
+			_ => None,
+		}
+	}
+}
+
+impl From<OwnedMessage> for OutboundMessage {
+	fn from(msg: OwnedMessage) -> Self {
+		OutboundMessage::Owned(msg)
+	}
+}
+
+impl From<Arc<[u8]>> for OutboundMessage {
+	fn from(data: Arc<[u8]>) -> Self {
+		OutboundMessage::Shared(data)
+	}
+}
+
+impl MessageTrait for OutboundMessage {
+	fn serialize(&self, w: &mut Write, masked: bool) -> WebSocketResult<()> {
+		match *self {
+			OutboundMessage::Owned(ref msg) => msg.serialize(w, masked),
+			// Borrow the shared buffer instead of copying it
+			OutboundMessage::Shared(ref b) => Message::binary(&b[..]).serialize(w, masked),
+		}
+	}
+
+	fn message_size(&self, masked: bool) -> usize {
+		match *self {
+			OutboundMessage::Owned(ref msg) => msg.message_size(masked),
+			OutboundMessage::Shared(ref b) => Message::binary(&b[..]).message_size(masked),
+		}
+	}
+
+	fn from_dataframes<D: DataFrameable>(frames: Vec<D>) -> WebSocketResult<Self> {
+		Ok(OutboundMessage::Owned(OwnedMessage::from_dataframes(frames)?))
+	}
+}
+
+/// Limits on the amount of outbound data that
+/// may be buffered for a single connection.
+#[derive(Copy, Clone, Debug)]
+pub struct OutboundLimits {
+	/// Number of queued messages above which
+	/// droppable packets will be coalesced.
+	pub high_water: usize,
+	/// Number of consecutive frames that a
+	/// connection may stay above `high_water`
+	/// (after coalescing) before it is
+	/// disconnected as a slow consumer.
+	pub max_frames_over: u32,
+	/// Number of queued messages at which the
+	/// connection is disconnected straight away.
+	pub max_queued: usize,
+}
+
+impl Default for OutboundLimits {
+	fn default() -> Self {
+		Self {
+			high_water: 1024,
+			// 5 seconds at 60 fps
+			max_frames_over: 300,
+			max_queued: 8192,
+		}
+	}
+}
+
+/// Messages waiting to be written to the
+/// sink of a single connection.
+#[derive(Debug, Default)]
+pub struct OutboundQueue {
+	queue: VecDeque<OutboundMessage>,
+	frames_over: u32,
+	overflowed: bool,
+}
+
+impl OutboundQueue {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn len(&self) -> usize {
+		self.queue.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.queue.is_empty()
+	}
+
+	pub fn push(&mut self, msg: OutboundMessage) {
+		self.queue.push_back(msg);
+	}
+	/// Push a message unless the queue has hit
+	/// `max_queued`. Once that happens nothing
+	/// more is queued and the connection should
+	/// be dropped.
+	pub fn push_limited(&mut self, msg: OutboundMessage, limits: &OutboundLimits) {
+		if self.queue.len() >= limits.max_queued {
+			self.overflowed = true;
+		}
+
+		if !self.overflowed {
+			self.queue.push_back(msg);
+		}
+	}
+	/// Whether a message was ever dropped
+	/// because of `max_queued`.
+	pub fn overflowed(&self) -> bool {
+		self.overflowed
+	}
+
+	pub fn pop(&mut self) -> Option<OutboundMessage> {
+		self.queue.pop_front()
+	}
+	/// Put back a message that the sink
+	/// was not ready to accept.
+	pub fn unpop(&mut self, msg: OutboundMessage) {
+		self.queue.push_front(msg);
+	}
+	pub fn clear(&mut self) {
+		self.queue.clear();
+	}
+
+	/// Remove all `PlayerUpdate` packets that have
+	/// been superseded by a later update for the
+	/// same player. Returns the number of messages
+	/// that were dropped.
+	pub fn coalesce(&mut self) -> usize {
+		let mut seen = FnvHashSet::default();
+		let before = self.queue.len();
+
+		// Walk backwards so that the most recent
+		// update for each player is the one kept
+		let mut kept = self
+			.queue
+			.drain(..)
+			.rev()
+			.filter(|msg| match player_update_id(msg) {
+				Some(id) => seen.insert(id),
+				None => true,
+			})
+			.collect::<Vec<_>>();
+
+		kept.reverse();
+		self.queue = kept.into_iter().collect();
+
+		before - self.queue.len()
+	}
+
+	/// Record whether the queue ended this frame
+	/// above the high-water mark. Returns true
+	/// if the connection has been over the limit
+	/// for too long, or has overflowed, and
+	/// should be dropped.
+	pub fn update_limit(&mut self, limits: &OutboundLimits) -> bool {
+		if self.queue.len() > limits.high_water {
+			self.frames_over += 1;
+		} else {
+			self.frames_over = 0;
+		}
+
+		self.overflowed || self.frames_over > limits.max_frames_over
+	}
+}
+
+/// Get the id of the player within a serialized
+/// `PlayerUpdate` packet, if the message is one.
+///
+/// The layout of the packet is a one-byte code,
+/// a `u32` clock, then the `u16` player id.
+fn player_update_id(msg: &OutboundMessage) -> Option<u16> {
+	match msg.data() {
+		Some(b) if b.len() >= 7 && b[0] == PLAYER_UPDATE => {
+			Some((b[5] as u16) | ((b[6] as u16) << 8))
+		}
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn update(id: u16, x: u8) -> OutboundMessage {
+		let data: Arc<[u8]> = vec![PLAYER_UPDATE, 0, 0, 0, 0, id as u8, (id >> 8) as u8, x].into();
+		data.into()
+	}
+
+	fn other(x: u8) -> OutboundMessage {
+		OwnedMessage::Binary(vec![PLAYER_UPDATE + 1, 0, 0, 0, 0, 1, 0, x]).into()
+	}
+
+	fn limits() -> OutboundLimits {
+		OutboundLimits {
+			high_water: 2,
+			max_frames_over: 2,
+			max_queued: 4,
+		}
+	}
+
+	fn contents(queue: &mut OutboundQueue) -> Vec<Vec<u8>> {
+		let mut out = vec![];
+		while let Some(msg) = queue.pop() {
+			out.push(msg.data().unwrap().to_vec());
+		}
+		out
+	}
+
+	#[test]
+	fn coalesce_keeps_latest_update_per_player() {
+		let mut queue = OutboundQueue::new();
+		queue.push(update(1, 0));
+		queue.push(other(0));
+		queue.push(update(2, 0));
+		queue.push(update(1, 1));
+		queue.push(update(0x100, 0));
+		queue.push(other(1));
+
+		assert_eq!(queue.coalesce(), 1);
+		assert_eq!(
+			contents(&mut queue),
+			vec![
+				other(0).data().unwrap().to_vec(),
+				update(2, 0).data().unwrap().to_vec(),
+				update(1, 1).data().unwrap().to_vec(),
+				update(0x100, 0).data().unwrap().to_vec(),
+				other(1).data().unwrap().to_vec(),
+			]
+		);
+	}
+
+	#[test]
+	fn coalesce_ignores_short_packets() {
+		let mut queue = OutboundQueue::new();
+		queue.push(OwnedMessage::Binary(vec![PLAYER_UPDATE, 0, 0]).into());
+		queue.push(OwnedMessage::Binary(vec![PLAYER_UPDATE, 0, 0]).into());
+
+		assert_eq!(queue.coalesce(), 0);
+		assert_eq!(queue.len(), 2);
+	}
+
+	#[test]
+	fn update_limit_allows_short_bursts() {
+		let mut queue = OutboundQueue::new();
+		for i in 0..3 {
+			queue.push(other(i));
+		}
+
+		assert!(!queue.update_limit(&limits()));
+		assert!(!queue.update_limit(&limits()));
+
+		// Dropping below the high-water mark
+		// resets the count
+		queue.pop();
+		assert!(!queue.update_limit(&limits()));
+		queue.push(other(3));
+		assert!(!queue.update_limit(&limits()));
+		assert!(!queue.update_limit(&limits()));
+		assert!(queue.update_limit(&limits()));
+	}
+
+	#[test]
+	fn hard_cap_drops_immediately() {
+		let mut queue = OutboundQueue::new();
+		for i in 0..6 {
+			queue.push_limited(other(i), &limits());
+		}
+
+		assert_eq!(queue.len(), 4);
+		assert!(queue.overflowed());
+		assert!(queue.update_limit(&limits()));
+
+		// Nothing more is queued, even once
+		// there is room again
+		queue.pop();
+		queue.push_limited(other(7), &limits());
+		assert_eq!(queue.len(), 3);
+	}
+}
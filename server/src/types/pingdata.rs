@@ -1,7 +1,7 @@
 use specs::*;
 
 use std::collections::VecDeque;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Copy, Clone, Component, Default, Debug)]
 pub struct Ping(pub f32);
@@ -16,6 +16,12 @@ pub struct PingFrame {
 pub struct PingData {
 	pub frames: VecDeque<PingFrame>,
 	pub idx: u32,
+	/// The most recently measured round-trip time.
+	pub rtt: Option<Duration>,
+	/// Round-trip time averaged over recent pings
+	/// so that a single slow ping doesn't throw
+	/// off anything that depends on it.
+	pub smoothed_rtt: Option<Duration>,
 }
 
 impl Ping {
@@ -49,6 +55,12 @@ impl PingData {
 				let ping = self.frames.drain(0..i + 1).last().unwrap();
 
 				let dur = now - ping.sent;
+				self.rtt = Some(dur);
+				// Same weighting as TCP uses
+				self.smoothed_rtt = Some(match self.smoothed_rtt {
+					Some(avg) => (avg * 7 + dur) / 8,
+					None => dur,
+				});
 
 				Some(Ping(
 					(dur.as_secs() * 1_000_000 + dur.subsec_micros() as u64) as f32 * 1e-3,
@@ -0,0 +1,55 @@
+use protocol::GameType;
+use types::Team;
+use utils::json::escape;
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// A snapshot of the state of the game,
+/// for server lists and health checks.
+#[derive(Clone, Debug)]
+pub struct ServerStatus {
+	pub room: String,
+	pub gametype: GameType,
+	pub players: u32,
+	/// Sorted by team
+	pub scores: Vec<(Team, u32)>,
+	pub uptime: Duration,
+}
+
+impl ServerStatus {
+	pub fn to_json(&self) -> String {
+		let scores = self
+			.scores
+			.iter()
+			.map(|&(team, score)| format!("{{\"team\":{},\"score\":{}}}", team.0, score))
+			.collect::<Vec<_>>();
+
+		format!(
+			"{{\"room\":\"{}\",\"gametype\":\"{:?}\",\"players\":{},\"scores\":[{}],\"uptime\":{}}}",
+			escape(&self.room),
+			self.gametype,
+			self.players,
+			scores.join(","),
+			self.uptime.as_secs()
+		)
+	}
+}
+
+/// Shared between the game loop, which
+/// keeps the status up to date, and the
+/// acceptor, which serves it over HTTP.
+#[derive(Clone, Default)]
+pub struct StatusHandle(Arc<RwLock<Option<ServerStatus>>>);
+
+impl StatusHandle {
+	pub fn set(&self, status: ServerStatus) {
+		*self.0.write().unwrap() = Some(status);
+	}
+
+	/// Returns `None` until the first
+	/// frame has run.
+	pub fn get(&self) -> Option<ServerStatus> {
+		self.0.read().unwrap().clone()
+	}
+}
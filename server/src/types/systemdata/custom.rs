@@ -0,0 +1,57 @@
+use specs::*;
+
+use protocol::server::ServerCustom;
+use protocol::{ServerCustomType, ServerPacket};
+use types::extension::{envelope, ClientExtensions, CustomMessage};
+use types::{ConnectionType, Connections};
+
+/// Sends messages over the extension channel
+/// to the players that asked for them.
+#[derive(SystemData)]
+pub struct CustomSender<'a> {
+	pub conns: Read<'a, Connections>,
+	pub extensions: ReadStorage<'a, ClientExtensions>,
+}
+
+fn packet<M: CustomMessage>(msg: &M) -> ServerPacket {
+	ServerPacket::ServerCustom(ServerCustom {
+		ty: ServerCustomType::Extension,
+		data: envelope(M::NAME, M::VERSION, &msg.to_json()),
+	})
+}
+
+impl<'a> CustomSender<'a> {
+	pub fn supports<M: CustomMessage>(&self, player: Entity) -> bool {
+		self.extensions
+			.get(player)
+			.map(|e| e.supports::<M>())
+			.unwrap_or(false)
+	}
+
+	/// Send `msg` to `player`. Returns false if the
+	/// player's client doesn't support it.
+	pub fn send_to_player<M: CustomMessage>(&self, player: Entity, msg: &M) -> bool {
+		if !self.supports::<M>(player) {
+			return false;
+		}
+
+		self.conns.send_to_player(player, packet(msg));
+		true
+	}
+
+	/// Send `msg` to every player whose client
+	/// supports it.
+	pub fn send_to_all<M: CustomMessage>(&self, msg: &M) {
+		let data = match self.conns.serialize(&packet(msg)) {
+			Some(data) => data,
+			None => return,
+		};
+
+		self.conns
+			.0
+			.iter()
+			.filter(|(_, conn)| conn.ty == ConnectionType::Primary)
+			.filter(|(_, conn)| conn.player.map(|p| self.supports::<M>(p)).unwrap_or(false))
+			.for_each(|(id, _)| self.conns.send_to(*id, data.clone()));
+	}
+}
@@ -3,6 +3,8 @@
 //! together. Writes to these components
 //! must still be done individually
 
+mod custom;
 mod isalive;
 
+pub use self::custom::CustomSender;
 pub use self::isalive::IsAlive;
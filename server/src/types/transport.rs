@@ -0,0 +1,41 @@
+use types::event::ConnectionEvent;
+
+use std::sync::mpsc::Sender;
+
+/// A source of client connections.
+///
+/// Transports accept connections and report
+/// everything that happens on them through the
+/// channel given to `start`. Each new connection
+/// hands over a [`ConnectionSink`](type.ConnectionSink.html)
+/// that the server uses to write to the client.
+pub trait Transport: Send {
+	/// Start accepting connections.
+	///
+	/// This must not block, transports that
+	/// need to wait on IO should spawn their
+	/// own thread to do so.
+	fn start(self: Box<Self>, channel: Sender<ConnectionEvent>);
+}
+
+impl Transport for Box<Transport> {
+	fn start(self: Box<Self>, channel: Sender<ConnectionEvent>) {
+		<Transport>::start(*self, channel)
+	}
+}
+
+/// Two transports feeding the same server.
+pub(crate) struct BothTransports<A, B>(pub A, pub B);
+
+impl<A, B> Transport for BothTransports<A, B>
+where
+	A: Transport,
+	B: Transport,
+{
+	fn start(self: Box<Self>, channel: Sender<ConnectionEvent>) {
+		let BothTransports(a, b) = *self;
+
+		Box::new(a).start(channel.clone());
+		Box::new(b).start(channel);
+	}
+}
@@ -0,0 +1,18 @@
+use std::fmt::Write;
+
+/// Escape a string so that it can be placed
+/// between quotes within a JSON document.
+pub fn escape(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+
+	for c in s.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+			c => out.push(c),
+		}
+	}
+
+	out
+}
@@ -3,4 +3,5 @@
 //! (not a system, handler, component, or type)
 
 pub mod geoip;
+pub mod json;
 pub mod timer;
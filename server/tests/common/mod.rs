@@ -0,0 +1,93 @@
+//! Shared setup for the integration tests.
+
+#![allow(dead_code)]
+
+use airmash_server::protocol::client::{Command, Key};
+use airmash_server::protocol::{ClientPacket, GameType, KeyCode};
+use airmash_server::testing::{FakeClient, TestServer};
+use airmash_server::types::{Distance, Health};
+use airmash_server::*;
+use specs::Entity;
+
+/// Free-for-all with every player on their own team.
+pub struct TestGameMode;
+
+impl GameMode for TestGameMode {
+	fn assign_team(&mut self, _: Entity, id: u16) -> Team {
+		Team(id)
+	}
+	fn spawn_pos(&mut self, _: Entity, _: Team) -> Position {
+		Position::default()
+	}
+	fn gametype(&self) -> GameType {
+		GameType::FFA
+	}
+	fn room(&self) -> String {
+		"test".to_owned()
+	}
+}
+
+pub fn new_server() -> TestServer {
+	TestServer::new(|server| server.with_engine().with_gamemode(TestGameMode))
+}
+
+/// Connect and log in a new player, returning
+/// the client along with the player's entity.
+pub fn login(server: &mut TestServer, name: &str) -> (FakeClient, Entity) {
+	let client = server.connect();
+	server.step();
+	client.login(name);
+	server.step();
+
+	let player = server
+		.player(&client)
+		.expect("Login did not create a player");
+
+	(client, player)
+}
+
+/// Connect and log in a pure spectator.
+pub fn spectator_login(server: &mut TestServer, name: &str) -> (FakeClient, Entity) {
+	let client = server.connect();
+	server.step();
+	client.login_spectator(name);
+	// Going into spectate takes an extra frame
+	server.step_n(2);
+
+	let player = server
+		.player(&client)
+		.expect("Login did not create a player");
+
+	(client, player)
+}
+
+pub fn set_pos(server: &mut TestServer, player: Entity, x: f32, y: f32) {
+	server
+		.world
+		.write_storage::<Position>()
+		.insert(player, Position::new(Distance::new(x), Distance::new(y)))
+		.unwrap();
+}
+
+pub fn set_health(server: &mut TestServer, player: Entity, health: f32) {
+	server
+		.world
+		.write_storage::<Health>()
+		.insert(player, Health::new(health))
+		.unwrap();
+}
+
+pub fn key(key: KeyCode, state: bool) -> ClientPacket {
+	ClientPacket::Key(Key {
+		seq: 0,
+		key,
+		state,
+	})
+}
+
+pub fn command(com: &str, data: &str) -> ClientPacket {
+	ClientPacket::Command(Command {
+		com: com.to_owned(),
+		data: data.to_owned(),
+	})
+}
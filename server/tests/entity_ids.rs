@@ -0,0 +1,175 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::types::{EntityIds, FIRST_ENTITY_ID};
+use airmash_server::Team;
+use specs::{Builder, Entity, World};
+
+use common::*;
+
+fn create(world: &mut World, n: usize) -> Vec<Entity> {
+	(0..n).map(|_| world.create_entity().build()).collect()
+}
+
+fn delete(world: &mut World, ids: &EntityIds, ent: Entity) {
+	ids.release(ent);
+	world.delete_entity(ent).unwrap();
+	world.maintain();
+
+	ids.release_deleted();
+}
+
+#[test]
+fn ids_start_after_reserved_ids() {
+	let mut world = World::new();
+	let ents = create(&mut world, 3);
+	let ids = EntityIds::default();
+
+	assert_eq!(ids.allocate(ents[1]), Some(FIRST_ENTITY_ID));
+	assert_eq!(ids.allocate(ents[0]), Some(FIRST_ENTITY_ID + 1));
+	assert_eq!(ids.allocate(ents[1]), Some(FIRST_ENTITY_ID));
+	assert_eq!(ids.get(ents[2]), None);
+	assert_eq!(ids.entity(FIRST_ENTITY_ID + 1), Some(ents[0]));
+	assert_eq!(ids.entity(0), None);
+	assert_eq!(ids.len(), 2);
+}
+
+#[test]
+fn released_ids_resolve_until_reused() {
+	let mut world = World::new();
+	let ents = create(&mut world, 1);
+	let ids = EntityIds::default();
+
+	let id = ids.allocate(ents[0]).unwrap();
+	delete(&mut world, &ids, ents[0]);
+
+	assert_eq!(ids.entity(id), Some(ents[0]));
+	assert_eq!(ids.get(ents[0]), Some(id));
+	assert_eq!(ids.len(), 0);
+}
+
+#[test]
+fn new_generation_gets_new_id() {
+	let mut world = World::new();
+	let old = create(&mut world, 1)[0];
+	let ids = EntityIds::default();
+
+	let old_id = ids.allocate(old).unwrap();
+	delete(&mut world, &ids, old);
+
+	// specs reuses the index of the deleted entity
+	let new = create(&mut world, 1)[0];
+	assert_eq!(new.id(), old.id());
+	assert_ne!(ids.allocate(new), Some(old_id));
+}
+
+#[test]
+fn ids_are_reused_oldest_first() {
+	let mut world = World::new();
+	let count = 0x10000 - FIRST_ENTITY_ID as usize;
+	let ents = create(&mut world, count + 2);
+	let ids = EntityIds::default();
+
+	for &ent in &ents[..count] {
+		assert!(ids.allocate(ent).is_some());
+	}
+	assert_eq!(ids.allocate(ents[count]), None);
+
+	let first = ids.get(ents[5]).unwrap();
+	let second = ids.get(ents[2]).unwrap();
+	delete(&mut world, &ids, ents[5]);
+	delete(&mut world, &ids, ents[2]);
+
+	assert_eq!(ids.allocate(ents[count]), Some(first));
+	assert_eq!(ids.allocate(ents[count + 1]), Some(second));
+	assert_eq!(ids.get(ents[5]), None);
+	assert_eq!(ids.entity(first), Some(ents[count]));
+}
+
+#[test]
+fn stale_entities_never_get_a_new_id() {
+	let mut world = World::new();
+	let count = 0x10000 - FIRST_ENTITY_ID as usize;
+	let ents = create(&mut world, count + 2);
+	let ids = EntityIds::default();
+
+	// Only allocating gives out ids
+	assert_eq!(ids.wire(ents[0]).0, 0);
+	assert_eq!(ids.get(ents[0]), None);
+
+	for &ent in &ents[..count] {
+		assert!(ids.allocate(ent).is_some());
+	}
+
+	let old = ents[4];
+	let id = ids.get(old).unwrap();
+	delete(&mut world, &ids, old);
+	assert_eq!(ids.allocate(ents[count]), Some(id));
+
+	// The old entity's id went to someone else
+	assert_eq!(ids.wire(old).0, 0);
+	assert_eq!(ids.get(old), None);
+	ids.release(old);
+	ids.release_deleted();
+
+	// Nothing was leaked or handed out
+	assert_eq!(ids.len(), count);
+	assert_eq!(ids.allocate(ents[count + 1]), None);
+}
+
+#[test]
+fn ids_deleted_in_one_frame_are_reused_in_order() {
+	let mut world = World::new();
+	let count = 0x10000 - FIRST_ENTITY_ID as usize;
+	let ents = create(&mut world, count + 3);
+	let ids = EntityIds::default();
+
+	for &ent in &ents[..count] {
+		assert!(ids.allocate(ent).is_some());
+	}
+
+	let order = [ents[9], ents[3], ents[7]];
+	let released = order
+		.iter()
+		.map(|&ent| ids.get(ent).unwrap())
+		.collect::<Vec<_>>();
+	for &ent in &order {
+		ids.release(ent);
+	}
+
+	// Nothing is free until the frame is over
+	assert_eq!(ids.allocate(ents[count]), None);
+
+	ids.release_deleted();
+
+	for (i, &id) in released.iter().enumerate() {
+		assert_eq!(ids.allocate(ents[count + i]), Some(id));
+	}
+}
+
+#[test]
+fn players_are_sent_with_allocated_ids() {
+	let mut server = new_server();
+	let (_first, pfirst) = login(&mut server, "first");
+	let (_second, psecond) = login(&mut server, "second");
+
+	let first = server.entity_id(pfirst).unwrap().0;
+	let second = server.entity_id(psecond).unwrap().0;
+
+	assert!(first >= FIRST_ENTITY_ID);
+	assert!(second >= FIRST_ENTITY_ID);
+	assert_ne!(first, second);
+}
+
+#[test]
+fn ffa_teams_are_wire_ids() {
+	let mut server = new_server();
+	let (_client, player) = login(&mut server, "player");
+
+	let id = server.entity_id(player).unwrap().0;
+	let team = *server.world.read_storage::<Team>().get(player).unwrap();
+
+	assert_eq!(team, Team(id));
+}
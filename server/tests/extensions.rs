@@ -0,0 +1,120 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::protocol::{ServerCustomType, ServerPacket};
+use airmash_server::testing::{FakeClient, TestServer};
+use airmash_server::types::extension::{CustomMessage, HudText};
+use airmash_server::types::systemdata::CustomSender;
+use specs::SystemData;
+
+use common::*;
+
+struct Capture(u32);
+
+impl CustomMessage for Capture {
+	const NAME: &'static str = "capture";
+	const VERSION: u32 = 2;
+
+	fn to_json(&self) -> String {
+		format!("{{\"points\":{}}}", self.0)
+	}
+}
+
+/// The data of every extension message
+/// that `client` has received.
+fn extension_messages(server: &TestServer, client: &FakeClient) -> Vec<String> {
+	server
+		.received(client)
+		.into_iter()
+		.filter_map(|p| match p {
+			ServerPacket::ServerCustom(ref p) if p.ty == ServerCustomType::Extension => {
+				Some(p.data.clone())
+			}
+			_ => None,
+		})
+		.collect()
+}
+
+fn hud() -> HudText {
+	HudText {
+		id: "objective".to_owned(),
+		text: "Hold the \"hill\"".to_owned(),
+	}
+}
+
+#[test]
+fn handshake_replies_with_hello() {
+	let mut server = new_server();
+	let (client, _) = login(&mut server, "modded");
+	server.received(&client);
+
+	client.send(&command("extensions", "hud:1,marker:1"));
+	server.step();
+
+	let messages = extension_messages(&server, &client);
+
+	assert_eq!(
+		messages,
+		vec![r#"{"t":"hello","v":1,"d":{"hud":1,"marker":1,"timer":1}}"#.to_owned()]
+	);
+}
+
+#[test]
+fn only_sent_to_clients_that_asked() {
+	let mut server = new_server();
+	let (modded, _) = login(&mut server, "modded");
+	let (unmodded, punmodded) = login(&mut server, "unmodded");
+
+	modded.send(&command("extensions", "hud:1"));
+	server.step();
+	server.received(&modded);
+	server.received(&unmodded);
+
+	{
+		let sender = CustomSender::fetch(&server.world.res);
+		sender.send_to_all(&hud());
+		assert!(!sender.send_to_player(punmodded, &hud()));
+	}
+	server.step();
+
+	assert_eq!(
+		extension_messages(&server, &modded),
+		vec![r#"{"t":"hud","v":1,"d":{"id":"objective","text":"Hold the \"hill\""}}"#.to_owned()]
+	);
+	assert!(extension_messages(&server, &unmodded).is_empty());
+}
+
+#[test]
+fn game_modes_can_register_messages() {
+	let mut server = TestServer::new(|server| {
+		server
+			.with_engine()
+			.with_gamemode(TestGameMode)
+			.with_extension::<Capture>()
+	});
+	let (old, pold) = login(&mut server, "old");
+	let (new, pnew) = login(&mut server, "new");
+
+	old.send(&command("extensions", "capture:1"));
+	new.send(&command("extensions", "capture:2"));
+	server.step();
+
+	let hello = extension_messages(&server, &new);
+	assert!(hello[0].contains(r#""capture":2"#));
+	server.received(&old);
+
+	{
+		let sender = CustomSender::fetch(&server.world.res);
+		assert!(!sender.send_to_player(pold, &Capture(3)));
+		assert!(sender.send_to_player(pnew, &Capture(3)));
+	}
+	server.step();
+
+	assert!(extension_messages(&server, &old).is_empty());
+	assert_eq!(
+		extension_messages(&server, &new),
+		vec![r#"{"t":"capture","v":2,"d":{"points":3}}"#.to_owned()]
+	);
+}
@@ -0,0 +1,86 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::protocol::{ErrorType, ServerPacket};
+use airmash_server::testing::TestServer;
+use airmash_server::types::{ConnectionLimits, Connections};
+
+use common::*;
+
+use std::net::IpAddr;
+
+fn limited(limits: ConnectionLimits) -> TestServer {
+	TestServer::new(|server| {
+		server
+			.with_engine()
+			.with_gamemode(TestGameMode)
+			.with_connection_limits(limits)
+	})
+}
+
+fn ip(s: &str) -> IpAddr {
+	s.parse().unwrap()
+}
+
+fn open_connections(server: &TestServer) -> usize {
+	server.world.read_resource::<Connections>().iter().count()
+}
+
+#[test]
+fn origins_outside_allowlist_are_rejected() {
+	let mut server = limited(ConnectionLimits {
+		allowed_origins: Some("https://airma.sh, https://example.com/".parse().unwrap()),
+		..Default::default()
+	});
+
+	let _ok = server.connect_from(ip("10.0.0.1"), Some("https://example.com"));
+	let _bad = server.connect_from(ip("10.0.0.2"), Some("https://evil.example"));
+	let _none = server.connect_from(ip("10.0.0.3"), None);
+	server.step();
+
+	assert_eq!(open_connections(&server), 1);
+}
+
+#[test]
+fn connections_per_ip_are_limited() {
+	let mut server = limited(ConnectionLimits {
+		max_connections_per_ip: Some(2),
+		..Default::default()
+	});
+
+	let _clients = (0..3)
+		.map(|_| server.connect_from(ip("10.0.0.1"), None))
+		.collect::<Vec<_>>();
+	let _other = server.connect_from(ip("10.0.0.2"), None);
+	server.step();
+
+	assert_eq!(open_connections(&server), 3);
+}
+
+#[test]
+fn players_per_ip_are_limited() {
+	let mut server = limited(ConnectionLimits {
+		max_players_per_ip: Some(1),
+		..Default::default()
+	});
+
+	let first = server.connect_from(ip("10.0.0.1"), None);
+	let second = server.connect_from(ip("10.0.0.1"), None);
+	server.step();
+	first.login("first");
+	server.step();
+	second.login("second");
+	server.step();
+
+	let rejected = server.received(&second).iter().any(|p| match p {
+		ServerPacket::Error(e) => e.error == ErrorType::InvalidLogin,
+		_ => false,
+	});
+
+	assert!(rejected, "No error was sent");
+	assert!(server.player(&first).is_some());
+	assert!(server.player(&second).is_none());
+	assert_eq!(open_connections(&server), 1);
+}
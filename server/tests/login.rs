@@ -0,0 +1,174 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::protocol::client::Login;
+use airmash_server::protocol::{ClientPacket, ErrorType, ServerPacket};
+use airmash_server::testing::{FakeClient, TestServer};
+use airmash_server::types::Connections;
+use airmash_server::Name;
+
+use common::*;
+
+#[test]
+fn login_sends_login_packet() {
+	let mut server = new_server();
+	let (client, player) = login(&mut server, "test");
+
+	let login = server
+		.received(&client)
+		.into_iter()
+		.filter_map(|p| match p {
+			ServerPacket::Login(p) => Some(p),
+			_ => None,
+		})
+		.next()
+		.expect("No login packet was sent");
+
+	assert!(login.success);
+	assert_eq!(Some(login.id), server.entity_id(player));
+	assert_eq!(login.room, "test");
+}
+
+#[test]
+fn login_announces_new_player() {
+	let mut server = new_server();
+	let (first, _) = login(&mut server, "first");
+	let (_second, player) = login(&mut server, "second");
+	let id = server.entity_id(player).unwrap();
+
+	let announced = server.received(&first).into_iter().any(|p| match p {
+		ServerPacket::PlayerNew(p) => p.id == id && p.name == "second",
+		_ => false,
+	});
+
+	assert!(announced);
+}
+
+#[test]
+fn disconnect_announces_leave() {
+	let mut server = new_server();
+	let (first, _) = login(&mut server, "first");
+	let (second, player) = login(&mut server, "second");
+	let id = server.entity_id(player).unwrap();
+	server.received(&first);
+
+	second.close();
+	server.step_n(2);
+
+	let left = server.received(&first).into_iter().any(|p| match p {
+		ServerPacket::PlayerLeave(p) => p.id == id,
+		_ => false,
+	});
+
+	assert!(left);
+	assert!(!server.world.entities().is_alive(player));
+}
+
+fn login_packet(name: &str, protocol: u8) -> ClientPacket {
+	ClientPacket::Login(Login {
+		protocol,
+		name: name.to_owned(),
+		session: "none".to_owned(),
+		horizon_x: 3000,
+		horizon_y: 3000,
+		flag: "UN".to_owned(),
+	})
+}
+
+fn errors(server: &TestServer, client: &FakeClient) -> Vec<ErrorType> {
+	server
+		.received(client)
+		.into_iter()
+		.filter_map(|p| match p {
+			ServerPacket::Error(e) => Some(e.error),
+			_ => None,
+		})
+		.collect()
+}
+
+fn player_name(server: &TestServer, client: &FakeClient) -> String {
+	let player = server
+		.player(client)
+		.expect("Login did not create a player");
+
+	server
+		.world
+		.read_storage::<Name>()
+		.get(player)
+		.unwrap()
+		.0
+		.clone()
+}
+
+#[test]
+fn login_rejects_wrong_protocol() {
+	let mut server = new_server();
+	let client = server.connect();
+	server.step();
+
+	client.send(&login_packet("test", 4));
+	server.step();
+
+	assert_eq!(errors(&server, &client), vec![ErrorType::IncorrectProtocol]);
+	assert!(server.player(&client).is_none());
+	assert_eq!(
+		server.world.read_resource::<Connections>().iter().count(),
+		0
+	);
+}
+
+#[test]
+fn login_rejects_empty_name() {
+	let mut server = new_server();
+	let client = server.connect();
+	server.step();
+
+	client.send(&login_packet(" \u{200B}\t\n", 5));
+	server.step();
+
+	assert_eq!(errors(&server, &client), vec![ErrorType::InvalidLogin]);
+	assert!(server.player(&client).is_none());
+}
+
+#[test]
+fn login_sanitizes_name() {
+	let mut server = new_server();
+	let client = server.connect();
+	server.step();
+
+	client.send(&login_packet(
+		"  some\u{202E}  \u{0}name that is far too long  ",
+		5,
+	));
+	server.step();
+
+	assert_eq!(player_name(&server, &client), "some name that is fa");
+}
+
+#[test]
+fn login_deduplicates_names() {
+	let mut server = new_server();
+	let (first, _) = login(&mut server, "name");
+	let (second, _) = login(&mut server, "NAME");
+	let (third, _) = login(&mut server, "name");
+
+	assert_eq!(player_name(&server, &first), "name");
+	assert_eq!(player_name(&server, &second), "NAME#2");
+	assert_eq!(player_name(&server, &third), "name#3");
+}
+
+#[test]
+fn second_login_is_rejected() {
+	let mut server = new_server();
+	let (client, player) = login(&mut server, "test");
+	server.received(&client);
+
+	client.login("again");
+	server.step();
+
+	assert_eq!(errors(&server, &client), vec![ErrorType::InvalidLogin]);
+	assert_eq!(server.player(&client), Some(player));
+	assert_eq!(player_name(&server, &client), "test");
+}
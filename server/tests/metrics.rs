@@ -0,0 +1,25 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::MetricsHandler;
+
+use common::*;
+
+#[test]
+fn metrics_track_players_and_packets() {
+	let mut server = new_server();
+	let _ = login(&mut server, "player");
+	server.step();
+
+	let text = server.world.read_resource::<MetricsHandler>().render();
+
+	assert!(text.contains("# TYPE airmash_players gauge\n"));
+	assert!(text.contains("airmash_players 1\n"));
+	assert!(text.contains("airmash_connections 1\n"));
+	// Login is packet type 0 in both directions
+	assert!(text.contains("airmash_packets_received_total{type=\"0\"} 1\n"));
+	assert!(text.contains("airmash_packets_sent_total{type=\"0\"} 1\n"));
+	assert!(text.contains("airmash_system_time_count{system=\""));
+}
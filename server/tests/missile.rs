@@ -0,0 +1,172 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::component::flag::IsMissile;
+use airmash_server::protocol::{KeyCode, ServerPacket};
+use airmash_server::testing::TestServer;
+use airmash_server::types::{LagCompensation, PingData};
+use airmash_server::Position;
+use specs::Join;
+
+use common::*;
+
+use std::time::Duration;
+
+#[test]
+fn missile_hits_and_kills() {
+	let mut server = new_server();
+	let (shooter, pshooter) = login(&mut server, "shooter");
+	let (target, ptarget) = login(&mut server, "target");
+
+	// Planes start pointing up, so put
+	// the target directly ahead
+	set_pos(&mut server, pshooter, 0.0, 0.0);
+	set_pos(&mut server, ptarget, 0.0, -300.0);
+	// Make sure that a single hit is fatal
+	set_health(&mut server, ptarget, 0.01);
+
+	// Wait out the delay before the first shot
+	server.step_n(60);
+	server.received(&shooter);
+	server.received(&target);
+
+	shooter.send(&key(KeyCode::Fire, true));
+	server.step_n(60);
+
+	let packets = server.received(&target);
+	let shooter_id = server.entity_id(pshooter).unwrap();
+	let target_id = server.entity_id(ptarget).unwrap();
+
+	let fired = packets.iter().any(|p| match p {
+		ServerPacket::PlayerFire(p) => p.id == shooter_id,
+		_ => false,
+	});
+	let hit = packets.iter().any(|p| match p {
+		ServerPacket::PlayerHit(p) => p.owner == shooter_id && p.players[0].id == target_id,
+		_ => false,
+	});
+	let killed = packets.iter().any(|p| match p {
+		ServerPacket::PlayerKill(p) => p.id == target_id && p.killer == Some(shooter_id),
+		_ => false,
+	});
+
+	assert!(fired, "Shooter never fired");
+	assert!(hit, "Missile never hit");
+	assert!(killed, "Target was not killed");
+}
+
+/// Fire at a target that moves out of the
+/// way just as the shot is fired. Returns
+/// whether the missile hit.
+fn fire_at_moved_target(rtt: Option<Duration>) -> bool {
+	let mut server = TestServer::new(|server| {
+		server
+			.with_engine()
+			.with_gamemode(TestGameMode)
+			.with_lag_compensation(LagCompensation {
+				max_rewind: Duration::from_secs(2),
+			})
+	});
+	let (shooter, pshooter) = login(&mut server, "shooter");
+	let (target, ptarget) = login(&mut server, "target");
+
+	set_pos(&mut server, pshooter, 0.0, 0.0);
+	set_pos(&mut server, ptarget, 0.0, -300.0);
+
+	server.step_n(120);
+	server.received(&target);
+
+	server
+		.world
+		.write_storage::<PingData>()
+		.get_mut(pshooter)
+		.unwrap()
+		.smoothed_rtt = rtt;
+	set_pos(&mut server, ptarget, 2000.0, -300.0);
+
+	shooter.send(&key(KeyCode::Fire, true));
+	server.step_n(60);
+
+	let shooter_id = server.entity_id(pshooter).unwrap();
+	let target_id = server.entity_id(ptarget).unwrap();
+
+	server.received(&target).iter().any(|p| match p {
+		ServerPacket::PlayerHit(p) => p.owner == shooter_id && p.players[0].id == target_id,
+		_ => false,
+	})
+}
+
+#[test]
+fn missile_hits_where_shooter_saw_target() {
+	assert!(
+		fire_at_moved_target(Some(Duration::from_secs(2))),
+		"Lag compensated missile missed"
+	);
+	assert!(
+		!fire_at_moved_target(None),
+		"Missile hit without lag compensation"
+	);
+}
+
+#[test]
+fn dodging_after_the_shot_is_not_rewound() {
+	let mut server = TestServer::new(|server| {
+		server
+			.with_engine()
+			.with_gamemode(TestGameMode)
+			.with_lag_compensation(LagCompensation {
+				max_rewind: Duration::from_secs(2),
+			})
+	});
+	let (shooter, pshooter) = login(&mut server, "shooter");
+	let (target, ptarget) = login(&mut server, "target");
+
+	// Far enough away that the missile is in the
+	// air for much longer than the shooter's ping
+	set_pos(&mut server, pshooter, 0.0, 0.0);
+	set_pos(&mut server, ptarget, 0.0, -1500.0);
+
+	server.step_n(120);
+	server
+		.world
+		.write_storage::<PingData>()
+		.get_mut(pshooter)
+		.unwrap()
+		.smoothed_rtt = Some(Duration::from_millis(500));
+
+	shooter.send(&key(KeyCode::Fire, true));
+	server.step();
+	shooter.send(&key(KeyCode::Fire, false));
+
+	// Dodge once the missile is less than
+	// a round trip away from the target
+	let mut close = false;
+	for _ in 0..600 {
+		server.step();
+
+		let missiles = server.world.read_storage::<IsMissile>();
+		let pos = server.world.read_storage::<Position>();
+		close = (&missiles, &pos)
+			.join()
+			.any(|(_, pos)| pos.y.inner() < -1300.0);
+
+		if close {
+			break;
+		}
+	}
+	assert!(close, "Missile never got close to the target");
+
+	server.received(&target);
+	set_pos(&mut server, ptarget, 2000.0, -1500.0);
+	server.step_n(60);
+
+	let shooter_id = server.entity_id(pshooter).unwrap();
+	let hit = server.received(&target).iter().any(|p| match p {
+		ServerPacket::PlayerHit(p) => p.owner == shooter_id,
+		_ => false,
+	});
+
+	assert!(!hit, "Missile hit where the target was before it dodged");
+}
@@ -0,0 +1,116 @@
+extern crate airmash_server;
+
+use airmash_server::{OptionsError, ServerOptions};
+
+use std::env;
+use std::fs::File;
+use std::fs;
+use std::io::Write;
+use std::process;
+use std::time::Duration;
+
+fn args(args: &[&str]) -> Vec<String> {
+	args.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn flags_override_env() {
+	let env = |name: &str| match name {
+		"AIRMASH_ADDR" => Some("127.0.0.1:4000".to_owned()),
+		"AIRMASH_ROOM" => Some("from-env".to_owned()),
+		_ => None,
+	};
+
+	let options = ServerOptions::parse(args(&["--room", "from-flag", "--tick-rate", "30"]), env)
+		.unwrap();
+
+	assert_eq!(options.addr, "127.0.0.1:4000");
+	assert_eq!(options.room("matrix"), "from-flag");
+	assert_eq!(options.tick_rate, 30);
+}
+
+#[test]
+fn config_file_is_overridden() {
+	// Another run of the tests may be using
+	// the temp dir at the same time
+	let path = env::temp_dir().join(format!("airmash-options-{}.conf", process::id()));
+	let mut file = File::create(&path).unwrap();
+	writeln!(file, "# comment").unwrap();
+	writeln!(file, "room = from-file").unwrap();
+	writeln!(file, "max-rewind = 100").unwrap();
+	writeln!(file, "threads = 2").unwrap();
+	drop(file);
+
+	let options = ServerOptions::parse(
+		args(&["--config", path.to_str().unwrap(), "--threads", "4"]),
+		|_: &str| None,
+	);
+	fs::remove_file(&path).unwrap();
+	let options = options.unwrap();
+
+	assert_eq!(options.room("matrix"), "from-file");
+	assert_eq!(options.max_rewind, Some(Duration::from_millis(100)));
+	assert_eq!(options.threads, Some(4));
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+	let none = |_: &str| None;
+
+	assert!(ServerOptions::parse(args(&["--tick-rate", "0"]), none).is_err());
+	assert!(ServerOptions::parse(args(&["--unknown", "1"]), none).is_err());
+	assert!(ServerOptions::parse(args(&["--room"]), none).is_err());
+	assert_eq!(
+		ServerOptions::parse(args(&["--room", "x", "--help"]), none).err(),
+		Some(OptionsError::Help)
+	);
+}
+
+#[test]
+fn connection_limits() {
+	let env = |name: &str| match name {
+		"AIRMASH_ALLOWED_ORIGINS" => Some("https://airma.sh,https://example.com".to_owned()),
+		_ => None,
+	};
+
+	let options = ServerOptions::parse(args(&["--max-players-per-ip", "3"]), env).unwrap();
+
+	assert_eq!(options.limits.max_players_per_ip, Some(3));
+	assert_eq!(options.limits.max_connections_per_ip, None);
+	assert!(options.limits.allows_origin(Some("https://example.com")));
+	assert!(!options.limits.allows_origin(None));
+}
+
+#[test]
+fn raw_transports() {
+	let env = |name: &str| match name {
+		"AIRMASH_RAW_SOCKET" => Some("/tmp/airmash.sock".to_owned()),
+		_ => None,
+	};
+
+	let options = ServerOptions::parse(args(&["--raw-addr", "127.0.0.1:3502"]), env).unwrap();
+
+	assert_eq!(options.raw_addr, Some("127.0.0.1:3502".to_owned()));
+	assert_eq!(options.raw_socket, Some("/tmp/airmash.sock".to_owned()));
+	assert_eq!(ServerOptions::default().raw_addr, None);
+}
+
+#[test]
+fn allowed_origins_rejects_raw_transports() {
+	let none = |_: &str| None;
+	let origins = "https://airma.sh";
+
+	assert!(ServerOptions::parse(args(&["--allowed-origins", origins]), none).is_ok());
+	assert!(
+		ServerOptions::parse(
+			args(&["--allowed-origins", origins, "--raw-addr", "127.0.0.1:3502"]),
+			none
+		).is_err()
+	);
+	assert!(
+		ServerOptions::parse(
+			args(&["--allowed-origins", origins, "--raw-socket", "/tmp/airmash.sock"]),
+			none
+		).is_err()
+	);
+}
@@ -0,0 +1,126 @@
+extern crate airmash_server;
+extern crate hyper;
+
+use airmash_server::types::event::ConnectionEvent;
+use airmash_server::{IpNet, Transport, TrustedProxies, WebsocketTransport};
+
+use hyper::header::Headers;
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpStream};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+fn ip(s: &str) -> IpAddr {
+	s.parse().unwrap()
+}
+
+fn chain(addrs: &[&str]) -> Vec<Option<IpAddr>> {
+	addrs.iter().map(|s| s.parse().ok()).collect()
+}
+
+#[test]
+fn ranges_match_prefix() {
+	let net: IpNet = "10.1.0.0/16".parse().unwrap();
+	assert!(net.contains(&ip("10.1.200.3")));
+	assert!(!net.contains(&ip("10.2.0.1")));
+	assert!(!net.contains(&ip("::1")));
+
+	let net: IpNet = "2001:db8::/32".parse().unwrap();
+	assert!(net.contains(&ip("2001:db8:cafe::17")));
+	assert!(!net.contains(&ip("2001:db9::1")));
+
+	assert!("10.0.0.0/33".parse::<IpNet>().is_err());
+}
+
+#[test]
+fn untrusted_peers_are_not_believed() {
+	let proxies: TrustedProxies = "10.0.0.0/8".parse().unwrap();
+
+	let client = proxies.resolve(ip("203.0.113.5"), &chain(&["1.2.3.4"]));
+	assert_eq!(client, ip("203.0.113.5"));
+}
+
+#[test]
+fn chain_is_walked_from_the_right() {
+	let proxies: TrustedProxies = "10.0.0.0/8, 192.168.1.1".parse().unwrap();
+
+	// The client made up 1.2.3.4, only 198.51.100.7
+	// was added by a proxy that we trust.
+	let client = proxies.resolve(
+		ip("10.0.0.1"),
+		&chain(&["1.2.3.4", "198.51.100.7", "192.168.1.1"]),
+	);
+	assert_eq!(client, ip("198.51.100.7"));
+
+	// Addresses hidden by a proxy stop the walk
+	let client = proxies.resolve(ip("10.0.0.1"), &chain(&["unknown", "10.0.0.2"]));
+	assert_eq!(client, ip("10.0.0.2"));
+}
+
+#[test]
+fn only_the_configured_header_is_read() {
+	let proxies: TrustedProxies = "10.0.0.1, 10.0.0.2=forwarded".parse().unwrap();
+
+	// The proxy sets X-Forwarded-For and passes on
+	// the Forwarded header made up by the client.
+	let mut headers = Headers::new();
+	headers.set_raw("Forwarded", vec![b"for=1.2.3.4".to_vec()]);
+	headers.set_raw("X-Forwarded-For", vec![b"198.51.100.7".to_vec()]);
+
+	let client = proxies.client_addr(ip("10.0.0.1"), &headers);
+	assert_eq!(client, ip("198.51.100.7"));
+
+	// Without the header it sets, it's the client
+	let mut headers = Headers::new();
+	headers.set_raw("Forwarded", vec![b"for=1.2.3.4".to_vec()]);
+
+	let client = proxies.client_addr(ip("10.0.0.1"), &headers);
+	assert_eq!(client, ip("10.0.0.1"));
+
+	// A proxy that sets Forwarded ignores X-Forwarded-For
+	let mut headers = Headers::new();
+	headers.set_raw("Forwarded", vec![b"for=198.51.100.7".to_vec()]);
+	headers.set_raw("X-Forwarded-For", vec![b"1.2.3.4".to_vec()]);
+
+	let client = proxies.client_addr(ip("10.0.0.2"), &headers);
+	assert_eq!(client, ip("198.51.100.7"));
+
+	assert!("10.0.0.1=via".parse::<TrustedProxies>().is_err());
+}
+
+#[test]
+fn connections_get_the_forwarded_address() {
+	let transport = WebsocketTransport::new("127.0.0.1:0")
+		.with_trusted_proxies("127.0.0.1".parse().unwrap())
+		.bind()
+		.unwrap();
+	let addr = transport.local_addr();
+
+	let (send, recv) = channel();
+	Box::new(transport).start(send);
+
+	let mut stream = TcpStream::connect(addr).unwrap();
+	write!(
+		stream,
+		"GET / HTTP/1.1\r\n\
+		 Host: localhost\r\n\
+		 Upgrade: websocket\r\n\
+		 Connection: Upgrade\r\n\
+		 Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+		 Sec-WebSocket-Version: 13\r\n\
+		 X-Forwarded-For: 1.2.3.4, 198.51.100.7\r\n\
+		 \r\n"
+	).unwrap();
+
+	let mut response = [0; 12];
+	stream.read_exact(&mut response).unwrap();
+	assert_eq!(&response, b"HTTP/1.1 101");
+
+	// The per-address limits are checked
+	// against this address.
+	match recv.recv_timeout(Duration::from_secs(5)).unwrap() {
+		ConnectionEvent::ConnectionOpen(evt) => assert_eq!(evt.addr, ip("198.51.100.7")),
+		_ => panic!("expected the connection to open"),
+	}
+}
@@ -0,0 +1,180 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::protocol::client::Login;
+use airmash_server::protocol::{from_bytes, to_bytes, ClientPacket, ServerPacket};
+use airmash_server::testing::TestServer;
+use airmash_server::types::Connections;
+use airmash_server::RawTransport;
+use specs::Entity;
+
+use common::*;
+
+#[cfg(unix)]
+use std::env;
+#[cfg(unix)]
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+fn write_frame(stream: &mut TcpStream, packet: &ClientPacket) {
+	let data = to_bytes(packet).unwrap();
+	let len = data.len() as u32;
+
+	stream
+		.write_all(&[
+			len as u8,
+			(len >> 8) as u8,
+			(len >> 16) as u8,
+			(len >> 24) as u8,
+		])
+		.unwrap();
+	stream.write_all(&data).unwrap();
+}
+
+fn read_frame(stream: &mut TcpStream) -> Vec<u8> {
+	let mut len = [0u8; 4];
+	stream.read_exact(&mut len).unwrap();
+
+	let len = len.iter().rev().fold(0, |acc, &b| (acc << 8) | b as usize);
+	let mut data = vec![0; len];
+	stream.read_exact(&mut data).unwrap();
+	data
+}
+
+/// Run frames until a player has logged in.
+/// Packets from the socket arrive on another
+/// thread so this can take a few tries.
+fn wait_for_player(server: &mut TestServer) -> Entity {
+	for _ in 0..500 {
+		server.step();
+
+		let player = server
+			.world
+			.read_resource::<Connections>()
+			.0
+			.values()
+			.filter_map(|c| c.player)
+			.next();
+
+		if let Some(player) = player {
+			return player;
+		}
+
+		thread::sleep(Duration::from_millis(10));
+	}
+
+	panic!("Login did not create a player");
+}
+
+#[test]
+fn raw_clients_can_log_in() {
+	let raw = RawTransport::bind("127.0.0.1:0").unwrap();
+	let addr = raw.local_addr().unwrap();
+
+	let mut server = TestServer::new(|server| {
+		server
+			.with_engine()
+			.with_gamemode(TestGameMode)
+			.with_transport(raw)
+	});
+
+	let mut stream = TcpStream::connect(addr).unwrap();
+	stream
+		.set_read_timeout(Some(Duration::from_secs(5)))
+		.unwrap();
+
+	write_frame(
+		&mut stream,
+		&ClientPacket::Login(Login {
+			protocol: 5,
+			name: "raw".to_owned(),
+			session: "none".to_owned(),
+			horizon_x: 3000,
+			horizon_y: 3000,
+			flag: "UN".to_owned(),
+		}),
+	);
+
+	let player = wait_for_player(&mut server);
+	let id = server.entity_id(player).unwrap();
+
+	// Other packets may be sent before the login
+	// response depending on the order systems run in.
+	for _ in 0..10 {
+		let data = read_frame(&mut stream);
+
+		if let ServerPacket::Login(login) = from_bytes(&data).unwrap() {
+			assert!(login.success);
+			assert_eq!(login.id, id);
+			return;
+		}
+	}
+
+	panic!("No login packet was sent");
+}
+
+#[test]
+fn closing_the_socket_closes_the_connection() {
+	let raw = RawTransport::bind("127.0.0.1:0").unwrap();
+	let addr = raw.local_addr().unwrap();
+
+	let mut server = TestServer::new(|server| {
+		server
+			.with_engine()
+			.with_gamemode(TestGameMode)
+			.with_transport(raw)
+	});
+
+	let stream = TcpStream::connect(addr).unwrap();
+
+	for _ in 0..500 {
+		server.step();
+		if !server.world.read_resource::<Connections>().0.is_empty() {
+			break;
+		}
+		thread::sleep(Duration::from_millis(10));
+	}
+	assert_eq!(server.world.read_resource::<Connections>().0.len(), 1);
+
+	drop(stream);
+
+	for _ in 0..500 {
+		server.step();
+		if server.world.read_resource::<Connections>().0.is_empty() {
+			return;
+		}
+		thread::sleep(Duration::from_millis(10));
+	}
+
+	panic!("The connection was never closed");
+}
+
+#[test]
+#[cfg(unix)]
+fn stale_unix_sockets_are_replaced() {
+	use airmash_server::UnixTransport;
+
+	let path = env::temp_dir().join(format!("airmash-raw-{}.sock", process::id()));
+	fs::remove_file(&path).ok();
+
+	// Dropping the listener leaves the socket behind
+	drop(UnixTransport::bind(&path).unwrap());
+	assert!(path.exists());
+
+	let live = UnixTransport::bind(&path).unwrap();
+	assert!(UnixTransport::bind(&path).is_err());
+	drop(live);
+	fs::remove_file(&path).unwrap();
+
+	// Files that aren't sockets are left alone
+	File::create(&path).unwrap();
+	assert!(UnixTransport::bind(&path).is_err());
+	fs::remove_file(&path).unwrap();
+}
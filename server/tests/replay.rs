@@ -0,0 +1,139 @@
+extern crate airmash_server;
+
+use airmash_server::protocol::GameType;
+use airmash_server::replay::{Record, ReplayHeader, ReplayReader, ReplayWriter, Target};
+
+use std::io;
+use std::time::Duration;
+
+fn header() -> ReplayHeader {
+	ReplayHeader {
+		gametype: GameType::CTF,
+		room: "matrix".to_owned(),
+		tick: Duration::from_millis(50),
+	}
+}
+
+fn records() -> Vec<Record> {
+	vec![
+		Record {
+			time: 0,
+			target: Target::All,
+			data: vec![1, 2, 3].into(),
+		},
+		Record {
+			time: 0,
+			target: Target::Player(3),
+			data: vec![].into(),
+		},
+		Record {
+			time: 20,
+			target: Target::Except(0x1234),
+			data: vec![0xFF; 300].into(),
+		},
+		Record {
+			time: 100_000,
+			target: Target::All,
+			data: vec![4].into(),
+		},
+	]
+}
+
+fn write(records: &[Record]) -> Vec<u8> {
+	let mut buf = vec![];
+
+	{
+		let mut writer = ReplayWriter::new(&mut buf, &header()).unwrap();
+		for record in records {
+			writer.write(record).unwrap();
+		}
+	}
+
+	buf
+}
+
+fn read(data: &[u8]) -> io::Result<(ReplayHeader, Vec<Record>)> {
+	let reader = ReplayReader::new(data)?;
+	let header = reader.header().clone();
+	let records = reader.collect::<io::Result<Vec<_>>>()?;
+
+	Ok((header, records))
+}
+
+fn assert_same(a: &[Record], b: &[Record]) {
+	assert_eq!(a.len(), b.len());
+
+	for (a, b) in a.iter().zip(b) {
+		assert_eq!(a.time, b.time);
+		assert_eq!(a.target, b.target);
+		assert_eq!(a.data, b.data);
+	}
+}
+
+#[test]
+fn records_round_trip() {
+	let (header, read) = read(&write(&records())).unwrap();
+
+	assert_eq!(header.gametype, GameType::CTF);
+	assert_eq!(header.room, "matrix");
+	assert_eq!(header.tick, Duration::from_millis(50));
+	assert_same(&read, &records());
+}
+
+#[test]
+fn empty_replay_round_trips() {
+	let (header, read) = read(&write(&[])).unwrap();
+
+	assert_eq!(header.room, "matrix");
+	assert!(read.is_empty());
+}
+
+#[test]
+fn records_out_of_order_are_rejected() {
+	let mut buf = vec![];
+	let mut writer = ReplayWriter::new(&mut buf, &header()).unwrap();
+	let mut records = records();
+	records.reverse();
+
+	writer.write(&records[0]).unwrap();
+	assert!(writer.write(&records[1]).is_err());
+}
+
+#[test]
+fn truncated_replays_fail_cleanly() {
+	let records = records();
+	let full = write(&records);
+	let header_len = write(&[]).len();
+
+	// Where each record ends in the file
+	let ends = (1..=records.len())
+		.map(|n| write(&records[..n]).len())
+		.collect::<Vec<_>>();
+
+	for len in 0..full.len() {
+		let result = read(&full[..len]);
+
+		if len < header_len {
+			assert!(result.is_err(), "header cut at {} bytes was accepted", len);
+		} else if len == header_len {
+			assert!(result.unwrap().1.is_empty());
+		} else if let Some(n) = ends.iter().position(|&end| end == len) {
+			// Cutting exactly between two records
+			// looks like a shorter replay.
+			assert_same(&result.unwrap().1, &records[..n + 1]);
+		} else {
+			assert!(result.is_err(), "record cut at {} bytes was accepted", len);
+		}
+	}
+}
+
+#[test]
+fn unknown_files_are_rejected() {
+	let mut data = write(&records());
+	data[0] = b'X';
+	assert!(read(&data).is_err());
+
+	let mut data = write(&records());
+	data[4] = 0xFF;
+	assert!(read(&data).is_err());
+}
@@ -0,0 +1,63 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::protocol::KeyCode;
+use airmash_server::types::{Energy, Health, Rotation};
+use airmash_server::{Position, Velocity};
+
+use common::*;
+
+use std::thread;
+use std::time::Duration;
+
+/// Play out a short fight and describe
+/// everything that came out of it.
+fn run() -> String {
+	let mut server = new_server();
+	let (shooter, pshooter) = login(&mut server, "shooter");
+	let (target, ptarget) = login(&mut server, "target");
+
+	set_pos(&mut server, pshooter, 0.0, 0.0);
+	set_pos(&mut server, ptarget, 500.0, -300.0);
+	server.step_n(60);
+
+	shooter.send(&key(KeyCode::Up, true));
+	shooter.send(&key(KeyCode::Fire, true));
+	target.send(&key(KeyCode::Left, true));
+	server.step_n(45);
+
+	shooter.send(&key(KeyCode::Fire, false));
+	target.send(&key(KeyCode::Left, false));
+	target.send(&key(KeyCode::Up, true));
+	server.step_n(90);
+
+	let mut out = String::new();
+
+	for &player in &[pshooter, ptarget] {
+		out += &format!(
+			"{:?} {:?} {:?} {:?} {:?}\n",
+			server.world.read_storage::<Position>().get(player),
+			server.world.read_storage::<Velocity>().get(player),
+			server.world.read_storage::<Rotation>().get(player),
+			server.world.read_storage::<Energy>().get(player),
+			server.world.read_storage::<Health>().get(player),
+		);
+	}
+
+	out += &format!("{:?}\n", server.received(&shooter));
+	out += &format!("{:?}\n", server.received(&target));
+	out
+}
+
+#[test]
+fn same_inputs_give_same_results() {
+	let first = run();
+
+	// Let the wall clock move on so that
+	// any use of it would show up.
+	thread::sleep(Duration::from_millis(50));
+
+	assert_eq!(first, run());
+}
@@ -0,0 +1,210 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::component::flag::{IsPureSpectator, IsSpectating};
+use airmash_server::component::reference::PlayerRef;
+use airmash_server::protocol::{KeyCode, ServerPacket};
+use airmash_server::{Position, Team};
+
+use common::*;
+
+#[test]
+fn spectate_despawns_player() {
+	let mut server = new_server();
+	let (spec, pspec) = login(&mut server, "spectator");
+	let (_other, _) = login(&mut server, "other");
+	server.received(&spec);
+
+	spec.send(&command("spectate", "-3"));
+	server.step();
+
+	let packets = server.received(&spec);
+	let id = server.entity_id(pspec).unwrap();
+
+	let killed = packets.iter().any(|p| match p {
+		ServerPacket::PlayerKill(p) => p.id == id && p.killer.is_none(),
+		_ => false,
+	});
+	let spectating = packets.iter().any(|p| match p {
+		ServerPacket::GameSpectate(_) => true,
+		_ => false,
+	});
+
+	assert!(killed);
+	assert!(spectating);
+	assert!(
+		server
+			.world
+			.read_storage::<IsSpectating>()
+			.get(pspec)
+			.is_some()
+	);
+}
+
+#[test]
+fn force_spectate_keeps_team() {
+	let mut server = new_server();
+	let (spec, pspec) = login(&mut server, "spectator");
+	let (other, _) = login(&mut server, "other");
+	let team = *server.world.read_storage::<Team>().get(pspec).unwrap();
+	server.received(&other);
+
+	spec.send(&command("spectate", "-3"));
+	server.step();
+
+	let reteamed = server.received(&other).iter().any(|p| match p {
+		ServerPacket::PlayerReteam(_) => true,
+		_ => false,
+	});
+
+	assert!(!reteamed);
+	assert_eq!(*server.world.read_storage::<Team>().get(pspec).unwrap(), team);
+	assert!(
+		server
+			.world
+			.read_storage::<IsPureSpectator>()
+			.get(pspec)
+			.is_none()
+	);
+}
+
+#[test]
+fn spectator_login_never_joins_a_team() {
+	let mut server = new_server();
+	let (other, pother) = login(&mut server, "other");
+	let (spec, pspec) = spectator_login(&mut server, "spectator");
+	let id = server.entity_id(pspec).unwrap();
+	let other_id = server.entity_id(pother).unwrap();
+
+	let following = server.received(&spec).iter().any(|p| match p {
+		ServerPacket::GameSpectate(p) => p.id == other_id,
+		_ => false,
+	});
+	let announced = server.received(&other).iter().any(|p| match p {
+		ServerPacket::PlayerNew(p) => p.id == id && p.team == Team(id.0),
+		_ => false,
+	});
+
+	assert!(following);
+	assert!(announced);
+	assert_eq!(
+		*server.world.read_storage::<Team>().get(pspec).unwrap(),
+		Team(id.0)
+	);
+	assert!(
+		server
+			.world
+			.read_storage::<IsPureSpectator>()
+			.get(pspec)
+			.is_some()
+	);
+}
+
+#[test]
+fn spectator_command_leaves_team() {
+	let mut server = new_server();
+	let (other, pother) = login(&mut server, "other");
+	let (spec, pspec) = login(&mut server, "spectator");
+	let id = server.entity_id(pspec).unwrap();
+	let other_id = server.entity_id(pother).unwrap();
+	server.received(&spec);
+	server.received(&other);
+
+	spec.send(&command("spectator", ""));
+	server.step();
+
+	let following = server.received(&spec).iter().any(|p| match p {
+		ServerPacket::GameSpectate(p) => p.id == other_id,
+		_ => false,
+	});
+	let reteamed = server.received(&other).iter().any(|p| match p {
+		ServerPacket::PlayerReteam(p) => p.players[0].id == id && p.players[0].team == Team(id.0),
+		_ => false,
+	});
+
+	assert!(following);
+	assert!(reteamed);
+	assert!(
+		server
+			.world
+			.read_storage::<IsPureSpectator>()
+			.get(pspec)
+			.is_some()
+	);
+	assert!(
+		server
+			.world
+			.read_storage::<IsSpectating>()
+			.get(pspec)
+			.is_some()
+	);
+}
+
+#[test]
+fn free_cam_follows_keys() {
+	let mut server = new_server();
+	let (_other, pother) = login(&mut server, "other");
+	let (spec, pspec) = spectator_login(&mut server, "spectator");
+	set_pos(&mut server, pother, 1000.0, 0.0);
+
+	// Spectating yourself switches to free-cam
+	let id = server.entity_id(pspec).unwrap();
+	spec.send(&command("spectate", &id.0.to_string()));
+	server.step();
+
+	assert_eq!(
+		server.world.read_storage::<PlayerRef>().get(pspec).unwrap().0,
+		pspec
+	);
+	let start = *server.world.read_storage::<Position>().get(pspec).unwrap();
+	assert_eq!(start.x.inner(), 1000.0);
+
+	spec.send(&key(KeyCode::Right, true));
+	server.step_n(10);
+
+	let end = *server.world.read_storage::<Position>().get(pspec).unwrap();
+	assert!(end.x > start.x);
+	assert_eq!(end.y, start.y);
+
+	// Following a player again stops the camera
+	// from responding to keys.
+	spec.send(&command("spectate", "-1"));
+	server.step();
+	let followed = *server.world.read_storage::<Position>().get(pspec).unwrap();
+	server.step_n(10);
+
+	assert_eq!(
+		server.world.read_storage::<PlayerRef>().get(pspec).unwrap().0,
+		pother
+	);
+	assert_eq!(
+		*server.world.read_storage::<Position>().get(pspec).unwrap(),
+		followed
+	);
+}
+
+#[test]
+fn respawn_leaves_pure_spectate() {
+	let mut server = new_server();
+	let (spec, pspec) = spectator_login(&mut server, "spectator");
+
+	spec.send(&command("respawn", "1"));
+	server.step();
+
+	assert!(
+		server
+			.world
+			.read_storage::<IsPureSpectator>()
+			.get(pspec)
+			.is_none()
+	);
+	assert!(
+		server
+			.world
+			.read_storage::<IsSpectating>()
+			.get(pspec)
+			.is_none()
+	);
+}
@@ -0,0 +1,30 @@
+extern crate airmash_server;
+extern crate specs;
+
+mod common;
+
+use airmash_server::types::StatusHandle;
+
+use common::*;
+
+#[test]
+fn status_reports_players() {
+	let mut server = new_server();
+	let _ = login(&mut server, "player");
+	// The status is only updated once a second
+	server.step_n(60);
+
+	let status = server
+		.world
+		.read_resource::<StatusHandle>()
+		.get()
+		.expect("Status was never set");
+
+	assert_eq!(status.room, "test");
+	assert_eq!(status.players, 1);
+	assert!(
+		status
+			.to_json()
+			.starts_with("{\"room\":\"test\",\"gametype\":\"FFA\",\"players\":1,")
+	);
+}
@@ -0,0 +1,83 @@
+extern crate airmash_server;
+extern crate native_tls;
+
+use airmash_server::protocol::GameType;
+use airmash_server::types::{ServerStatus, StatusHandle};
+use airmash_server::{Transport, WebsocketTransport};
+
+use native_tls::TlsConnector;
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+const IDENTITY: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/identity.p12");
+
+fn transport() -> WebsocketTransport<&'static str> {
+	WebsocketTransport::new("127.0.0.1:0")
+}
+
+#[test]
+fn status_is_served_over_tls() {
+	let status = StatusHandle::default();
+	status.set(ServerStatus {
+		room: "secure".to_owned(),
+		gametype: GameType::FFA,
+		players: 0,
+		scores: vec![],
+		uptime: Duration::from_secs(0),
+	});
+
+	let transport = transport()
+		.with_tls("127.0.0.1:0", IDENTITY, "test")
+		.unwrap()
+		.with_status(status)
+		.bind()
+		.unwrap();
+	let addr = transport.tls_addr().expect("TLS address was not bound");
+
+	let (send, _recv) = channel();
+	Box::new(transport).start(send);
+
+	let connector = TlsConnector::builder().unwrap().build().unwrap();
+	let stream = TcpStream::connect(addr).unwrap();
+	let mut stream = connector
+		.danger_connect_without_providing_domain_for_certificate_verification_and_server_name_indication(stream)
+		.unwrap();
+
+	stream
+		.write_all(b"GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n")
+		.unwrap();
+
+	let mut response = String::new();
+	stream.read_to_string(&mut response).ok();
+
+	assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+	assert!(response.contains("\"room\":\"secure\""), "{}", response);
+}
+
+#[test]
+fn address_in_use_is_an_error() {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let addr = listener.local_addr().unwrap();
+
+	assert!(WebsocketTransport::new(addr).bind().is_err());
+
+	// Only the TLS address is taken
+	let free: SocketAddr = "127.0.0.1:0".parse().unwrap();
+	let tls = WebsocketTransport::new(free)
+		.with_tls(addr, IDENTITY, "test")
+		.unwrap();
+	assert!(tls.bind().is_err());
+}
+
+#[test]
+fn bad_identity_is_an_error() {
+	assert!(transport().with_tls("127.0.0.1:0", IDENTITY, "wrong").is_err());
+	assert!(
+		transport()
+			.with_tls("127.0.0.1:0", "tests/data/missing.p12", "test")
+			.is_err()
+	);
+}
@@ -0,0 +1,8 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate specgen;
+
+fuzz_target!(|data: &[u8]| {
+	let _ = specgen::parse(data);
+});
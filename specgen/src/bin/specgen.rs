@@ -0,0 +1,65 @@
+
+extern crate specgen;
+
+use specgen::*;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process;
+
+const USAGE: &'static str = "usage: specgen <typescript|json|markdown> <SPEC> [OUTPUT]";
+
+fn generate<W>(mode: &str, bytes: &[u8], out: &mut W) -> io::Result<()>
+where W: Write
+{
+	match mode {
+		"typescript" | "ts" => TypeScriptBuilder::new().build(bytes, out),
+		"json" => JsonSchemaBuilder::new().build(bytes, out),
+		"markdown" | "md" => MarkdownBuilder::new().build(bytes, out),
+		_ => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	}
+}
+
+fn main() {
+	let args = env::args().collect::<Vec<String>>();
+
+	if args.len() < 3 || args.len() > 4 {
+		eprintln!("{}", USAGE);
+		process::exit(2);
+	}
+
+	let mut bytes = vec![];
+	if let Err(e) = File::open(&args[2]).and_then(|mut f| f.read_to_end(&mut bytes)) {
+		eprintln!("specgen: {}: {}", args[2], e);
+		process::exit(1);
+	}
+
+	// Check the spec here so that diagnostics
+	// point at the real file name.
+	let diagnostics = match parse(&bytes) {
+		Ok(protocol) => validate(&protocol),
+		Err(e) => vec![e]
+	};
+
+	for diagnostic in diagnostics.iter() {
+		eprintln!("{}", diagnostic.render(&args[2], &bytes));
+	}
+
+	if diagnostics.iter().any(|d| !d.is_warning()) {
+		process::exit(1);
+	}
+
+	let result = match args.get(3) {
+		Some(path) => File::create(path).and_then(|mut f| generate(&args[1], &bytes, &mut f)),
+		None => generate(&args[1], &bytes, &mut io::stdout())
+	};
+
+	if let Err(e) = result {
+		eprintln!("specgen: {}", e);
+		process::exit(1);
+	}
+}
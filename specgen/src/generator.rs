@@ -1,5 +1,6 @@
 
 use parser::*;
+use validate::load;
 use std::io::{Write, Error};
 
 pub struct Generator {
@@ -80,12 +81,13 @@ impl Generator {
 	pub fn build<W>(self, specdata: &[u8], writer: &mut W) -> Result<(), Error>
 	where W: Write
 	{
-		let Protocol{ specs, types } = parse(specdata).unwrap();
+		let Protocol{ specs, types } = load(specdata)?;
 
 		writeln!(writer, "use std::convert::From;")?;
 
 		for ty in types {
 			match ty.class {
+				TypeClass::Extern => (),
 				TypeClass::Enum(branches, base_ty) => {
 					self.print_attrs(writer)?;
 
@@ -192,6 +194,12 @@ impl Generator {
 			)?;
 
 			for def in spec.defs {
+				// Packets without any fields are unit
+				// variants of the packet enum.
+				if def.code.is_some() && def.fields.is_empty() {
+					continue;
+				}
+
 				Self::print_docs(writer, &def.docs)?;
 				self.print_attrs(writer)?;
 				writeln!(writer, 
@@ -0,0 +1,290 @@
+use parser::*;
+use validate::load;
+use std::io::{Write, Error, ErrorKind};
+
+/// Just enough of a JSON value to build
+/// the schema without pulling in serde.
+enum Json {
+	Bool(bool),
+	Number(f64),
+	Str(String),
+	Array(Vec<Json>),
+	Object(Vec<(String, Json)>)
+}
+
+impl Json {
+	fn str(s: &str) -> Json {
+		Json::Str(s.to_owned())
+	}
+	fn object(fields: Vec<(&str, Json)>) -> Json {
+		Json::Object(fields.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+	}
+
+	fn write_str<W>(writer: &mut W, s: &str) -> Result<(), Error>
+	where W: Write
+	{
+		write!(writer, "\"")?;
+		for c in s.chars() {
+			match c {
+				'"' => write!(writer, "\\\"")?,
+				'\\' => write!(writer, "\\\\")?,
+				'\n' => write!(writer, "\\n")?,
+				'\r' => write!(writer, "\\r")?,
+				'\t' => write!(writer, "\\t")?,
+				c if (c as u32) < 0x20 => write!(writer, "\\u{:04x}", c as u32)?,
+				c => write!(writer, "{}", c)?
+			}
+		}
+		write!(writer, "\"")
+	}
+
+	fn write<W>(&self, writer: &mut W, indent: usize) -> Result<(), Error>
+	where W: Write
+	{
+		let pad = "\t".repeat(indent + 1);
+		let end = "\t".repeat(indent);
+
+		match self {
+			Json::Bool(b) => write!(writer, "{}", b),
+			Json::Number(n) => write!(writer, "{}", n),
+			Json::Str(s) => Self::write_str(writer, s),
+			Json::Array(items) => {
+				if items.is_empty() {
+					return write!(writer, "[]");
+				}
+
+				writeln!(writer, "[")?;
+				for (i, item) in items.iter().enumerate() {
+					write!(writer, "{}", pad)?;
+					item.write(writer, indent + 1)?;
+					writeln!(writer, "{}", if i + 1 == items.len() { "" } else { "," })?;
+				}
+				write!(writer, "{}]", end)
+			},
+			Json::Object(fields) => {
+				if fields.is_empty() {
+					return write!(writer, "{{}}");
+				}
+
+				writeln!(writer, "{{")?;
+				for (i, (key, val)) in fields.iter().enumerate() {
+					write!(writer, "{}", pad)?;
+					Self::write_str(writer, key)?;
+					write!(writer, ": ")?;
+					val.write(writer, indent + 1)?;
+					writeln!(writer, "{}", if i + 1 == fields.len() { "" } else { "," })?;
+				}
+				write!(writer, "{}}}", end)
+			}
+		}
+	}
+}
+
+/// Generates a JSON schema (draft 07) describing
+/// the JSON form of every definition in a spec.
+///
+/// Each definition is keyed by `<spec>.<name>`
+/// under `definitions` and carries its packet
+/// code in `x-code`. The `x-packets` object maps
+/// the packet names in each spec to their codes.
+pub struct JsonSchemaGenerator;
+
+fn integer(min: f64, max: f64) -> Json {
+	Json::object(vec![
+		("type", Json::str("integer")),
+		("minimum", Json::Number(min)),
+		("maximum", Json::Number(max)),
+	])
+}
+fn number() -> Json {
+	Json::object(vec![("type", Json::str("number"))])
+}
+fn reference(name: &str) -> Json {
+	Json::object(vec![("$ref", Json::Str(format!("#/definitions/{}", name)))])
+}
+fn strict_object(props: Vec<(&str, Json)>) -> Json {
+	let required = props.iter().map(|&(k, _)| Json::str(k)).collect();
+
+	Json::object(vec![
+		("type", Json::str("object")),
+		("properties", Json::object(props)),
+		("required", Json::Array(required)),
+		("additionalProperties", Json::Bool(false)),
+	])
+}
+
+fn unknown_type(ty: &SpecFieldType) -> Error {
+	Error::new(
+		ErrorKind::InvalidData,
+		format!("type `{}` has no JSON schema equivalent", ty.to_str())
+	)
+}
+
+impl JsonSchemaGenerator {
+	pub fn new() -> Self {
+		JsonSchemaGenerator
+	}
+
+	fn field_schema(
+		spec: &str,
+		enums: &[&str],
+		defs: &[&str],
+		ty: &SpecFieldType
+	) -> Result<Json, Error> {
+		Ok(match ty {
+			SpecFieldType::Simple(name) => match *name {
+				"bool" => Json::object(vec![("type", Json::str("boolean"))]),
+				"u8" | "Level" | "Flag" => integer(0.0, 255.0),
+				"u16" | "Entity" | "Team" => integer(0.0, 65535.0),
+				"u32" | "Score" => integer(0.0, 4294967295.0),
+				"i8" => integer(-128.0, 127.0),
+				"i16" => integer(-32768.0, 32767.0),
+				"i32" => integer(-2147483648.0, 2147483647.0),
+				"f32" | "f64" | "Rotation" | "Speed" | "HealthRegen" | "EnergyRegen" => number(),
+				"Health" | "Energy" => Json::object(vec![
+					("type", Json::str("number")),
+					("minimum", Json::Number(0.0)),
+					("maximum", Json::Number(1.0)),
+				]),
+				"Text" => Json::object(vec![("type", Json::str("string"))]),
+				"TextBig" => Json::object(vec![("type", Json::str("string"))]),
+				"Position" | "Position24" | "Position_f32" | "LowResPos" | "Velocity" | "Accel" => {
+					reference("Vector2")
+				},
+				"Upgrades" | "ServerKeyState" => reference(name),
+				name if enums.contains(&name) => reference(name),
+				name if defs.contains(&name) => reference(&format!("{}.{}", spec, name)),
+				_ => return Err(unknown_type(ty))
+			},
+			SpecFieldType::Compound(name, args) => match (*name, args.as_slice()) {
+				("Array", [inner]) | ("ArraySmall", [inner]) => {
+					let max = if *name == "Array" { 65535.0 } else { 255.0 };
+
+					Json::object(vec![
+						("type", Json::str("array")),
+						("items", Self::field_schema(spec, enums, defs, inner)?),
+						("maxItems", Json::Number(max)),
+					])
+				},
+				("Option", [inner]) => Json::object(vec![
+					("oneOf", Json::Array(vec![
+						Self::field_schema(spec, enums, defs, inner)?,
+						Json::object(vec![("type", Json::str("null"))]),
+					]))
+				]),
+				_ => return Err(unknown_type(ty))
+			}
+		})
+	}
+
+	fn description(docs: &[&str]) -> Option<Json> {
+		if docs.is_empty() {
+			return None;
+		}
+
+		let lines = docs.iter().map(|d| d.trim()).collect::<Vec<_>>();
+		Some(Json::Str(lines.join("\n")))
+	}
+
+	pub fn build<W>(self, specdata: &[u8], writer: &mut W) -> Result<(), Error>
+	where W: Write
+	{
+		let Protocol{ specs, types } = load(specdata)?;
+
+		let mut definitions = vec![
+			("Vector2".to_owned(), strict_object(vec![
+				("x", number()),
+				("y", number()),
+			])),
+			("Upgrades".to_owned(), strict_object(vec![
+				("speed", integer(0.0, 7.0)),
+				("shield", Json::object(vec![("type", Json::str("boolean"))])),
+				("inferno", Json::object(vec![("type", Json::str("boolean"))])),
+			])),
+			("ServerKeyState".to_owned(), strict_object(
+				["up", "down", "left", "right", "boost", "strafe", "stealth", "flagspeed"]
+					.iter()
+					.map(|&k| (k, Json::object(vec![("type", Json::str("boolean"))])))
+					.collect()
+			)),
+		];
+
+		for ty in types.iter() {
+			let (branches, base_ty) = match ty.class {
+				TypeClass::Enum(ref branches, ref base_ty) => (branches, base_ty),
+				TypeClass::Extern => continue
+			};
+
+			let mut schema = vec![];
+			if let Some(desc) = Self::description(&ty.docs) {
+				schema.push(("description", desc));
+			}
+			schema.push(("type", Json::str("string")));
+			schema.push(("enum", Json::Array(
+				branches.iter().map(|b| Json::str(b.name)).collect()
+			)));
+			schema.push(("x-type", Json::Str(base_ty.to_str())));
+			schema.push(("x-values", Json::Object(
+				branches.iter()
+					.map(|b| (b.name.to_owned(), Json::Number(b.value as f64)))
+					.collect()
+			)));
+
+			definitions.push((ty.name.to_owned(), Json::object(schema)));
+		}
+
+		let enums = types.iter()
+			.filter(|t| match t.class { TypeClass::Enum(..) => true, _ => false })
+			.map(|t| t.name)
+			.collect::<Vec<_>>();
+		let mut packets = vec![];
+
+		for spec in specs.iter() {
+			let defs = spec.defs.iter().map(|d| d.name).collect::<Vec<_>>();
+
+			for def in spec.defs.iter() {
+				let mut props = vec![];
+				for field in def.fields.iter() {
+					let mut schema = match Self::field_schema(spec.name, &enums, &defs, &field.ty)? {
+						Json::Object(fields) => fields,
+						_ => unreachable!()
+					};
+					if let Some(desc) = Self::description(&field.docs) {
+						schema.insert(0, ("description".to_owned(), desc));
+					}
+					schema.push(("x-type".to_owned(), Json::Str(field.ty.to_str())));
+
+					props.push((field.name, Json::Object(schema)));
+				}
+
+				let mut schema = match strict_object(props) {
+					Json::Object(fields) => fields,
+					_ => unreachable!()
+				};
+				if let Some(desc) = Self::description(&def.docs) {
+					schema.insert(0, ("description".to_owned(), desc));
+				}
+				if let Some(code) = def.code {
+					schema.push(("x-code".to_owned(), Json::Number(code as f64)));
+				}
+
+				definitions.push((format!("{}.{}", spec.name, def.name), Json::Object(schema)));
+			}
+
+			packets.push((spec.name.to_owned(), Json::Object(
+				spec.defs.iter()
+					.filter_map(|d| d.code.map(|c| (d.name.to_owned(), Json::Number(c as f64))))
+					.collect()
+			)));
+		}
+
+		let schema = Json::object(vec![
+			("$schema", Json::str("http://json-schema.org/draft-07/schema#")),
+			("definitions", Json::Object(definitions)),
+			("x-packets", Json::Object(packets)),
+		]);
+
+		schema.write(writer, 0)?;
+		writeln!(writer)
+	}
+}
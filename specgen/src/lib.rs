@@ -1,6 +1,18 @@
 
 mod parser;
+mod validate;
 mod generator;
+mod typescript;
+mod jsonschema;
+mod markdown;
+
+#[cfg(test)]
+mod tests;
 
 pub use parser::SpecFieldType as FieldType;
+pub use parser::{parse, Error as ParseError, ErrorType as ParseErrorType, Protocol, Span};
+pub use validate::validate;
 pub use generator::Generator as SerdeBuilder;
+pub use typescript::TypeScriptGenerator as TypeScriptBuilder;
+pub use jsonschema::JsonSchemaGenerator as JsonSchemaBuilder;
+pub use markdown::MarkdownGenerator as MarkdownBuilder;
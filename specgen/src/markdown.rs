@@ -0,0 +1,358 @@
+use parser::*;
+use validate::load;
+use std::io::{Write, Error};
+
+/// Generates a Markdown reference for a spec with
+/// the wire layout of every definition, as well
+/// as the values of all enums.
+///
+/// Links between sections use explicit HTML
+/// anchors so that the output renders the same
+/// on GitHub and within rustdoc.
+pub struct MarkdownGenerator {
+	title: String
+}
+
+// Encoded sizes of the built in types, `None`
+// means that the size depends on the value.
+fn builtin_size(name: &str) -> Option<Option<usize>> {
+	Some(match name {
+		"bool" | "u8" | "i8" => Some(1),
+		"u16" | "i16" => Some(2),
+		"u32" | "i32" | "f32" => Some(4),
+		"f64" => Some(8),
+		"Text" | "TextBig" => None,
+		"Flag" | "Level" | "Health" | "Energy" | "Upgrades" | "ServerKeyState" => Some(1),
+		"Entity" | "Team" | "Rotation" | "Speed" | "HealthRegen" | "EnergyRegen" => Some(2),
+		"LowResPos" => Some(2),
+		"Score" | "Position" | "Velocity" | "Accel" => Some(4),
+		"Position24" => Some(6),
+		"Position_f32" => Some(8),
+		_ => return None
+	})
+}
+
+struct Layout<'a, 'b> {
+	spec: &'a str,
+	types: &'b [Type<'a>],
+	defs: &'b [SpecDefinition<'a>]
+}
+
+impl<'a, 'b> Layout<'a, 'b> {
+	fn def(&self, name: &str) -> Option<&'b SpecDefinition<'a>> {
+		self.defs.iter().find(|d| d.name == name)
+	}
+	fn ty(&self, name: &str) -> Option<&'b Type<'a>> {
+		self.types.iter().find(|t| t.name == name)
+	}
+
+	/// The encoded size of a type, or
+	/// `None` if it isn't fixed.
+	fn size(&self, ty: &SpecFieldType) -> Option<usize> {
+		match *ty {
+			SpecFieldType::Simple(name) => {
+				if let Some(size) = builtin_size(name) {
+					return size;
+				}
+				if let Some(def) = self.def(name) {
+					return def.fields.iter()
+						.fold(Some(0), |acc, f| acc.and_then(|a| self.size(&f.ty).map(|s| a + s)));
+				}
+
+				match self.ty(name).map(|t| &t.class) {
+					Some(&TypeClass::Enum(_, ref base)) => self.size(base),
+					_ => None
+				}
+			},
+			// An id of 0 is used for None
+			SpecFieldType::Compound("Option", ref args) if args[0].to_str() == "Entity" => Some(2),
+			SpecFieldType::Compound(..) => None
+		}
+	}
+
+	/// The smallest number of bytes that
+	/// a type can be encoded in.
+	fn min_size(&self, ty: &SpecFieldType) -> usize {
+		if let Some(size) = self.size(ty) {
+			return size;
+		}
+
+		match *ty {
+			SpecFieldType::Simple("Text") => 1,
+			SpecFieldType::Simple("TextBig") => 2,
+			SpecFieldType::Simple(name) => match self.def(name) {
+				Some(def) => def.fields.iter().map(|f| self.min_size(&f.ty)).sum(),
+				None => 0
+			},
+			SpecFieldType::Compound("Array", _) => 2,
+			SpecFieldType::Compound("ArraySmall", _) => 1,
+			SpecFieldType::Compound(..) => 0
+		}
+	}
+
+	fn size_desc(&self, ty: &SpecFieldType) -> String {
+		if let Some(size) = self.size(ty) {
+			return size.to_string();
+		}
+
+		let prefix = match *ty {
+			SpecFieldType::Simple("Text") => return "1 + len".to_owned(),
+			SpecFieldType::Simple("TextBig") => return "2 + len".to_owned(),
+			SpecFieldType::Compound("Array", _) => 2,
+			SpecFieldType::Compound("ArraySmall", _) => 1,
+			_ => return "varies".to_owned()
+		};
+
+		match *ty {
+			SpecFieldType::Compound(_, ref args) => match self.size(&args[0]) {
+				Some(size) => format!("{} + {}n", prefix, size),
+				None => format!("{} + varies", prefix)
+			},
+			_ => unreachable!()
+		}
+	}
+
+	/// The type in spec syntax, with links to
+	/// the sections of any types it contains.
+	fn type_link(&self, ty: &SpecFieldType) -> String {
+		match *ty {
+			SpecFieldType::Simple(name) => {
+				if self.def(name).is_some() {
+					format!("[{}](#{}-{})", name, self.spec, name)
+				}
+				else if self.ty(name).is_some() {
+					format!("[{}](#type-{})", name, name)
+				}
+				else {
+					format!("`{}`", name)
+				}
+			},
+			SpecFieldType::Compound(name, ref args) => {
+				let args = args.iter().map(|a| self.type_link(a)).collect::<Vec<_>>();
+				format!("{}\\[{}\\]", name, args.join(", "))
+			}
+		}
+	}
+}
+
+// Doc comments keep the space after `#`
+fn doc_lines<'a>(docs: &'a [&'a str]) -> impl Iterator<Item = &'a str> + 'a {
+	docs.iter().map(|d| if d.starts_with(' ') { &d[1..] } else { d })
+}
+
+fn bytes(n: usize) -> String {
+	if n == 1 { "1 byte".to_owned() } else { format!("{} bytes", n) }
+}
+
+// Joins docs into a single line for a table cell
+fn doc_cell(docs: &[&str]) -> String {
+	docs.iter()
+		.map(|d| d.trim())
+		.filter(|d| !d.is_empty())
+		.collect::<Vec<_>>()
+		.join(" ")
+		.replace("|", "\\|")
+}
+
+impl MarkdownGenerator {
+	pub fn new() -> Self {
+		Self {
+			title: "Protocol Reference".to_owned()
+		}
+	}
+
+	pub fn title(mut self, title: &str) -> Self {
+		self.title = title.to_owned();
+		self
+	}
+
+	fn print_docs<W>(writer: &mut W, docs: &[&str]) -> Result<(), Error>
+	where W: Write
+	{
+		if docs.is_empty() {
+			return Ok(());
+		}
+
+		let mut in_code = false;
+
+		for line in doc_lines(docs) {
+			if line.trim_start().starts_with("```") {
+				let lang = line.trim_start()[3..].trim();
+
+				// Rust examples are written as doctests for
+				// the generated code, they'd be duplicated
+				// and can't be run from here.
+				if !in_code && (lang.is_empty() || lang == "rust") {
+					writeln!(writer, "```rust,ignore")?;
+				}
+				else {
+					writeln!(writer, "{}", line)?;
+				}
+
+				in_code = !in_code;
+			}
+			else if in_code {
+				// Skip lines hidden from doctests
+				if line.trim() == "#" || line.trim_start().starts_with("# ") {
+					continue;
+				}
+
+				writeln!(writer, "{}", line)?;
+			}
+			else if line.starts_with('#') {
+				// Headings go below the definition's
+				writeln!(writer, "###{}", line)?;
+			}
+			else {
+				writeln!(writer, "{}", line)?;
+			}
+		}
+		writeln!(writer)
+	}
+
+	fn print_type<W>(writer: &mut W, ty: &Type) -> Result<(), Error>
+	where W: Write
+	{
+		writeln!(writer, "<a id=\"type-{}\"></a>\n", ty.name)?;
+		writeln!(writer, "### {}\n", ty.name)?;
+		Self::print_docs(writer, &ty.docs)?;
+
+		match ty.class {
+			TypeClass::Extern => {
+				let size = match builtin_size(ty.name) {
+					Some(Some(size)) => bytes(size),
+					Some(None) => "varies".to_owned(),
+					None => "defined by the implementation".to_owned()
+				};
+
+				writeln!(writer, "**Size:** {}\n", size)?;
+			},
+			TypeClass::Enum(ref branches, ref base) => {
+				writeln!(writer, "Encoded as `{}`.\n", base.to_str())?;
+
+				if branches.is_empty() {
+					writeln!(writer, "*No known values.*\n")?;
+					return Ok(());
+				}
+
+				writeln!(writer, "| Value | Name | Description |")?;
+				writeln!(writer, "|------:|------|-------------|")?;
+				for branch in branches.iter() {
+					writeln!(writer,
+						"| {} | `{}` | {} |",
+						branch.value, branch.name, doc_cell(&branch.docs)
+					)?;
+				}
+				writeln!(writer)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn print_def<W>(writer: &mut W, layout: &Layout, def: &SpecDefinition) -> Result<(), Error>
+	where W: Write
+	{
+		writeln!(writer, "<a id=\"{}-{}\"></a>\n", layout.spec, def.name)?;
+		match def.code {
+			Some(code) => writeln!(writer, "### {} ({})\n", def.name, code)?,
+			None => writeln!(writer, "### {}\n", def.name)?
+		}
+		Self::print_docs(writer, &def.docs)?;
+
+		if def.code.is_none() {
+			writeln!(writer, "*Only sent as part of other packets.*\n")?;
+		}
+
+		writeln!(writer, "| Offset | Field | Type | Size | Description |")?;
+		writeln!(writer, "|-------:|-------|------|-----:|-------------|")?;
+
+		let mut offset = Some(0);
+		let mut min = 0;
+
+		if let Some(code) = def.code {
+			writeln!(writer, "| 0 | *code* | `u8` | 1 | Always `{}` |", code)?;
+			offset = Some(1);
+			min = 1;
+		}
+
+		for field in def.fields.iter() {
+			writeln!(writer,
+				"| {} | `{}` | {} | {} | {} |",
+				offset.map(|o| o.to_string()).unwrap_or("-".to_owned()),
+				field.name,
+				layout.type_link(&field.ty),
+				layout.size_desc(&field.ty),
+				doc_cell(&field.docs)
+			)?;
+
+			let size = layout.size(&field.ty);
+			offset = offset.and_then(|o| size.map(|s| o + s));
+			min += layout.min_size(&field.ty);
+		}
+
+		match offset {
+			Some(size) => writeln!(writer, "\n**Size:** {}\n", bytes(size))?,
+			None => writeln!(writer, "\n**Size:** at least {}\n", bytes(min))?
+		}
+
+		Ok(())
+	}
+
+	pub fn build<W>(self, specdata: &[u8], writer: &mut W) -> Result<(), Error>
+	where W: Write
+	{
+		let Protocol{ specs, types } = load(specdata)?;
+
+		writeln!(writer, "# {}\n", self.title)?;
+		writeln!(writer,
+			"All values are little-endian. Sizes are in bytes, \
+			`n` is the number of elements in an array and \
+			`len` is the length of a string in bytes.\n"
+		)?;
+
+		writeln!(writer, "- [Types](#types)")?;
+		for spec in specs.iter() {
+			writeln!(writer, "- [`{name}`](#spec-{name})", name=spec.name)?;
+		}
+		writeln!(writer)?;
+
+		writeln!(writer, "<a id=\"types\"></a>\n")?;
+		writeln!(writer, "## Types\n")?;
+		for ty in types.iter() {
+			Self::print_type(writer, ty)?;
+		}
+
+		for spec in specs.iter() {
+			let layout = Layout {
+				spec: spec.name,
+				types: &types,
+				defs: &spec.defs
+			};
+
+			writeln!(writer, "<a id=\"spec-{}\"></a>\n", spec.name)?;
+			writeln!(writer, "## `{}`\n", spec.name)?;
+			Self::print_docs(writer, &spec.docs)?;
+
+			let mut packets = spec.defs.iter()
+				.filter_map(|d| d.code.map(|c| (c, d.name)))
+				.collect::<Vec<_>>();
+			packets.sort();
+
+			if !packets.is_empty() {
+				writeln!(writer, "| Code | Packet |")?;
+				writeln!(writer, "|-----:|--------|")?;
+				for (code, name) in packets {
+					writeln!(writer, "| {} | [{name}](#{}-{name}) |", code, spec.name, name=name)?;
+				}
+				writeln!(writer)?;
+			}
+
+			for def in spec.defs.iter() {
+				Self::print_def(writer, &layout, def)?;
+			}
+		}
+
+		Ok(())
+	}
+}
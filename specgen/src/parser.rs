@@ -1,4 +1,5 @@
 
+use std::fmt;
 use std::str::{self, Utf8Error};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -15,10 +16,36 @@ pub enum TokenData<'a> {
 	DocComment(&'a str),
 }
 
+/// A location within the spec file. Lines and
+/// columns both start at 1, `len` is the number
+/// of characters covered on that line.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+	pub line: u32,
+	pub col:  u32,
+	pub len:  u32
+}
+
+impl Span {
+	pub fn new(line: u32, col: u32, len: u32) -> Self {
+		Self { line, col, len }
+	}
+
+	/// Extends this span to cover up to the end
+	/// of `other`, if both are on the same line.
+	fn to(self, other: Span) -> Span {
+		if other.line != self.line || other.col < self.col {
+			return self;
+		}
+
+		Span::new(self.line, self.col, other.col + other.len - self.col)
+	}
+}
+
 #[derive(Copy, Clone, Debug)]
 struct Token<'a> {
 	pub ty: TokenData<'a>,
-	pub line: u32
+	pub span: Span
 }
 
 #[derive(Clone, Debug)]
@@ -43,14 +70,21 @@ impl<'a> SpecFieldType<'a> {
 pub struct SpecField<'a> {
 	pub name: &'a str,
 	pub ty:   SpecFieldType<'a>,
-	pub docs: Vec<&'a str>
+	pub docs: Vec<&'a str>,
+	pub span: Span,
+	/// Covers the whole type of the field.
+	pub ty_span: Span
 }
 
 #[derive(Clone, Default, Debug)]
 pub struct SpecDefinition<'a> {
 	pub name: &'a str,
 	pub docs: Vec<&'a str>,
-	pub fields: Vec<SpecField<'a>>
+	/// The packet code, only definitions that
+	/// are sent as packets on their own have one.
+	pub code: Option<u32>,
+	pub fields: Vec<SpecField<'a>>,
+	pub span: Span
 }
 
 #[derive(Clone, Debug, Default)]
@@ -58,18 +92,22 @@ pub struct Spec<'a> {
 	pub name: &'a str,
 	pub docs: Vec<&'a str>,
 	pub defs: Vec<SpecDefinition<'a>>,
+	pub span: Span
 }
 
 #[derive(Clone, Debug)]
 pub struct EnumBranch<'a> {
 	pub name:  &'a str,
 	pub docs:  Vec<&'a str>,
-	pub value: u32
+	pub value: u32,
+	pub span:  Span
 }
 
 #[derive(Clone, Debug)]
 pub enum TypeClass<'a> {
-	//Extern,
+	/// A type that is encoded by the backend,
+	/// the spec only declares that it exists.
+	Extern,
 	//TypeDef(Box<SpecFieldType<'a>>),
 	Enum(Vec<EnumBranch<'a>>, SpecFieldType<'a>)
 }
@@ -78,7 +116,8 @@ pub enum TypeClass<'a> {
 pub struct Type<'a> {
 	pub name: &'a str,
 	pub docs: Vec<&'a str>,
-	pub class: TypeClass<'a>
+	pub class: TypeClass<'a>,
+	pub span: Span
 }
 
 #[derive(Clone, Debug, Default)]
@@ -95,22 +134,171 @@ pub enum ErrorType<'a> {
 	Utf8Error(Utf8Error),
 	ExpectingIdent(TokenData<'a>),
 	ExpectingKeyword(&'static str, TokenData<'a>),
-	ExpectingRBracket(TokenData<'a>),
+	ExpectingLBracket(TokenData<'a>),
 	ExpectingColon(TokenData<'a>),
 	ExpectingComma(TokenData<'a>),
 	ExpectingEquals(TokenData<'a>),
-	InvalidIntegerLiteral(&'a str)
+	ExpectingInteger(TokenData<'a>),
+	InvalidIntegerLiteral(&'a str),
+	NestingTooDeep,
+
+	// Errors found by validation after parsing,
+	// any span in these points to the earlier
+	// conflicting definition.
+	DuplicateName(&'a str, Span),
+	DuplicatePacketCode(u32, &'a str, Span),
+	PacketCodeOutOfRange(u32),
+	DuplicateDiscriminant(u32, &'a str, Span),
+	DiscriminantOutOfRange(u32, &'a str),
+	InvalidEnumBase(&'a str),
+	UnknownType(&'a str),
+	WrongArgumentCount(&'a str, usize, usize),
+
+	/// Only a warning, the generated enum
+	/// can never be constructed.
+	EmptyEnum(&'a str)
 }
 
 #[derive(Copy, Clone, Debug)]
 pub struct Error<'a> {
 	pub ty: ErrorType<'a>,
-	pub line: u32
+	pub span: Span
 }
 
 impl<'a> Error<'a> {
-	pub fn new(ty: ErrorType<'a>, line: u32) -> Self {
-		Self { ty, line }
+	pub fn new(ty: ErrorType<'a>, span: Span) -> Self {
+		Self { ty, span }
+	}
+
+	/// Warnings don't stop code from
+	/// being generated.
+	pub fn is_warning(&self) -> bool {
+		match self.ty {
+			ErrorType::EmptyEnum(_) => true,
+			_ => false
+		}
+	}
+
+	/// Formats the error along with the line of
+	/// `source` that it points to, e.g.
+	///
+	/// ```text
+	/// error: unknown type `Foo`
+	///  --> airmash.prtcl:12:9
+	///    |
+	/// 12 | 		id: Foo,
+	///    | 		    ^^^
+	/// ```
+	pub fn render(&self, path: &str, source: &[u8]) -> String {
+		let Span { line, col, len } = self.span;
+		let kind = if self.is_warning() { "warning" } else { "error" };
+		let mut out = format!("{}: {}\n", kind, self.ty);
+		let gutter = line.to_string().len();
+
+		out += &format!("{:w$}--> {}:{}:{}\n", "", path, line, col, w = gutter);
+
+		let text = source
+			.split(|&c| c == b'\n')
+			.nth((line as usize).wrapping_sub(1))
+			.map(|l| String::from_utf8_lossy(l).trim_end().to_string());
+
+		if let Some(text) = text {
+			// Keep tabs so the marker lines up
+			let indent = text.chars()
+				.take((col as usize).saturating_sub(1))
+				.map(|c| if c == '\t' { '\t' } else { ' ' })
+				.collect::<String>();
+
+			out += &format!("{:w$} |\n", "", w = gutter);
+			out += &format!("{} | {}\n", line, text);
+			out += &format!(
+				"{:w$} | {}{}\n",
+				"", indent, "^".repeat(len.max(1) as usize),
+				w = gutter
+			);
+		}
+
+		out
+	}
+}
+
+impl<'a> fmt::Display for TokenData<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			TokenData::Ident(s) => write!(f, "`{}`", s),
+			TokenData::LBracket => write!(f, "`{{`"),
+			TokenData::RBracket => write!(f, "`}}`"),
+			TokenData::Colon => write!(f, "`:`"),
+			TokenData::LBrace => write!(f, "`[`"),
+			TokenData::RBrace => write!(f, "`]`"),
+			TokenData::Comma => write!(f, "`,`"),
+			TokenData::Equals => write!(f, "`=`"),
+			TokenData::Integer(n) => write!(f, "`{}`", n),
+			TokenData::DocComment(_) => write!(f, "a comment")
+		}
+	}
+}
+
+impl<'a> fmt::Display for ErrorType<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use self::ErrorType::*;
+
+		match *self {
+			UnexpectedEOF => write!(f, "unexpected end of file"),
+			NonAsciiCodepoint(b) => write!(f, "non-ascii byte 0x{:02X}", b),
+			UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+			Utf8Error(ref e) => write!(f, "{}", e),
+			ExpectingIdent(t) => write!(f, "expected a name, found {}", t),
+			ExpectingKeyword(kw, t) => write!(f, "expected `{}`, found {}", kw, t),
+			ExpectingLBracket(t) => write!(f, "expected `{{`, found {}", t),
+			ExpectingColon(t) => write!(f, "expected `:`, found {}", t),
+			ExpectingComma(t) => write!(f, "expected `,`, found {}", t),
+			ExpectingEquals(t) => write!(f, "expected `=`, found {}", t),
+			ExpectingInteger(t) => write!(f, "expected an integer, found {}", t),
+			InvalidIntegerLiteral(s) => write!(f, "invalid integer literal `{}`", s),
+			NestingTooDeep => write!(f, "type is nested too deeply"),
+			// Built in types don't have a line
+			DuplicateName(name, Span { line: 0, .. }) => write!(
+				f, "`{}` is already defined",
+				name
+			),
+			DuplicateName(name, first) => write!(
+				f, "`{}` is already defined on line {}",
+				name, first.line
+			),
+			DuplicatePacketCode(code, name, first) => write!(
+				f, "packet code {} is already used by `{}` on line {}",
+				code, name, first.line
+			),
+			PacketCodeOutOfRange(code) => write!(
+				f, "packet code {} does not fit in a u8",
+				code
+			),
+			DuplicateDiscriminant(value, name, first) => write!(
+				f, "value {} is already used by `{}` on line {}",
+				value, name, first.line
+			),
+			DiscriminantOutOfRange(value, base) => write!(
+				f, "value {} does not fit in a {}",
+				value, base
+			),
+			InvalidEnumBase(ty) => write!(
+				f, "enums must be based on an integer type, found `{}`",
+				ty
+			),
+			UnknownType(ty) => write!(f, "unknown type `{}`", ty),
+			WrongArgumentCount(ty, expected, found) => write!(
+				f, "`{}` takes {} type argument(s) but {} were given",
+				ty, expected, found
+			),
+			EmptyEnum(name) => write!(f, "enum `{}` has no variants", name)
+		}
+	}
+}
+
+impl<'a> fmt::Display for Error<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}: {}", self.span.line, self.span.col, self.ty)
 	}
 }
 
@@ -123,26 +311,32 @@ impl<'a> Tokens<'a> {
 	fn tokenize(b: &'a [u8]) -> Result<Vec<Token<'a>>, Error<'a>> {
 		let mut i = 0;
 		let mut line = 1;
+		let mut line_start = 0;
 		let mut result = vec![];
 
 		while i < b.len() {
+			let col = (i - line_start) as u32 + 1;
+			let at = |len: usize| Span::new(line, col, len as u32);
+			let at_byte = |idx: usize| Span::new(line, (idx - line_start) as u32 + 1, 1);
+
 			if b[i] > 127 {
-				return Err(Error::new(ErrorType::NonAsciiCodepoint(b[i]), line))
+				return Err(Error::new(ErrorType::NonAsciiCodepoint(b[i]), at(1)))
 			}
 
 			let data = match b[i] as char {
 				'\n' => {
 					line += 1;
 					i += 1;
+					line_start = i;
 					continue;
 				},
-				':' => Token{ line, ty: TokenData::Colon },
-				',' => Token{ line, ty: TokenData::Comma },
-				'{' => Token{ line, ty: TokenData::LBracket },
-				'}' => Token{ line, ty: TokenData::RBracket },
-				'[' => Token{ line, ty: TokenData::LBrace },
-				']' => Token{ line, ty: TokenData::RBrace },
-				'=' => Token{ line, ty: TokenData::Equals },
+				':' => Token{ span: at(1), ty: TokenData::Colon },
+				',' => Token{ span: at(1), ty: TokenData::Comma },
+				'{' => Token{ span: at(1), ty: TokenData::LBracket },
+				'}' => Token{ span: at(1), ty: TokenData::RBracket },
+				'[' => Token{ span: at(1), ty: TokenData::LBrace },
+				']' => Token{ span: at(1), ty: TokenData::RBrace },
+				'=' => Token{ span: at(1), ty: TokenData::Equals },
 				'a'...'z' | 'A'...'Z' => {
 					let mut len = 1;
 
@@ -152,7 +346,7 @@ impl<'a> Tokens<'a> {
 						}
 						if b[i + len] > 127 {
 							return Err(Error::new(
-								ErrorType::NonAsciiCodepoint(b[i+len]), line
+								ErrorType::NonAsciiCodepoint(b[i+len]), at_byte(i + len)
 							));
 						}
 
@@ -168,21 +362,26 @@ impl<'a> Tokens<'a> {
 						Ok(s) => s,
 						Err(e) => return Err(Error::new(
 							ErrorType::Utf8Error(e), 
-							line
+							at(len)
 						))
 					};
 
+					let span = at(len);
 					i += len - 1;
 
-					Token {	ty: TokenData::Ident(s), line }
+					Token {	ty: TokenData::Ident(s), span }
 				},
 				'#' => {
 					let mut len = 0;
 					
-					loop {						
+					loop {
+						// Comments may run up to the end of the file
+						if i + len == b.len() {
+							break;
+						}
 						if b[i + len] > 127 {
 							return Err(Error::new(
-								ErrorType::NonAsciiCodepoint(b[i + len]), line
+								ErrorType::NonAsciiCodepoint(b[i + len]), at_byte(i + len)
 							));
 						}
 
@@ -196,15 +395,18 @@ impl<'a> Tokens<'a> {
 					let s = match str::from_utf8(&b[i+1..i+len]) {
 						Ok(s) => s,
 						Err(e) => return Err(Error::new(
-							ErrorType::Utf8Error(e), line
+							ErrorType::Utf8Error(e), at(len)
 						))
 					};
 
-					i += len;
+					let span = at(len);
+					// Leave the newline for the next
+					// iteration so that it is counted.
+					i += len - 1;
 
 					Token {
 						ty: TokenData::DocComment(s),
-						line
+						span
 					}
 				},
 				'\r' | ' ' | '\t' => {
@@ -219,7 +421,9 @@ impl<'a> Tokens<'a> {
 							break;
 						}
 						if b[i + len] > 127 {
-							return Err(Error::new(ErrorType::NonAsciiCodepoint(b[i+1]), line));
+							return Err(Error::new(
+								ErrorType::NonAsciiCodepoint(b[i+len]), at_byte(i + len)
+							));
 						}
 
 						match b[i + len] as char {
@@ -229,13 +433,13 @@ impl<'a> Tokens<'a> {
 									Ok(s) => s,
 									Err(e) => return Err(Error::new(
 										ErrorType::Utf8Error(e), 
-										line
+										at(len)
 									))
 								};
 								
 								return Err(Error::new(
 									ErrorType::InvalidIntegerLiteral(s),
-									line
+									at(len + 1)
 								));
 							},
 							_ => break
@@ -246,7 +450,7 @@ impl<'a> Tokens<'a> {
 						Ok(s) => s,
 						Err(e) => return Err(Error::new(
 							ErrorType::Utf8Error(e), 
-							line
+							at(len)
 						))
 					};
 
@@ -254,15 +458,16 @@ impl<'a> Tokens<'a> {
 						Ok(n) => n,
 						Err(_) => return Err(Error::new(
 							ErrorType::InvalidIntegerLiteral(s),
-							line
+							at(len)
 						))
 					};
 
+					let span = at(len);
 					i += len-1;
 
-					Token{ ty: TokenData::Integer(num), line }
+					Token{ ty: TokenData::Integer(num), span }
 				}
-				_ => return Err(Error::new(ErrorType::UnexpectedChar(b[i] as char), line))
+				_ => return Err(Error::new(ErrorType::UnexpectedChar(b[i] as char), at(1)))
 			};
 
 			result.push(data);
@@ -282,13 +487,21 @@ impl<'a> Tokens<'a> {
 
 	pub fn current(&self) -> Result<Token<'a>, Error<'a>> {
 		if self.tokens.len() <= self.index {
-			return Err(Error{ 
-				ty: ErrorType::UnexpectedEOF, 
-				line: self.tokens.last().map(|x| x.line).unwrap_or(0)
-			});
+			// Point just past the last token
+			let span = self.tokens.last()
+				.map(|x| Span::new(x.span.line, x.span.col + x.span.len, 1))
+				.unwrap_or(Span::new(1, 1, 1));
+
+			return Err(Error::new(ErrorType::UnexpectedEOF, span));
 		}
 		Ok(self.tokens[self.index])
 	}
+	/// The span of the token that was
+	/// most recently stepped over.
+	pub fn previous(&self) -> Span {
+		assert!(self.index > 0);
+		self.tokens[self.index - 1].span
+	}
 	pub fn step(&mut self) {
 		self.index += 1;
 	}
@@ -303,7 +516,7 @@ impl<'a> Tokens<'a> {
 }
 
 fn parse_kw<'a>(tokens: &mut Tokens<'a>, kw: &'static str) -> Result<(), Error<'a>> {
-	let Token { ty, line } = tokens.current()?;
+	let Token { ty, span } = tokens.current()?;
 
 	if let TokenData::Ident(s) = ty {
 		if s == kw {
@@ -314,7 +527,7 @@ fn parse_kw<'a>(tokens: &mut Tokens<'a>, kw: &'static str) -> Result<(), Error<'
 
 	Err(Error::new(
 		ErrorType::ExpectingKeyword(kw, ty),
-		line
+		span
 	))
 }
 
@@ -323,12 +536,12 @@ fn parse_spec_kw<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
 }
 
 fn parse_lbracket<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
-	let Token { ty, line } = tokens.current()?;
+	let Token { ty, span } = tokens.current()?;
 
 	if ty != TokenData::LBracket {
 		return Err(Error::new(
-			ErrorType::ExpectingRBracket(ty),
-			line
+			ErrorType::ExpectingLBracket(ty),
+			span
 		))
 	}
 
@@ -336,12 +549,12 @@ fn parse_lbracket<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
 	Ok(())
 }
 fn parse_comma<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
-		let Token { ty, line } = tokens.current()?;
+		let Token { ty, span } = tokens.current()?;
 
 	if ty != TokenData::Comma {
 		return Err(Error::new(
 			ErrorType::ExpectingComma(ty),
-			line
+			span
 		))
 	}
 
@@ -349,12 +562,12 @@ fn parse_comma<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
 	Ok(())
 }
 fn parse_colon<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
-		let Token { ty, line } = tokens.current()?;
+		let Token { ty, span } = tokens.current()?;
 
 	if ty != TokenData::Colon {
 		return Err(Error::new(
 			ErrorType::ExpectingColon(ty),
-			line
+			span
 		))
 	}
 
@@ -362,12 +575,12 @@ fn parse_colon<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
 	Ok(())
 }
 fn parse_equals<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
-		let Token { ty, line } = tokens.current()?;
+		let Token { ty, span } = tokens.current()?;
 
 	if ty != TokenData::Equals {
 		return Err(Error::new(
 			ErrorType::ExpectingEquals(ty),
-			line
+			span
 		))
 	}
 
@@ -375,7 +588,25 @@ fn parse_equals<'a>(tokens: &mut Tokens<'a>) -> Result<(), Error<'a>> {
 	Ok(())
 }
 
+// Compound types are parsed recursively, this
+// stops something like `A[A[A[...` from
+// overflowing the stack.
+const MAX_TYPE_DEPTH: u32 = 32;
+
 fn parse_field_type<'a>(tokens: &mut Tokens<'a>) -> Result<SpecFieldType<'a>, Error<'a>> {
+	parse_field_type_nested(tokens, 0)
+}
+
+fn parse_field_type_nested<'a>(
+	tokens: &mut Tokens<'a>,
+	depth: u32
+) -> Result<SpecFieldType<'a>, Error<'a>> {
+	let Token { span, .. } = tokens.current()?;
+	
+	if depth > MAX_TYPE_DEPTH {
+		return Err(Error::new(ErrorType::NestingTooDeep, span));
+	}
+
 	let name = parse_ident(tokens)?;
 
 	if tokens.current()?.ty == TokenData::LBrace {
@@ -384,7 +615,7 @@ fn parse_field_type<'a>(tokens: &mut Tokens<'a>) -> Result<SpecFieldType<'a>, Er
 		let mut subtypes = vec![];
 
 		while tokens.current()?.ty != TokenData::RBrace {
-			subtypes.push(parse_field_type(tokens)?);
+			subtypes.push(parse_field_type_nested(tokens, depth + 1)?);
 
 			// Comma not required for last element
 			if tokens.current()?.ty == TokenData::RBrace {}
@@ -401,32 +632,32 @@ fn parse_field_type<'a>(tokens: &mut Tokens<'a>) -> Result<SpecFieldType<'a>, Er
 }
 
 fn parse_ident<'a>(tokens: &mut Tokens<'a>) -> Result<&'a str, Error<'a>> {
-	let Token{ ty, line } = tokens.current()?;
+	let Token{ ty, span } = tokens.current()?;
 
 	if let TokenData::Ident(s) = ty {
 		tokens.step();
 		Ok(s)
 	}
 	else {
-		Err(Error::new(ErrorType::ExpectingIdent(ty), line))
+		Err(Error::new(ErrorType::ExpectingIdent(ty), span))
 	}
 }
 fn parse_num<'a>(tokens: &mut Tokens<'a>) -> Result<u32, Error<'a>> {
-	let Token{ ty, line } = tokens.current()?;
+	let Token{ ty, span } = tokens.current()?;
 
 	if let TokenData::Integer(n) = ty {
 		tokens.step();
 		Ok(n)
 	}
 	else {
-		Err(Error::new(ErrorType::ExpectingIdent(ty), line))
+		Err(Error::new(ErrorType::ExpectingInteger(ty), span))
 	}
 }
 
 fn parse_docs<'a>(tokens: &mut Tokens<'a>) -> Result<Vec<&'a str>, Error<'a>> {
 	let mut docs = vec![];
 	
-	while let Token{ ty: TokenData::DocComment(doc), .. } = tokens.current()? {
+	while let Ok(Token{ ty: TokenData::DocComment(doc), .. }) = tokens.current() {
 		docs.push(doc);
 		tokens.step();
 	}
@@ -437,13 +668,16 @@ fn parse_docs<'a>(tokens: &mut Tokens<'a>) -> Result<Vec<&'a str>, Error<'a>> {
 fn parse_field<'a>(tokens: &mut Tokens<'a>) -> Result<SpecField<'a>, Error<'a>> {
 	let docs = parse_docs(tokens)?;
 	let name = parse_ident(tokens)?;
+	let span = tokens.previous();
 	parse_colon(tokens)?;
+	let ty_start = tokens.current()?.span;
 	let ty = parse_field_type(tokens)?;
+	let ty_span = ty_start.to(tokens.previous());
 
 	if tokens.current()?.ty == TokenData::RBracket {}
 	else { parse_comma(tokens)?; }
 
-	Ok(SpecField { name, ty, docs })
+	Ok(SpecField { name, ty, docs, span, ty_span })
 }
 
 fn parse_def<'a>(tokens: &mut Tokens<'a>) -> Result<SpecDefinition<'a>, Error<'a>> {
@@ -451,6 +685,14 @@ fn parse_def<'a>(tokens: &mut Tokens<'a>) -> Result<SpecDefinition<'a>, Error<'a
 
 	def.docs = parse_docs(tokens)?;
 	def.name = parse_ident(tokens)?;
+	def.span = tokens.previous();
+
+	if tokens.current()?.ty == TokenData::Equals {
+		tokens.step();
+		def.code = Some(parse_num(tokens)?);
+		def.span = def.span.to(tokens.previous());
+	}
+
 	parse_lbracket(tokens)?;
 
 	while tokens.current()?.ty != TokenData::RBracket {
@@ -467,6 +709,7 @@ fn parse_spec<'a>(tokens: &mut Tokens<'a>, docs: Vec<&'a str>) -> Result<Spec<'a
 
 	parse_spec_kw(tokens)?;
 	let name = parse_ident(tokens)?;
+	let span = tokens.previous();
 	parse_lbracket(tokens)?;
 
 	while tokens.current()?.ty != TokenData::RBracket {
@@ -477,20 +720,23 @@ fn parse_spec<'a>(tokens: &mut Tokens<'a>, docs: Vec<&'a str>) -> Result<Spec<'a
 	Ok(Spec {
 		defs,
 		name,
-		docs
+		docs,
+		span
 	})
 }
 
 fn parse_enum_branch<'a>(tokens: &mut Tokens<'a>) -> Result<EnumBranch<'a>, Error<'a>> {
 	let docs = parse_docs(tokens)?;
 	let name = parse_ident(tokens)?;
+	let start = tokens.previous();
 	parse_equals(tokens)?;
 	let value = parse_num(tokens)?;
 
 	Ok(EnumBranch {
 		docs,
 		name,
-		value
+		value,
+		span: start.to(tokens.previous())
 	})
 }
 
@@ -498,6 +744,7 @@ fn parse_enum<'a>(tokens: &mut Tokens<'a>, docs: Vec<&'a str>) -> Result<Type<'a
 	parse_kw(tokens, "enum")?;
 
 	let name = parse_ident(tokens)?;
+	let span = tokens.previous();
 	parse_colon(tokens)?;
 	let ty = parse_field_type(tokens)?;
 	let mut branches = vec![];
@@ -517,10 +764,27 @@ fn parse_enum<'a>(tokens: &mut Tokens<'a>, docs: Vec<&'a str>) -> Result<Type<'a
 	Ok(Type {
 		name,
 		docs,
-		class: TypeClass::Enum(branches, ty)
+		class: TypeClass::Enum(branches, ty),
+		span
 	})
 }
 
+fn parse_extern<'a>(tokens: &mut Tokens<'a>, docs: Vec<&'a str>) -> Result<Type<'a>, Error<'a>> {
+	parse_kw(tokens, "extern")?;
+
+	let name = parse_ident(tokens)?;
+
+	Ok(Type {
+		name,
+		docs,
+		class: TypeClass::Extern,
+		span: tokens.previous()
+	})
+}
+
+/// Parses a spec file. This only checks the
+/// syntax, use [`validate`](fn.validate.html)
+/// to check that the result makes sense.
 pub fn parse<'a>(input: &'a [u8]) -> Result<Protocol<'a>, Error<'a>> {
 	let mut tokens = Tokens::new(input)?;
 	let mut result = Protocol::default();
@@ -528,6 +792,11 @@ pub fn parse<'a>(input: &'a [u8]) -> Result<Protocol<'a>, Error<'a>> {
 	while !tokens.empty() {
 		let docs = parse_docs(&mut tokens)?;
 
+		// Comments at the end of the file
+		if tokens.empty() {
+			break;
+		}
+
 		if let Ok(_) = parse_kw(&mut tokens, "spec") {
 			tokens.rollback();
 			result.specs.push(parse_spec(&mut tokens, docs)?);
@@ -536,12 +805,16 @@ pub fn parse<'a>(input: &'a [u8]) -> Result<Protocol<'a>, Error<'a>> {
 			tokens.rollback();
 			result.types.push(parse_enum(&mut tokens, docs)?);
 		}
+		else if let Ok(_) = parse_kw(&mut tokens, "extern") {
+			tokens.rollback();
+			result.types.push(parse_extern(&mut tokens, docs)?);
+		}
 		else {
-			let Token{ line, ty } = tokens.current()?;
+			let Token{ span, ty } = tokens.current()?;
 
 			return Err(Error::new(
-				ErrorType::ExpectingKeyword("spec or enum", ty),
-				line
+				ErrorType::ExpectingKeyword("spec, enum or extern", ty),
+				span
 			));
 		}
 	}
@@ -0,0 +1,399 @@
+use parser::*;
+use validate::validate;
+use {JsonSchemaBuilder, MarkdownBuilder, SerdeBuilder, TypeScriptBuilder};
+
+const SPEC: &'static str = "
+# A counter
+extern Counter
+
+enum Kind : u8 {
+	# The first kind
+	A = 1,
+	B = 2
+}
+
+spec server {
+	Hello = 3 {
+		# Who to greet
+		name: u8,
+		kind: Kind,
+		items: Array[Item]
+	}
+	Empty = 4 {}
+	Item {
+		count: Counter,
+		other: Option[u16]
+	}
+}
+";
+
+fn errors(spec: &str) -> Vec<Error> {
+	validate(&parse(spec.as_bytes()).unwrap())
+}
+
+fn parse_error(spec: &str) -> Error {
+	parse(spec.as_bytes()).unwrap_err()
+}
+
+fn generate(builder: SerdeBuilder, spec: &str) -> String {
+	let mut out = vec![];
+	builder.build(spec.as_bytes(), &mut out).unwrap();
+	String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn parses_definitions() {
+	let Protocol { specs, types } = parse(SPEC.as_bytes()).unwrap();
+
+	assert_eq!(types.len(), 2);
+	assert_eq!(types[0].name, "Counter");
+	assert_eq!(types[0].docs, vec![" A counter"]);
+	assert_eq!(types[0].span, Span::new(3, 8, 7));
+
+	match types[1].class {
+		TypeClass::Enum(ref branches, ref base) => {
+			assert_eq!(base.to_str(), "u8");
+			assert_eq!(branches.len(), 2);
+			assert_eq!(branches[0].name, "A");
+			assert_eq!(branches[0].value, 1);
+			assert_eq!(branches[0].docs, vec![" The first kind"]);
+			assert_eq!(branches[0].span, Span::new(7, 2, 5));
+		}
+		_ => panic!("Kind should be an enum"),
+	}
+
+	assert_eq!(specs.len(), 1);
+	assert_eq!(specs[0].name, "server");
+
+	let defs = &specs[0].defs;
+	assert_eq!(defs.len(), 3);
+	assert_eq!(defs[0].code, Some(3));
+	assert_eq!(defs[0].span, Span::new(12, 2, 9));
+	assert_eq!(defs[1].code, Some(4));
+	assert!(defs[1].fields.is_empty());
+	assert_eq!(defs[2].code, None);
+
+	let fields = &defs[0].fields;
+	assert_eq!(fields[0].docs, vec![" Who to greet"]);
+	assert_eq!(fields[2].name, "items");
+	assert_eq!(fields[2].ty.to_str(), "Array<Item>");
+	assert_eq!(fields[2].ty_span, Span::new(16, 10, 11));
+}
+
+#[test]
+fn lines_are_counted_after_comments() {
+	let Protocol { types, .. } = parse(b"# one\n# two\nextern A\n").unwrap();
+
+	assert_eq!(types[0].span, Span::new(3, 8, 1));
+}
+
+#[test]
+fn comment_at_end_of_file() {
+	let Protocol { types, .. } = parse(b"extern A\n# trailing").unwrap();
+
+	assert_eq!(types.len(), 1);
+}
+
+#[test]
+fn syntax_error_location() {
+	let e = parse_error("spec a {\n\tFoo {\n\t\tx u8\n\t}\n}");
+
+	match e.ty {
+		ErrorType::ExpectingColon(TokenData::Ident("u8")) => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+	assert_eq!(e.span, Span::new(3, 5, 2));
+}
+
+#[test]
+fn unexpected_eof() {
+	let e = parse_error("spec a {\n\tFoo {");
+
+	match e.ty {
+		ErrorType::UnexpectedEOF => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+	assert_eq!(e.span, Span::new(2, 7, 1));
+}
+
+#[test]
+fn packet_code_must_be_integer() {
+	match parse_error("spec a { Foo = Bar {} }").ty {
+		ErrorType::ExpectingInteger(TokenData::Ident("Bar")) => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+}
+
+#[test]
+fn deeply_nested_types() {
+	let spec = format!("spec a {{ Foo {{ x: {}", "Array[".repeat(64));
+	let e = parse_error(&spec);
+
+	match e.ty {
+		ErrorType::NestingTooDeep => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+}
+
+#[test]
+fn valid_spec() {
+	assert!(errors(SPEC).is_empty());
+}
+
+#[test]
+fn protocol_spec_is_valid() {
+	let spec = include_str!("../../protocol/airmash.prtcl");
+	let errors = errors(spec);
+
+	assert!(
+		errors.iter().all(|e| e.is_warning()),
+		"{:?}",
+		errors
+	);
+}
+
+#[test]
+fn duplicate_packet_code() {
+	let errors = errors("spec a {\n\tFoo = 1 {}\n\tBar = 1 {}\n}");
+
+	assert_eq!(errors.len(), 1);
+	match errors[0].ty {
+		ErrorType::DuplicatePacketCode(1, "Foo", first) => {
+			assert_eq!(first, Span::new(2, 2, 7))
+		}
+		ty => panic!("unexpected error {:?}", ty),
+	}
+	assert_eq!(errors[0].span, Span::new(3, 2, 7));
+}
+
+#[test]
+fn same_code_in_different_specs() {
+	assert!(errors("spec a { Foo = 1 {} }\nspec b { Foo = 1 {} }").is_empty());
+}
+
+#[test]
+fn packet_code_out_of_range() {
+	match errors("spec a { Foo = 256 {} }")[0].ty {
+		ErrorType::PacketCodeOutOfRange(256) => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+}
+
+#[test]
+fn duplicate_discriminant() {
+	let errors = errors("enum E : u8 {\n\tA = 1,\n\tB = 1\n}");
+
+	assert_eq!(errors.len(), 1);
+	match errors[0].ty {
+		ErrorType::DuplicateDiscriminant(1, "A", _) => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+	assert_eq!(errors[0].span, Span::new(3, 2, 5));
+}
+
+#[test]
+fn discriminant_out_of_range() {
+	match errors("enum E : u8 { A = 256 }")[0].ty {
+		ErrorType::DiscriminantOutOfRange(256, "u8") => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+}
+
+#[test]
+fn enum_base_must_be_integer() {
+	match errors("enum E : f32 { A = 1 }")[0].ty {
+		ErrorType::InvalidEnumBase("f32") => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+}
+
+#[test]
+fn empty_enum_is_a_warning() {
+	let errors = errors("enum E : u8 {}");
+
+	assert_eq!(errors.len(), 1);
+	assert!(errors[0].is_warning());
+	match errors[0].ty {
+		ErrorType::EmptyEnum("E") => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+}
+
+#[test]
+fn unknown_types() {
+	let errors = errors("spec a { Foo { x: Bar, y: Array[Baz], z: Vec[u8] } }");
+	let names = errors
+		.iter()
+		.map(|e| match e.ty {
+			ErrorType::UnknownType(name) => name,
+			ty => panic!("unexpected error {:?}", ty),
+		})
+		.collect::<Vec<_>>();
+
+	assert_eq!(names, vec!["Bar", "Baz", "Vec"]);
+}
+
+#[test]
+fn definitions_are_local_to_their_spec() {
+	let errors = errors("spec a { Foo {} }\nspec b { Bar { x: Foo } }");
+
+	match errors[0].ty {
+		ErrorType::UnknownType("Foo") => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+}
+
+#[test]
+fn wrong_argument_count() {
+	match errors("spec a { Foo { x: Option[u8, u8] } }")[0].ty {
+		ErrorType::WrongArgumentCount("Option", 1, 2) => (),
+		ty => panic!("unexpected error {:?}", ty),
+	}
+}
+
+#[test]
+fn duplicate_names() {
+	let errors = errors("extern A\nextern A\nspec a { B { x: u8, x: u8 } }");
+	let names = errors
+		.iter()
+		.map(|e| match e.ty {
+			ErrorType::DuplicateName(name, _) => name,
+			ty => panic!("unexpected error {:?}", ty),
+		})
+		.collect::<Vec<_>>();
+
+	assert_eq!(names, vec!["A", "x"]);
+}
+
+#[test]
+fn render_points_at_error() {
+	let spec = "spec a {\n\tFoo {\n\t\tx: Bar\n\t}\n}";
+	let errors = errors(spec);
+
+	assert_eq!(
+		errors[0].render("test.prtcl", spec.as_bytes()),
+		"error: unknown type `Bar`\n \
+		 --> test.prtcl:3:6\n  \
+		 |\n\
+		 3 | \t\tx: Bar\n  \
+		 | \t\t   ^^^\n"
+	);
+}
+
+#[test]
+fn rust_output() {
+	let out = generate(SerdeBuilder::new(), SPEC);
+
+	assert!(out.contains("pub enum Kind {"));
+	assert!(out.contains("Kind::A => 1,"));
+	assert!(out.contains("pub mod server {"));
+	assert!(out.contains("pub struct Hello {"));
+	assert!(out.contains("pub struct Item {"));
+
+	// Empty packets are unit variants
+	// of the packet enum instead.
+	assert!(!out.contains("struct Empty"));
+}
+
+#[test]
+fn rust_output_uses_maps() {
+	let builder = SerdeBuilder::new()
+		.map_name(|n| if n == "kind" { "ty".to_owned() } else { n.to_owned() })
+		.type_ser(|t| format!("ser_{}", t.to_str().replace("<", "_").replace(">", "")))
+		.def_attr("derive(Default)");
+	let out = generate(builder, SPEC);
+
+	assert!(out.contains("pub ty: Kind,"));
+	assert!(out.contains("ser_Array_Item(&self.items, ser)?;"));
+	assert!(out.contains("#[derive(Default)]"));
+}
+
+#[test]
+fn builders_report_invalid_specs() {
+	let spec = b"spec a { Foo = 1 {}\nBar = 1 {} }";
+	let mut out = vec![];
+
+	let err = SerdeBuilder::new().build(spec, &mut out).unwrap_err();
+	assert!(err.to_string().contains("packet code 1 is already used by `Foo`"));
+
+	assert!(TypeScriptBuilder::new().build(spec, &mut out).is_err());
+	assert!(JsonSchemaBuilder::new().build(spec, &mut out).is_err());
+}
+
+#[test]
+fn typescript_output() {
+	let spec = "extern Text\nenum Kind : u8 { A = 1 }\n\
+	            spec server { Hello = 3 { kind: Kind, text: Text } }";
+	let mut out = vec![];
+	TypeScriptBuilder::new().build(spec.as_bytes(), &mut out).unwrap();
+	let out = String::from_utf8(out).unwrap();
+
+	assert!(out.contains("export enum Kind {"));
+	assert!(out.contains("export namespace server {"));
+	assert!(out.contains("kind: readKind(r),"));
+	assert!(out.contains("text: r.text(),"));
+	assert!(out.contains("case 3: p = { c: 3, ...readHello(r) }; break;"));
+}
+
+#[test]
+fn typescript_rejects_unmapped_extern() {
+	let mut out = vec![];
+	let res = TypeScriptBuilder::new().build(SPEC.as_bytes(), &mut out);
+
+	assert!(res.unwrap_err().to_string().contains("`Counter`"));
+}
+
+#[test]
+fn json_schema_output() {
+	let spec = "enum Kind : u8 { A = 1 }\nspec server { Hello = 3 { kind: Kind } }";
+	let mut out = vec![];
+	JsonSchemaBuilder::new().build(spec.as_bytes(), &mut out).unwrap();
+	let out = String::from_utf8(out).unwrap();
+
+	assert!(out.contains("\"server.Hello\": {"));
+	assert!(out.contains("\"$ref\": \"#/definitions/Kind\""));
+	assert!(out.contains("\"x-code\": 3"));
+}
+
+fn markdown(spec: &str) -> String {
+	let mut out = vec![];
+	MarkdownBuilder::new().build(spec.as_bytes(), &mut out).unwrap();
+	String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn markdown_layout() {
+	let out = markdown(SPEC);
+
+	assert!(out.contains("### Hello (3)"));
+	assert!(out.contains("| 0 | *code* | `u8` | 1 | Always `3` |"));
+	assert!(out.contains("| 1 | `name` | `u8` | 1 | Who to greet |"));
+	assert!(out.contains("| 2 | `kind` | [Kind](#type-Kind) | 1 |  |"));
+	assert!(out.contains("| 3 | `items` | Array\\[[Item](#server-Item)\\] | 2 + varies |  |"));
+	assert!(out.contains("**Size:** at least 5 bytes"));
+	assert!(out.contains("| 1 | `A` | The first kind |"));
+	assert!(out.contains("| 4 | [Empty](#server-Empty) |"));
+}
+
+#[test]
+fn markdown_fixed_size() {
+	let out = markdown("spec a { Foo = 1 { x: u16, y: Option[Entity] } }\nextern Entity");
+
+	assert!(out.contains("| 3 | `y` | Option\\[[Entity](#type-Entity)\\] | 2 |  |"));
+	assert!(out.contains("**Size:** 5 bytes"));
+}
+
+#[test]
+fn markdown_docs() {
+	let out = markdown(
+		"# Some docs\n\
+		 # # Example\n\
+		 # ```\n\
+		 # # hidden();\n\
+		 # shown();\n\
+		 # ```\n\
+		 extern A"
+	);
+
+	assert!(out.contains("Some docs\n#### Example\n```rust,ignore\nshown();\n```\n"));
+}
@@ -0,0 +1,609 @@
+use parser::*;
+use validate::load;
+use std::io::{Write, Error, ErrorKind};
+
+/// Reader and writer for every built-in field type.
+/// The scaling here has to match `protocol/src/field.rs`.
+const RUNTIME: &'static str = r#"
+export class DecodeError extends Error {}
+export class EncodeError extends Error {}
+
+export interface Vector2 {
+	x: number;
+	y: number;
+}
+
+export interface Upgrades {
+	speed: number;
+	shield: boolean;
+	inferno: boolean;
+}
+
+export interface ServerKeyState {
+	up: boolean;
+	down: boolean;
+	left: boolean;
+	right: boolean;
+	boost: boolean;
+	strafe: boolean;
+	stealth: boolean;
+	flagspeed: boolean;
+}
+
+const utf8Decoder = new TextDecoder("utf-8", { fatal: true });
+const utf8Encoder = new TextEncoder();
+
+export class Reader {
+	private view: DataView;
+	private offset = 0;
+
+	constructor(buf: ArrayBuffer | Uint8Array) {
+		this.view = buf instanceof Uint8Array
+			? new DataView(buf.buffer, buf.byteOffset, buf.byteLength)
+			: new DataView(buf);
+	}
+
+	private take(len: number): number {
+		if (this.offset + len > this.view.byteLength) {
+			throw new DecodeError("unexpected end of packet");
+		}
+
+		const at = this.offset;
+		this.offset += len;
+		return at;
+	}
+
+	end(): void {
+		if (this.offset != this.view.byteLength) {
+			throw new DecodeError("trailing bytes after packet");
+		}
+	}
+
+	bool(): boolean { return this.u8() != 0; }
+	u8(): number { return this.view.getUint8(this.take(1)); }
+	u16(): number { return this.view.getUint16(this.take(2), true); }
+	u32(): number { return this.view.getUint32(this.take(4), true); }
+	i8(): number { return this.view.getInt8(this.take(1)); }
+	i16(): number { return this.view.getInt16(this.take(2), true); }
+	i32(): number { return this.view.getInt32(this.take(4), true); }
+	f32(): number { return this.view.getFloat32(this.take(4), true); }
+	f64(): number { return this.view.getFloat64(this.take(8), true); }
+
+	private str(len: number): string {
+		const at = this.take(len);
+		const bytes = new Uint8Array(this.view.buffer, this.view.byteOffset + at, len);
+
+		try {
+			return utf8Decoder.decode(bytes);
+		} catch (e) {
+			throw new DecodeError("invalid utf-8 in string");
+		}
+	}
+
+	text(): string { return this.str(this.u8()); }
+	textBig(): string { return this.str(this.u16()); }
+
+	array<T>(item: () => T): T[] {
+		const len = this.u16();
+		const result: T[] = [];
+		for (let i = 0; i < len; ++i) {
+			result.push(item());
+		}
+		return result;
+	}
+	arraySmall<T>(item: () => T): T[] {
+		const len = this.u8();
+		const result: T[] = [];
+		for (let i = 0; i < len; ++i) {
+			result.push(item());
+		}
+		return result;
+	}
+
+	optionEntity(): number | null {
+		const id = this.u16();
+		return id == 0 ? null : id;
+	}
+
+	private shifted(mult: number, shift: number): number {
+		return (this.u16() - shift) / mult;
+	}
+	private coord24(): number {
+		const hi = this.u16();
+		const lo = this.u8();
+		return (hi * 256 + lo - 8388608) / 512;
+	}
+
+	rotation(): number { return this.u16() / 6553.6; }
+	speed(): number { return this.shifted(1638.4, 32768); }
+	regen(): number { return this.shifted(1.0e6, 32768); }
+	healthEnergy(): number { return this.u8() / 255; }
+
+	velocity(): Vector2 {
+		const x = this.speed();
+		const y = this.speed();
+		return { x, y };
+	}
+	accel(): Vector2 {
+		const x = this.shifted(32768, 32768);
+		const y = this.shifted(32768, 32768);
+		return { x, y };
+	}
+	pos(): Vector2 {
+		const x = this.shifted(2, 32768);
+		const y = this.shifted(4, 32768);
+		return { x, y };
+	}
+	pos24(): Vector2 {
+		const x = this.coord24();
+		const y = this.coord24();
+		return { x, y };
+	}
+	posF32(): Vector2 {
+		const x = this.f32();
+		const y = this.f32();
+		return { x, y };
+	}
+	lowResPos(): Vector2 {
+		const x = (this.u8() - 128) * 128;
+		const y = (this.u8() - 128) * 64;
+		return { x, y };
+	}
+
+	upgrades(): Upgrades {
+		const v = this.u8();
+		return {
+			speed: v & 7,
+			shield: (v & 8) != 0,
+			inferno: (v & 16) != 0,
+		};
+	}
+	keyState(): ServerKeyState {
+		const v = this.u8();
+		return {
+			up: (v & 1) != 0,
+			down: (v & 2) != 0,
+			left: (v & 4) != 0,
+			right: (v & 8) != 0,
+			boost: (v & 16) != 0,
+			strafe: (v & 32) != 0,
+			stealth: (v & 64) != 0,
+			flagspeed: (v & 128) != 0,
+		};
+	}
+}
+
+export class Writer {
+	private buf = new Uint8Array(64);
+	private view = new DataView(this.buf.buffer);
+	private offset = 0;
+
+	private reserve(len: number): number {
+		if (this.offset + len > this.buf.length) {
+			let size = this.buf.length * 2;
+			while (size < this.offset + len) {
+				size *= 2;
+			}
+
+			const buf = new Uint8Array(size);
+			buf.set(this.buf);
+			this.buf = buf;
+			this.view = new DataView(buf.buffer);
+		}
+
+		const at = this.offset;
+		this.offset += len;
+		return at;
+	}
+
+	finish(): Uint8Array {
+		return this.buf.slice(0, this.offset);
+	}
+
+	bool(v: boolean): void { this.u8(v ? 1 : 0); }
+	u8(v: number): void { this.view.setUint8(this.reserve(1), v); }
+	u16(v: number): void { this.view.setUint16(this.reserve(2), v, true); }
+	u32(v: number): void { this.view.setUint32(this.reserve(4), v, true); }
+	i8(v: number): void { this.view.setInt8(this.reserve(1), v); }
+	i16(v: number): void { this.view.setInt16(this.reserve(2), v, true); }
+	i32(v: number): void { this.view.setInt32(this.reserve(4), v, true); }
+	f32(v: number): void { this.view.setFloat32(this.reserve(4), v, true); }
+	f64(v: number): void { this.view.setFloat64(this.reserve(8), v, true); }
+
+	private bytes(v: Uint8Array): void {
+		this.buf.set(v, this.reserve(v.length));
+	}
+
+	text(v: string): void {
+		const bytes = utf8Encoder.encode(v);
+		if (bytes.length > 0xFF) {
+			throw new EncodeError("text is longer than 255 bytes");
+		}
+		this.u8(bytes.length);
+		this.bytes(bytes);
+	}
+	textBig(v: string): void {
+		const bytes = utf8Encoder.encode(v);
+		if (bytes.length > 0xFFFF) {
+			throw new EncodeError("text is longer than 65535 bytes");
+		}
+		this.u16(bytes.length);
+		this.bytes(bytes);
+	}
+
+	array<T>(v: T[], item: (x: T) => void): void {
+		if (v.length > 0xFFFF) {
+			throw new EncodeError("array has more than 65535 elements");
+		}
+		this.u16(v.length);
+		v.forEach(item);
+	}
+	arraySmall<T>(v: T[], item: (x: T) => void): void {
+		if (v.length > 0xFF) {
+			throw new EncodeError("array has more than 255 elements");
+		}
+		this.u8(v.length);
+		v.forEach(item);
+	}
+
+	optionEntity(v: number | null): void {
+		this.u16(v === null ? 0 : v);
+	}
+
+	private shifted(v: number, mult: number, shift: number): void {
+		this.u16(Math.trunc(v * mult) + shift);
+	}
+	private coord24(v: number): void {
+		const u = (Math.trunc(v * 512) + 8388608) >>> 0;
+		this.u16((u >>> 8) & 0xFFFF);
+		this.u8(u & 0xFF);
+	}
+
+	rotation(v: number): void { this.u16(Math.trunc(v * 6553.6)); }
+	speed(v: number): void { this.shifted(v, 1638.4, 32768); }
+	regen(v: number): void { this.shifted(v, 1.0e6, 32768); }
+	healthEnergy(v: number): void {
+		this.u8(Math.trunc(Math.min(1, Math.max(0, v)) * 255));
+	}
+
+	velocity(v: Vector2): void {
+		this.speed(v.x);
+		this.speed(v.y);
+	}
+	accel(v: Vector2): void {
+		this.shifted(v.x, 32768, 32768);
+		this.shifted(v.y, 32768, 32768);
+	}
+	pos(v: Vector2): void {
+		this.shifted(v.x, 2, 32768);
+		this.shifted(v.y, 4, 32768);
+	}
+	pos24(v: Vector2): void {
+		this.coord24(v.x);
+		this.coord24(v.y);
+	}
+	posF32(v: Vector2): void {
+		this.f32(v.x);
+		this.f32(v.y);
+	}
+	lowResPos(v: Vector2): void {
+		this.u8(Math.trunc(v.x / 128) + 128);
+		this.u8(Math.trunc(v.y / 64) + 128);
+	}
+
+	upgrades(v: Upgrades): void {
+		if (v.speed > 7) {
+			throw new EncodeError("upgrade speed must be less than 8");
+		}
+		this.u8(v.speed | (v.shield ? 8 : 0) | (v.inferno ? 16 : 0));
+	}
+	keyState(v: ServerKeyState): void {
+		this.u8(
+			(v.up ? 1 : 0)
+			| (v.down ? 2 : 0)
+			| (v.left ? 4 : 0)
+			| (v.right ? 8 : 0)
+			| (v.boost ? 16 : 0)
+			| (v.strafe ? 32 : 0)
+			| (v.stealth ? 64 : 0)
+			| (v.flagspeed ? 128 : 0)
+		);
+	}
+}
+"#;
+
+/// Generates TypeScript interfaces for every
+/// definition in a spec, along with functions
+/// to encode and decode them.
+///
+/// Each spec becomes a namespace with a `Packet`
+/// union (tagged by the packet code `c`) and
+/// `encode`/`decode` functions for it.
+pub struct TypeScriptGenerator;
+
+// Built in types that map directly
+// to a method on Reader and Writer.
+fn builtin(name: &str) -> Option<(&'static str, &'static str)> {
+	Some(match name {
+		"bool" => ("boolean", "bool"),
+		"u8" => ("number", "u8"),
+		"u16" => ("number", "u16"),
+		"u32" => ("number", "u32"),
+		"i8" => ("number", "i8"),
+		"i16" => ("number", "i16"),
+		"i32" => ("number", "i32"),
+		"f32" => ("number", "f32"),
+		"f64" => ("number", "f64"),
+		"Text" => ("string", "text"),
+		"TextBig" => ("string", "textBig"),
+		"Entity" => ("number", "u16"),
+		"Team" => ("number", "u16"),
+		"Level" => ("number", "u8"),
+		"Score" => ("number", "u32"),
+		"Flag" => ("number", "u8"),
+		"Rotation" => ("number", "rotation"),
+		"Speed" => ("number", "speed"),
+		"Health" => ("number", "healthEnergy"),
+		"Energy" => ("number", "healthEnergy"),
+		"HealthRegen" => ("number", "regen"),
+		"EnergyRegen" => ("number", "regen"),
+		"Position" => ("Vector2", "pos"),
+		"Position24" => ("Vector2", "pos24"),
+		"Position_f32" => ("Vector2", "posF32"),
+		"LowResPos" => ("Vector2", "lowResPos"),
+		"Velocity" => ("Vector2", "velocity"),
+		"Accel" => ("Vector2", "accel"),
+		"Upgrades" => ("Upgrades", "upgrades"),
+		"ServerKeyState" => ("ServerKeyState", "keyState"),
+		_ => return None
+	})
+}
+
+fn unknown_type(ty: &SpecFieldType) -> Error {
+	Error::new(
+		ErrorKind::InvalidData,
+		format!("type `{}` has no TypeScript equivalent", ty.to_str())
+	)
+}
+
+struct Names<'a> {
+	enums: Vec<&'a str>,
+	defs: Vec<&'a str>
+}
+
+impl<'a> Names<'a> {
+	fn ts_type(&self, ty: &SpecFieldType) -> Result<String, Error> {
+		match ty {
+			SpecFieldType::Simple(name) => {
+				if let Some((ts, _)) = builtin(name) {
+					Ok(ts.to_owned())
+				}
+				else if self.enums.contains(name) || self.defs.contains(name) {
+					Ok(name.to_string())
+				}
+				else {
+					Err(unknown_type(ty))
+				}
+			},
+			SpecFieldType::Compound(name, args) => match (*name, args.as_slice()) {
+				("Array", [inner]) | ("ArraySmall", [inner]) => {
+					Ok(format!("{}[]", self.ts_type(inner)?))
+				},
+				("Option", [inner]) => Ok(format!("{} | null", self.ts_type(inner)?)),
+				_ => Err(unknown_type(ty))
+			}
+		}
+	}
+
+	fn read(&self, ty: &SpecFieldType) -> Result<String, Error> {
+		match ty {
+			SpecFieldType::Simple(name) => {
+				if let Some((_, method)) = builtin(name) {
+					Ok(format!("r.{}()", method))
+				}
+				else if self.enums.contains(name) || self.defs.contains(name) {
+					Ok(format!("read{}(r)", name))
+				}
+				else {
+					Err(unknown_type(ty))
+				}
+			},
+			SpecFieldType::Compound(name, args) => match (*name, args.as_slice()) {
+				("Array", [inner]) => Ok(format!("r.array(() => {})", self.read(inner)?)),
+				("ArraySmall", [inner]) => {
+					Ok(format!("r.arraySmall(() => {})", self.read(inner)?))
+				},
+				("Option", [SpecFieldType::Simple("Entity")]) => Ok("r.optionEntity()".to_owned()),
+				_ => Err(unknown_type(ty))
+			}
+		}
+	}
+
+	fn write(&self, ty: &SpecFieldType, val: &str) -> Result<String, Error> {
+		match ty {
+			SpecFieldType::Simple(name) => {
+				if let Some((_, method)) = builtin(name) {
+					Ok(format!("w.{}({})", method, val))
+				}
+				else if self.enums.contains(name) || self.defs.contains(name) {
+					Ok(format!("write{}(w, {})", name, val))
+				}
+				else {
+					Err(unknown_type(ty))
+				}
+			},
+			SpecFieldType::Compound(name, args) => match (*name, args.as_slice()) {
+				("Array", [inner]) => {
+					Ok(format!("w.array({}, (x) => {})", val, self.write(inner, "x")?))
+				},
+				("ArraySmall", [inner]) => {
+					Ok(format!("w.arraySmall({}, (x) => {})", val, self.write(inner, "x")?))
+				},
+				("Option", [SpecFieldType::Simple("Entity")]) => {
+					Ok(format!("w.optionEntity({})", val))
+				},
+				_ => Err(unknown_type(ty))
+			}
+		}
+	}
+}
+
+impl TypeScriptGenerator {
+	pub fn new() -> Self {
+		TypeScriptGenerator
+	}
+
+	fn print_docs<'a, W>(writer: &mut W, docs: &[&'a str], indent: &str) -> Result<(), Error>
+	where W: Write
+	{
+		if docs.is_empty() {
+			return Ok(());
+		}
+
+		writeln!(writer, "{}/**", indent)?;
+		for doc in docs {
+			writeln!(writer, "{} *{}", indent, doc.replace("*/", "*\\/"))?;
+		}
+		writeln!(writer, "{} */", indent)?;
+
+		Ok(())
+	}
+
+	pub fn build<W>(self, specdata: &[u8], writer: &mut W) -> Result<(), Error>
+	where W: Write
+	{
+		let Protocol{ specs, types } = load(specdata)?;
+
+		writeln!(writer, "// Generated by specgen, do not edit.")?;
+		writeln!(writer, "{}", RUNTIME)?;
+
+		for ty in types.iter() {
+			let (branches, base_ty) = match ty.class {
+				TypeClass::Enum(ref branches, ref base_ty) => (branches, base_ty),
+				TypeClass::Extern => continue
+			};
+			let base = match builtin(&base_ty.to_str()) {
+				Some(("number", method)) => method,
+				_ => return Err(unknown_type(base_ty))
+			};
+
+			Self::print_docs(writer, &ty.docs, "")?;
+			writeln!(writer, "export enum {} {{", ty.name)?;
+			for branch in branches.iter() {
+				Self::print_docs(writer, &branch.docs, "\t")?;
+				writeln!(writer, "\t{} = {},", branch.name, branch.value)?;
+			}
+			writeln!(writer, "}}\n")?;
+
+			writeln!(writer, "export function read{name}(r: Reader): {name} {{", name=ty.name)?;
+			writeln!(writer, "\tconst v = r.{}();", base)?;
+			writeln!(writer, "\tif ((<any>{})[v] === undefined) {{", ty.name)?;
+			writeln!(writer, "\t\tthrow new DecodeError(\"invalid {} \" + v);", ty.name)?;
+			writeln!(writer, "\t}}")?;
+			writeln!(writer, "\treturn v;")?;
+			writeln!(writer, "}}")?;
+
+			writeln!(writer,
+				"export function write{name}(w: Writer, v: {name}): void {{",
+				name=ty.name
+			)?;
+			writeln!(writer, "\tw.{}(v);", base)?;
+			writeln!(writer, "}}\n")?;
+		}
+
+		let enums = types.iter()
+			.filter(|t| match t.class { TypeClass::Enum(..) => true, _ => false })
+			.map(|t| t.name)
+			.collect::<Vec<_>>();
+
+		for spec in specs.iter() {
+			let names = Names {
+				enums: enums.clone(),
+				defs: spec.defs.iter().map(|d| d.name).collect()
+			};
+
+			Self::print_docs(writer, &spec.docs, "")?;
+			writeln!(writer, "export namespace {} {{", spec.name)?;
+
+			for def in spec.defs.iter() {
+				Self::print_docs(writer, &def.docs, "\t")?;
+				writeln!(writer, "\texport interface {} {{", def.name)?;
+				for field in def.fields.iter() {
+					Self::print_docs(writer, &field.docs, "\t\t")?;
+					writeln!(writer, "\t\t{}: {};", field.name, names.ts_type(&field.ty)?)?;
+				}
+				writeln!(writer, "\t}}\n")?;
+
+				writeln!(writer,
+					"\texport function read{name}(r: Reader): {name} {{",
+					name=def.name
+				)?;
+				writeln!(writer, "\t\treturn {{")?;
+				for field in def.fields.iter() {
+					writeln!(writer, "\t\t\t{}: {},", field.name, names.read(&field.ty)?)?;
+				}
+				writeln!(writer, "\t\t}};")?;
+				writeln!(writer, "\t}}")?;
+
+				writeln!(writer,
+					"\texport function write{name}(w: Writer, v: {name}): void {{",
+					name=def.name
+				)?;
+				for field in def.fields.iter() {
+					let val = format!("v.{}", field.name);
+					writeln!(writer, "\t\t{};", names.write(&field.ty, &val)?)?;
+				}
+				writeln!(writer, "\t}}\n")?;
+			}
+
+			let packets = spec.defs.iter()
+				.filter_map(|d| d.code.map(|c| (d.name, c)))
+				.collect::<Vec<_>>();
+
+			writeln!(writer, "\texport const codes = {{")?;
+			for &(name, code) in packets.iter() {
+				writeln!(writer, "\t\t{}: {},", name, code)?;
+			}
+			writeln!(writer, "\t}};\n")?;
+
+			writeln!(writer, "\texport type Packet =")?;
+			for &(name, code) in packets.iter() {
+				writeln!(writer, "\t\t| ({{ c: {} }} & {})", code, name)?;
+			}
+			writeln!(writer, "\t\t;\n")?;
+
+			writeln!(writer, "\texport function decode(buf: ArrayBuffer | Uint8Array): Packet {{")?;
+			writeln!(writer, "\t\tconst r = new Reader(buf);")?;
+			writeln!(writer, "\t\tconst c = r.u8();")?;
+			writeln!(writer, "\t\tlet p: Packet;")?;
+			writeln!(writer, "\t\tswitch (c) {{")?;
+			for &(name, code) in packets.iter() {
+				writeln!(writer,
+					"\t\t\tcase {code}: p = {{ c: {code}, ...read{name}(r) }}; break;",
+					code=code,
+					name=name
+				)?;
+			}
+			writeln!(writer, "\t\t\tdefault: throw new DecodeError(\"unknown packet code \" + c);")?;
+			writeln!(writer, "\t\t}}")?;
+			writeln!(writer, "\t\tr.end();")?;
+			writeln!(writer, "\t\treturn p;")?;
+			writeln!(writer, "\t}}\n")?;
+
+			writeln!(writer, "\texport function encode(p: Packet): Uint8Array {{")?;
+			writeln!(writer, "\t\tconst w = new Writer();")?;
+			writeln!(writer, "\t\tw.u8(p.c);")?;
+			writeln!(writer, "\t\tswitch (p.c) {{")?;
+			for &(name, code) in packets.iter() {
+				writeln!(writer, "\t\t\tcase {}: write{}(w, p); break;", code, name)?;
+			}
+			writeln!(writer, "\t\t}}")?;
+			writeln!(writer, "\t\treturn w.finish();")?;
+			writeln!(writer, "\t}}")?;
+
+			writeln!(writer, "}}\n")?;
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,226 @@
+
+use parser::*;
+use std::collections::HashMap;
+use std::io;
+
+/// Types that every backend knows how to encode
+/// and that don't need to be declared.
+const PRIMITIVES: &'static [&'static str] = &[
+	"bool", "u8", "u16", "u32", "i8", "i16", "i32", "f32", "f64"
+];
+
+/// Compound types along with the number
+/// of type arguments they take.
+const COMPOUNDS: &'static [(&'static str, usize)] = &[
+	("Array", 1),
+	("ArraySmall", 1),
+	("Option", 1)
+];
+
+fn integer_max(ty: &str) -> Option<u32> {
+	Some(match ty {
+		"u8" => 0xFF,
+		"u16" => 0xFFFF,
+		"u32" => 0xFFFFFFFF,
+		"i8" => 0x7F,
+		"i16" => 0x7FFF,
+		"i32" => 0x7FFFFFFF,
+		_ => return None
+	})
+}
+
+struct Checker<'a, 'b> {
+	errors: Vec<Error<'a>>,
+	types: &'b HashMap<&'a str, Span>,
+}
+
+impl<'a, 'b> Checker<'a, 'b> {
+	fn error(&mut self, ty: ErrorType<'a>, span: Span) {
+		self.errors.push(Error::new(ty, span));
+	}
+
+	/// Records `name` in `seen`, reporting an
+	/// error if it was already there.
+	fn unique(&mut self, seen: &mut HashMap<&'a str, Span>, name: &'a str, span: Span) {
+		if let Some(&first) = seen.get(name) {
+			self.error(ErrorType::DuplicateName(name, first), span);
+		}
+		else {
+			seen.insert(name, span);
+		}
+	}
+
+	fn check_type(&mut self, ty: &SpecFieldType<'a>, defs: &HashMap<&'a str, Span>, span: Span) {
+		match *ty {
+			SpecFieldType::Simple(name) => {
+				let known = PRIMITIVES.contains(&name)
+					|| self.types.contains_key(name)
+					|| defs.contains_key(name);
+
+				if !known {
+					self.error(ErrorType::UnknownType(name), span);
+				}
+			},
+			SpecFieldType::Compound(name, ref args) => {
+				match COMPOUNDS.iter().find(|&&(n, _)| n == name) {
+					Some(&(_, count)) if count != args.len() => {
+						self.error(ErrorType::WrongArgumentCount(name, count, args.len()), span);
+					},
+					Some(_) => (),
+					None => self.error(ErrorType::UnknownType(name), span)
+				}
+
+				for arg in args.iter() {
+					self.check_type(arg, defs, span);
+				}
+			}
+		}
+	}
+
+	fn check_enum(&mut self, ty: &Type<'a>, branches: &[EnumBranch<'a>], base: &SpecFieldType<'a>) {
+		let max = match *base {
+			SpecFieldType::Simple(name) => match integer_max(name) {
+				Some(max) => Some((name, max)),
+				None => {
+					self.error(ErrorType::InvalidEnumBase(name), ty.span);
+					None
+				}
+			},
+			SpecFieldType::Compound(name, _) => {
+				self.error(ErrorType::InvalidEnumBase(name), ty.span);
+				None
+			}
+		};
+
+		if branches.is_empty() {
+			self.error(ErrorType::EmptyEnum(ty.name), ty.span);
+		}
+
+		let mut names = HashMap::new();
+		let mut values: HashMap<u32, (&'a str, Span)> = HashMap::new();
+
+		for branch in branches.iter() {
+			self.unique(&mut names, branch.name, branch.span);
+
+			if let Some(&(first, span)) = values.get(&branch.value) {
+				self.error(
+					ErrorType::DuplicateDiscriminant(branch.value, first, span),
+					branch.span
+				);
+			}
+			else {
+				values.insert(branch.value, (branch.name, branch.span));
+			}
+
+			if let Some((base, max)) = max {
+				if branch.value > max {
+					self.error(ErrorType::DiscriminantOutOfRange(branch.value, base), branch.span);
+				}
+			}
+		}
+	}
+
+	fn check_spec(&mut self, spec: &Spec<'a>) {
+		let mut defs = HashMap::new();
+		let mut codes: HashMap<u32, (&'a str, Span)> = HashMap::new();
+
+		for def in spec.defs.iter() {
+			if let Some(&first) = self.types.get(def.name) {
+				self.error(ErrorType::DuplicateName(def.name, first), def.span);
+			}
+			else {
+				self.unique(&mut defs, def.name, def.span);
+			}
+
+			if let Some(code) = def.code {
+				if code > 0xFF {
+					self.error(ErrorType::PacketCodeOutOfRange(code), def.span);
+				}
+
+				if let Some(&(first, span)) = codes.get(&code) {
+					self.error(ErrorType::DuplicatePacketCode(code, first, span), def.span);
+				}
+				else {
+					codes.insert(code, (def.name, def.span));
+				}
+			}
+		}
+
+		for def in spec.defs.iter() {
+			let mut fields = HashMap::new();
+
+			for field in def.fields.iter() {
+				self.unique(&mut fields, field.name, field.span);
+				self.check_type(&field.ty, &defs, field.ty_span);
+			}
+		}
+	}
+}
+
+/// Checks a parsed protocol for problems that the
+/// parser can't catch, such as unknown types or
+/// two packets sharing the same code.
+///
+/// All problems are returned, in the order that
+/// they appear within the spec. Some of them are
+/// only warnings, see
+/// [`Error::is_warning`](struct.Error.html#method.is_warning).
+pub fn validate<'a>(protocol: &Protocol<'a>) -> Vec<Error<'a>> {
+	let mut types = HashMap::new();
+	let mut errors = vec![];
+
+	for ty in protocol.types.iter() {
+		if PRIMITIVES.contains(&ty.name) {
+			errors.push(Error::new(ErrorType::DuplicateName(ty.name, Span::default()), ty.span));
+		}
+		else if let Some(&first) = types.get(ty.name) {
+			errors.push(Error::new(ErrorType::DuplicateName(ty.name, first), ty.span));
+		}
+		else {
+			types.insert(ty.name, ty.span);
+		}
+	}
+
+	let mut checker = Checker { errors, types: &types };
+
+	for ty in protocol.types.iter() {
+		if let TypeClass::Enum(ref branches, ref base) = ty.class {
+			checker.check_enum(ty, branches, base);
+		}
+	}
+
+	let mut specs = HashMap::new();
+	for spec in protocol.specs.iter() {
+		checker.unique(&mut specs, spec.name, spec.span);
+		checker.check_spec(spec);
+	}
+
+	let mut errors = checker.errors;
+	errors.sort_by_key(|e| (e.span.line, e.span.col));
+	errors
+}
+
+/// Parses and validates a spec, formatting every
+/// error into a single `io::Error` for backends.
+/// Warnings are ignored.
+pub(crate) fn load<'a>(specdata: &'a [u8]) -> Result<Protocol<'a>, io::Error> {
+	let protocol = match parse(specdata) {
+		Ok(protocol) => protocol,
+		Err(e) => return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			e.render("<spec>", specdata)
+		))
+	};
+
+	let errors = validate(&protocol)
+		.into_iter()
+		.filter(|e| !e.is_warning())
+		.map(|e| e.render("<spec>", specdata))
+		.collect::<Vec<_>>();
+
+	if !errors.is_empty() {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, errors.join("\n")));
+	}
+
+	Ok(protocol)
+}